@@ -1,10 +1,21 @@
 use std::io;
 
 use anyhow::{bail, Result};
-use ds_decomp::analysis::functions::Function;
+use ds_decomp::{
+    analysis::{
+        fixed_point::{format_fx, looks_like_fx32},
+        functions::Function,
+        memory_regions::FixedAddress,
+        secure_area::SwiFunction,
+    },
+    config::symbol::FxKind,
+};
 use unarm::{ArmVersion, DisplayOptions, Endian, ParseFlags, ParseMode, Parser, RegNames};
 
-use crate::config::symbol::{SymDataExt, SymbolLookup};
+use crate::{
+    config::symbol::{SymDataExt, SymbolLookup},
+    util::asm::AsmTarget,
+};
 
 pub trait FunctionExt {
     fn write_assembly<W: io::Write>(
@@ -15,6 +26,16 @@ pub trait FunctionExt {
         base_address: u32,
         ual: bool,
     ) -> Result<()>;
+
+    /// Names the address outside of the modules which is loaded from the pool constant at `pool_address`, such as an I/O
+    /// register or VRAM. Absolute symbols in symbols.txt take precedence over the built-in names.
+    fn fixed_address_load(
+        &self,
+        pool_address: u32,
+        module_code: &[u8],
+        base_address: u32,
+        symbols: &SymbolLookup,
+    ) -> Option<String>;
 }
 
 impl FunctionExt for Function {
@@ -40,17 +61,22 @@ impl FunctionExt for Function {
         }
 
         let mut jump_table = None;
+        // Inline assembly is wrapped in a function by the caller
+        let declare = symbols.asm_target != AsmTarget::MwccInline;
 
         while let Some((address, ins, parsed_ins)) = parser.next() {
-            if address == self.first_instruction_address() {
-                // declare self
-                writeln!(w, "    .global {}", self.name())?;
+            if declare && address == self.first_instruction_address() {
+                // declare self, unless it's local to the file
+                let local = symbols.symbol_map.get_function(address)?.is_some_and(|(_, symbol)| symbol.local);
+                if !local {
+                    writeln!(w, "    .global {}", self.name())?;
+                }
                 if self.is_thumb() {
                     writeln!(w, "    thumb_func_start {}", self.name())?;
                 } else {
                     writeln!(w, "    arm_func_start {}", self.name())?;
                 }
-                writeln!(w, "{}: ; {:#010x}", self.name(), self.first_instruction_address())?;
+                writeln!(w, "{}: {} {:#010x}", self.name(), symbols.asm_target.comment(), self.first_instruction_address())?;
             }
 
             let ins_size = parser.mode.instruction_size(0) as u32;
@@ -61,7 +87,7 @@ impl FunctionExt for Function {
             }
             if let Some((table, sym)) = symbols.symbol_map.get_jump_table(address)? {
                 jump_table = Some((table, sym));
-                writeln!(w, "{}: ; jump table", sym.name)?;
+                writeln!(w, "{}: {} jump table", sym.name, symbols.asm_target.comment())?;
             }
 
             // write data
@@ -72,7 +98,7 @@ impl FunctionExt for Function {
                 };
                 parser.seek_forward(address + size);
 
-                writeln!(w, "{}: ; inline table", sym.name)?;
+                writeln!(w, "{}: {} inline table", sym.name, symbols.asm_target.comment())?;
 
                 let start = (sym.addr - base_address) as usize;
                 let end = start + size as usize;
@@ -113,6 +139,12 @@ impl FunctionExt for Function {
                     )?;
                     if let Some(reference) = parsed_ins.pc_relative_reference(address, pc_load_offset) {
                         symbols.write_ambiguous_symbols_comment(w, address, reference)?;
+                        if let Some(name) = self.fixed_address_load(reference, module_code, base_address, symbols) {
+                            write!(w, " {} {name}", symbols.asm_target.comment())?;
+                        }
+                    }
+                    if let Some(swi_function) = SwiFunction::from_instruction(&parsed_ins, self.is_thumb()) {
+                        write!(w, " {} BIOS {}", symbols.asm_target.comment(), swi_function.signature())?;
                     }
                 }
             }
@@ -120,7 +152,7 @@ impl FunctionExt for Function {
             // write jump table case
             if let Some((_table, sym)) = jump_table {
                 let case = (address - sym.addr) / ins_size;
-                writeln!(w, " ; case {case}")?;
+                writeln!(w, " {} case {case}", symbols.asm_target.comment())?;
             } else {
                 writeln!(w)?;
             }
@@ -141,7 +173,11 @@ impl FunctionExt for Function {
                     write!(w, "{}: ", pool_symbol.name)?;
 
                     if !symbols.write_symbol(w, pool_address, const_value, &mut false, "")? {
-                        writeln!(w, ".word {const_value:#x}")?;
+                        write!(w, ".word {const_value:#x}")?;
+                        if looks_like_fx32(const_value) {
+                            write!(w, " {} {}", symbols.asm_target.comment(), format_fx(FxKind::Fx32.to_f64(bytes)))?;
+                        }
+                        writeln!(w)?;
                     }
                 } else {
                     if pool_address > parser.address {
@@ -156,14 +192,35 @@ impl FunctionExt for Function {
             }
         }
 
-        if self.is_thumb() {
-            writeln!(w, "    thumb_func_end {}", self.name())?;
-        } else {
-            writeln!(w, "    arm_func_end {}", self.name())?;
+        if declare {
+            if self.is_thumb() {
+                writeln!(w, "    thumb_func_end {}", self.name())?;
+            } else {
+                writeln!(w, "    arm_func_end {}", self.name())?;
+            }
+            writeln!(w)?;
         }
 
-        writeln!(w)?;
-
         Ok(())
     }
+
+    fn fixed_address_load(
+        &self,
+        pool_address: u32,
+        module_code: &[u8],
+        base_address: u32,
+        symbols: &SymbolLookup,
+    ) -> Option<String> {
+        // Pointers into modules are relocated instead
+        if !self.pool_constants().contains(&pool_address) || symbols.relocations.get(pool_address).is_some() {
+            return None;
+        }
+        let start = (pool_address - base_address) as usize;
+        let bytes = module_code.get(start..start + 4)?;
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        if let Some(symbol) = symbols.symbol_maps.absolute_symbol(value) {
+            return Some(symbol.name.clone());
+        }
+        FixedAddress::classify(value).map(|fixed_address| fixed_address.to_string())
+    }
 }
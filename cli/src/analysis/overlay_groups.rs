@@ -1,4 +1,5 @@
-use anyhow::{bail, Result};
+use std::{collections::BTreeMap, ops::Range};
+
 use ds_rom::rom::Overlay;
 
 pub struct OverlayGroups {
@@ -12,80 +13,235 @@ pub struct OverlayGroup {
     pub start_address: u32,
     pub end_address: u32,
     pub overlays: Vec<OverlayIndex>,
+    /// Overlays which this group is placed after, or the ARM9 program if empty and the group is not fixed.
     pub after: Vec<OverlayIndex>,
+    /// Whether the group is placed at `start_address` instead of after other modules.
+    pub fixed: bool,
 }
 
+/// Largest alignment which is recognized as padding between an overlay and the module before it.
+const MAX_ALIGNMENT: u32 = 0x20;
+
 impl OverlayGroups {
-    pub fn analyze(main_end_address: u32, overlays: &[Overlay]) -> Result<OverlayGroups> {
-        let (first_group, first_group_end, mut ungrouped_overlays) =
-            overlays.iter().fold((vec![], 0, vec![]), |(mut first_group, mut first_group_end, mut rest), overlay| {
-                if overlay.base_address() == main_end_address {
-                    first_group.push(overlay.id());
-                    first_group_end = first_group_end.max(overlay.end_address())
-                } else {
-                    rest.push(overlay.id());
-                }
-                (first_group, first_group_end, rest)
-            });
+    /// Groups the overlays by which modules they are loaded after. Overlays with an explicit origin in `origins` are placed
+    /// there. Overlays which don't start exactly at the end of ARM9 or another overlay, such as overlays in TCM, at fixed
+    /// addresses or aligned after another module, are placed at their original address.
+    pub fn analyze(main_end_address: u32, overlays: &[Overlay], origins: &BTreeMap<OverlayIndex, u32>) -> OverlayGroups {
+        let overlays = overlays.iter().map(|overlay| overlay.base_address()..overlay.end_address()).collect::<Vec<_>>();
+        Self::from_ranges(main_end_address, &overlays, origins)
+    }
 
-        let mut groups = vec![OverlayGroup {
-            index: 0,
-            start_address: main_end_address,
-            end_address: first_group_end,
-            overlays: first_group,
-            after: vec![],
-        }];
+    /// Groups overlays by their address ranges, indexed by overlay ID.
+    fn from_ranges(main_end_address: u32, overlays: &[Range<u32>], origins: &BTreeMap<OverlayIndex, u32>) -> OverlayGroups {
+        let mut groups = Self { groups: vec![] };
+        let mut ungrouped_overlays = (0..overlays.len() as OverlayIndex).collect::<Vec<_>>();
 
-        let mut new_group = vec![];
-        let mut groups_to_connect = vec![0u16]; // list of groups (indices) which may be preceded by ungrouped overlays
-        while !ungrouped_overlays.is_empty() {
-            let Some(connect_index) = groups_to_connect.pop() else {
-                bail!("No more overlay groups to connect to, are there gaps between overlays?");
-            };
-            let connect_index = connect_index as usize;
-
-            for i in 0..groups[connect_index].overlays.len() {
-                let grouped_overlay = &overlays[groups[connect_index].overlays[i] as usize];
-                let overlay_end = grouped_overlay.end_address();
-
-                let mut group_end = 0;
-                for j in (0..ungrouped_overlays.len()).rev() {
-                    let overlay = &overlays[ungrouped_overlays[j] as usize];
-                    if overlay.base_address() == grouped_overlay.end_address() {
-                        new_group.push(ungrouped_overlays.remove(j));
-                        group_end = group_end.max(overlay.end_address());
-                    }
-                }
-
-                if !new_group.is_empty() {
-                    let after = groups[connect_index]
-                        .overlays
-                        .iter()
-                        .cloned()
-                        .filter(|&id| overlays[id as usize].end_address() <= overlay_end)
-                        .collect();
-
-                    new_group.reverse();
-
-                    let index = groups.len() as u16;
-                    groups.push(OverlayGroup {
-                        index,
-                        start_address: overlay_end,
-                        end_address: group_end,
-                        overlays: new_group,
-                        after,
-                    });
-                    groups_to_connect.push(index);
-
-                    new_group = vec![];
-                }
+        // list of groups (indices) which may be preceded by ungrouped overlays
+        let mut groups_to_connect = vec![];
+        let mut explicit_groups = BTreeMap::<u32, Vec<OverlayIndex>>::new();
+        ungrouped_overlays.retain(|id| {
+            let Some(&origin) = origins.get(id) else { return true };
+            explicit_groups.entry(origin).or_default().push(*id);
+            false
+        });
+        for (origin, group_overlays) in explicit_groups {
+            groups_to_connect.push(groups.add_fixed_group(overlays, origin, group_overlays));
+        }
+
+        groups_to_connect.extend(groups.connect(overlays, &mut ungrouped_overlays, &[(None, main_end_address)]));
+        loop {
+            while let Some(connect_index) = groups_to_connect.pop() {
+                let preceding = groups.groups[connect_index as usize]
+                    .overlays
+                    .iter()
+                    .map(|&id| (Some(id), overlays[id as usize].end))
+                    .collect::<Vec<_>>();
+                groups_to_connect.extend(groups.connect(overlays, &mut ungrouped_overlays, &preceding));
             }
+
+            let Some(start_address) = ungrouped_overlays.iter().map(|&id| overlays[id as usize].start).min() else {
+                break;
+            };
+            let (fixed_overlays, rest): (Vec<_>, Vec<_>) =
+                ungrouped_overlays.into_iter().partition(|&id| overlays[id as usize].start == start_address);
+            ungrouped_overlays = rest;
+            log::debug!("Overlays {fixed_overlays:?} don't follow any other module, placing them at {start_address:#010x}");
+            groups_to_connect.push(groups.add_fixed_group(overlays, start_address, fixed_overlays));
         }
 
-        Ok(Self { groups })
+        groups
+    }
+
+    fn add_fixed_group(&mut self, overlays: &[Range<u32>], start_address: u32, group_overlays: Vec<OverlayIndex>) -> u16 {
+        let index = self.groups.len() as u16;
+        self.groups.push(OverlayGroup {
+            index,
+            start_address,
+            end_address: group_overlays.iter().map(|&id| overlays[id as usize].end).max().unwrap(),
+            overlays: group_overlays,
+            after: vec![],
+            fixed: true,
+        });
+        index
+    }
+
+    /// Moves the ungrouped overlays which start at or shortly after the end of a preceding module into new groups, one for
+    /// each start address. A preceding module is an overlay, or the ARM9 program if its ID is `None`. Returns the indices of
+    /// the new groups.
+    fn connect(
+        &mut self,
+        overlays: &[Range<u32>],
+        ungrouped_overlays: &mut Vec<OverlayIndex>,
+        preceding: &[(Option<OverlayIndex>, u32)],
+    ) -> Vec<u16> {
+        // Maps start address to the end address it follows, the alignment and the overlays starting there
+        let mut new_groups = BTreeMap::<u32, (u32, u32, Vec<OverlayIndex>)>::new();
+        ungrouped_overlays.retain(|&id| {
+            let start_address = overlays[id as usize].start;
+            let closest = preceding
+                .iter()
+                .filter_map(|&(_, end_address)| Some((end_address, alignment_between(end_address, start_address)?)))
+                .max_by_key(|&(end_address, _)| end_address);
+            let Some((end_address, alignment)) = closest else { return true };
+            new_groups.entry(start_address).or_insert_with(|| (end_address, alignment, vec![])).2.push(id);
+            false
+        });
+
+        let mut indices = vec![];
+        for (start_address, (end_address, alignment, group_overlays)) in new_groups {
+            // The linkers can't all align an origin which follows other modules, so aligned groups keep their address
+            if alignment > 1 {
+                log::debug!(
+                    "Overlays {group_overlays:?} are aligned to {alignment:#x} after {end_address:#010x}, placing them at \
+                    {start_address:#010x}"
+                );
+                indices.push(self.add_fixed_group(overlays, start_address, group_overlays));
+                continue;
+            }
+
+            let after = preceding.iter().filter(|&&(_, end)| end <= end_address).filter_map(|&(id, _)| id).collect();
+
+            let index = self.groups.len() as u16;
+            self.groups.push(OverlayGroup {
+                index,
+                start_address,
+                end_address: group_overlays.iter().map(|&id| overlays[id as usize].end).max().unwrap(),
+                overlays: group_overlays,
+                after,
+                fixed: false,
+            });
+            indices.push(index);
+        }
+        indices
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &OverlayGroup> {
         self.groups.iter()
     }
+
+    /// Returns the address which an overlay is placed at, if it's in a fixed group.
+    pub fn fixed_origin(&self, id: OverlayIndex) -> Option<u32> {
+        self.groups
+            .iter()
+            .find(|group| group.overlays.contains(&id))
+            .filter(|group| group.fixed)
+            .map(|group| group.start_address)
+    }
+}
+
+/// Returns the smallest alignment which places a module starting at `start_address` right after `end_address`.
+fn alignment_between(end_address: u32, start_address: u32) -> Option<u32> {
+    let mut alignment = 1;
+    while alignment <= MAX_ALIGNMENT {
+        if end_address.next_multiple_of(alignment) == start_address {
+            return Some(alignment);
+        }
+        alignment *= 2;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start address, end address, overlays, overlays it's placed after, and whether it's fixed
+    type ExpectedGroup<'a> = (u32, u32, &'a [OverlayIndex], &'a [OverlayIndex], bool);
+
+    struct Case<'a> {
+        name: &'a str,
+        main_end_address: u32,
+        overlays: &'a [Range<u32>],
+        origins: &'a [(OverlayIndex, u32)],
+        groups: &'a [ExpectedGroup<'a>],
+    }
+
+    const CASES: &[Case] = &[
+        Case {
+            name: "contiguous after ARM9 and other overlays",
+            main_end_address: 0x02100000,
+            overlays: &[0x02100000..0x02100100, 0x02100000..0x02100080, 0x02100100..0x02100200],
+            origins: &[],
+            groups: &[(0x02100000, 0x02100100, &[0, 1], &[], false), (0x02100100, 0x02100200, &[2], &[0, 1], false)],
+        },
+        Case {
+            name: "explicit origin",
+            main_end_address: 0x02100000,
+            overlays: &[0x02100000..0x02100100, 0x02200000..0x02200100, 0x02200100..0x02200180],
+            origins: &[(1, 0x02200000)],
+            groups: &[
+                (0x02200000, 0x02200100, &[1], &[], true),
+                (0x02100000, 0x02100100, &[0], &[], false),
+                (0x02200100, 0x02200180, &[2], &[1], false),
+            ],
+        },
+        Case {
+            name: "aligned after ARM9, and not aligned within MAX_ALIGNMENT",
+            main_end_address: 0x02100004,
+            overlays: &[0x02100020..0x02100100, 0x02100100..0x02100200, 0x02100008..0x02100010, 0x02100040..0x02100060],
+            origins: &[],
+            groups: &[
+                (0x02100008, 0x02100010, &[2], &[], true),
+                (0x02100020, 0x02100100, &[0], &[], true),
+                (0x02100100, 0x02100200, &[1], &[0], false),
+                (0x02100040, 0x02100060, &[3], &[], true),
+            ],
+        },
+        Case {
+            name: "starting before the end of ARM9",
+            main_end_address: 0x02100000,
+            overlays: &[0x01ff8000..0x01ff8100, 0x01ff8100..0x01ff8200, 0x02100000..0x02100100, 0x020ff000..0x020ff100],
+            origins: &[],
+            groups: &[
+                (0x02100000, 0x02100100, &[2], &[], false),
+                (0x01ff8000, 0x01ff8100, &[0], &[], true),
+                (0x01ff8100, 0x01ff8200, &[1], &[0], false),
+                (0x020ff000, 0x020ff100, &[3], &[], true),
+            ],
+        },
+    ];
+
+    #[test]
+    fn analyze() {
+        for case in CASES {
+            let origins = case.origins.iter().copied().collect();
+            let groups = OverlayGroups::from_ranges(case.main_end_address, case.overlays, &origins);
+            let groups = groups
+                .iter()
+                .map(|group| {
+                    (group.start_address, group.end_address, group.overlays.as_slice(), group.after.as_slice(), group.fixed)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(groups, case.groups, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn fixed_origin() {
+        let origins = [(1, 0x02200000)].into_iter().collect();
+        let groups = OverlayGroups::from_ranges(0x02100000, &[0x02100000..0x02100100, 0x02200000..0x02200100], &origins);
+        assert_eq!(groups.fixed_origin(0), None);
+        assert_eq!(groups.fixed_origin(1), Some(0x02200000));
+        assert_eq!(groups.fixed_origin(2), None);
+    }
 }
@@ -0,0 +1,258 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::{Config, ConfigModule},
+    delinks::{DelinkFile, Delinks},
+    module::ModuleKind,
+};
+use object::elf::{R_ARM_ABS32, R_ARM_CALL, R_ARM_JUMP24, R_ARM_PC24, R_ARM_THM_PC22, R_ARM_THM_XPC22, R_ARM_XPC25};
+
+use crate::{
+    config::delinks::DelinksExt,
+    linker::{
+        input::InputObject,
+        relocate::{relocate, RelocationTarget},
+    },
+    util::{bytes::FromSlice, path::PathExt},
+};
+
+/// Verifies that objects assembled from the output of `dsd dis` are equivalent to the delinked objects.
+#[derive(Args)]
+pub struct CheckObjects {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to the assembled objects, in the same directory structure as the assembly files from `dsd dis`.
+    #[arg(long, short = 'o')]
+    pub objects_path: PathBuf,
+
+    /// Return failing exit code if an object doesn't pass the checks.
+    #[arg(long, short = 'f')]
+    pub fail: bool,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum RelocationClass {
+    Absolute,
+    ArmBranch,
+    ThumbCall,
+    Other(u32),
+}
+
+#[derive(PartialEq, Eq, Clone)]
+enum RelocationDestination {
+    /// Offset into a section of the object itself
+    Section(String, i64),
+    /// Undefined symbol and addend
+    Symbol(String, i64),
+}
+
+/// Relocation with the differences between assemblers removed, such as relocation types, implicit addends and whether the
+/// target is a section or symbol.
+#[derive(PartialEq, Eq, Clone)]
+struct RelocationKey {
+    class: RelocationClass,
+    destination: RelocationDestination,
+}
+
+impl CheckObjects {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+        let delinks_path = config_dir.normalize_join(&config.delinks_path)?;
+
+        let mut num_files = 0;
+        let mut num_failed = 0;
+
+        let mut check_module = |module: &ConfigModule, module_kind: ModuleKind| -> Result<()> {
+            let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&module.delinks), module_kind)?;
+            for file in &delinks.files {
+                num_files += 1;
+                if !self.check_file(module, file, &delinks_path)? {
+                    num_failed += 1;
+                }
+            }
+            Ok(())
+        };
+
+        check_module(&config.main_module, ModuleKind::Arm9)?;
+        for autoload in &config.autoloads {
+            check_module(&autoload.module, ModuleKind::Autoload(autoload.kind))?;
+        }
+        for overlay in &config.overlays {
+            check_module(&overlay.module, ModuleKind::Overlay(overlay.id))?;
+        }
+
+        log::info!("{} out of {num_files} objects are equivalent to the delinked objects", num_files - num_failed);
+
+        if self.fail && num_failed > 0 {
+            bail!("Some object(s) didn't pass the checks.");
+        }
+
+        Ok(())
+    }
+
+    fn check_file(&self, module: &ConfigModule, file: &DelinkFile, delinks_path: &Path) -> Result<bool> {
+        let (file_path, _) = file.split_file_ext();
+        let assembled_path = self.objects_path.join(format!("{}/{file_path}.o", module.name));
+        if !assembled_path.exists() {
+            log::error!("{}: assembled object '{}' not found", file.name, assembled_path.display());
+            return Ok(false);
+        }
+
+        let delinked = InputObject::from_file(delinks_path.join(format!("{file_path}.o")))?;
+        let assembled = InputObject::from_file(&assembled_path)?;
+
+        let errors = compare_objects(file, &delinked, &assembled);
+        for error in &errors {
+            log::error!("{}: {error}", file.name);
+        }
+        Ok(errors.is_empty())
+    }
+}
+
+fn compare_objects(file: &DelinkFile, expected: &InputObject, actual: &InputObject) -> Vec<String> {
+    let mut errors = vec![];
+
+    for (expected_index, expected_section) in expected.sections.iter().enumerate() {
+        let name = &expected_section.name;
+        let Some(actual_index) = actual.sections.iter().position(|s| &s.name == name) else {
+            errors.push(format!("section {name} is missing"));
+            continue;
+        };
+        let actual_section = &actual.sections[actual_index];
+        if expected_section.size != actual_section.size {
+            errors.push(format!("section {name} has size {:#x}, expected {:#x}", actual_section.size, expected_section.size));
+            continue;
+        }
+
+        let base_address = file.sections.by_name(name).map(|(_, s)| s.start_address()).unwrap_or(0);
+        let (expected_data, expected_relocations) = normalize_section(expected, expected_index, base_address);
+        let (actual_data, actual_relocations) = normalize_section(actual, actual_index, base_address);
+
+        if let Some(offset) = expected_data.iter().zip(actual_data.iter()).position(|(a, b)| a != b) {
+            errors.push(format!("section {name} differs at offset {offset:#x}"));
+        }
+
+        for (offset, expected_relocation) in &expected_relocations {
+            match actual_relocations.get(offset) {
+                Some(actual_relocation) if actual_relocation == expected_relocation => {}
+                Some(actual_relocation) => errors.push(format!(
+                    "relocation at {offset:#x} in {name} is {actual_relocation}, expected {expected_relocation}"
+                )),
+                None => errors.push(format!("missing relocation {expected_relocation} at {offset:#x} in {name}")),
+            }
+        }
+        for (offset, actual_relocation) in &actual_relocations {
+            if !expected_relocations.contains_key(offset) {
+                errors.push(format!("unexpected relocation {actual_relocation} at {offset:#x} in {name}"));
+            }
+        }
+    }
+
+    for expected_symbol in &expected.symbols {
+        let Some(expected_section) = expected_symbol.section else { continue };
+        if !expected_symbol.global {
+            continue;
+        }
+        let expected_section = &expected.sections[expected_section].name;
+        let actual_symbol = actual.symbols.iter().find(|s| s.name == expected_symbol.name && s.section.is_some());
+        match actual_symbol {
+            Some(actual_symbol) => {
+                let actual_section = &actual.sections[actual_symbol.section.unwrap()].name;
+                if actual_section != expected_section || actual_symbol.value != expected_symbol.value {
+                    errors.push(format!(
+                        "symbol {} is at {actual_section}+{:#x}, expected {expected_section}+{:#x}",
+                        expected_symbol.name, actual_symbol.value, expected_symbol.value
+                    ));
+                } else if !actual_symbol.global {
+                    errors.push(format!("symbol {} is not global", expected_symbol.name));
+                }
+            }
+            None => errors.push(format!("symbol {} is missing", expected_symbol.name)),
+        }
+    }
+
+    errors
+}
+
+/// Returns the contents of a section with branches within the section resolved, since assemblers resolve those without
+/// emitting relocations. The remaining relocations are returned by offset, and their bytes are cleared in the contents.
+fn normalize_section(
+    object: &InputObject,
+    section_index: usize,
+    base_address: u32,
+) -> (Vec<u8>, BTreeMap<u32, RelocationKey>) {
+    let section = &object.sections[section_index];
+    let mut data = section.data.clone().unwrap_or_default();
+    let mut relocations = BTreeMap::new();
+
+    for relocation in &section.relocations {
+        let offset = relocation.offset;
+        let symbol = &object.symbols[relocation.symbol];
+        let class = RelocationClass::new(relocation.r_type);
+        let bytes = data.get_mut(offset as usize..offset as usize + 4);
+
+        if let Some(bytes) = bytes {
+            if class != RelocationClass::Absolute && symbol.section == Some(section_index) {
+                let target = RelocationTarget { address: base_address + symbol.value, thumb: symbol.thumb, function: true };
+                let word = u32::from_le_slice(bytes);
+                if let Ok(word) = relocate(relocation.r_type, word, base_address + offset, &target, relocation.addend) {
+                    bytes.copy_from_slice(&word.to_le_bytes());
+                    continue;
+                }
+            }
+            bytes.fill(0);
+        }
+
+        let destination = match symbol.section {
+            Some(index) => {
+                RelocationDestination::Section(object.sections[index].name.clone(), symbol.value as i64 + relocation.addend)
+            }
+            None => RelocationDestination::Symbol(symbol.name.clone(), relocation.addend),
+        };
+        relocations.insert(offset, RelocationKey { class, destination });
+    }
+
+    (data, relocations)
+}
+
+impl RelocationClass {
+    fn new(r_type: u32) -> Self {
+        match r_type {
+            R_ARM_ABS32 => Self::Absolute,
+            R_ARM_PC24 | R_ARM_XPC25 | R_ARM_CALL | R_ARM_JUMP24 => Self::ArmBranch,
+            R_ARM_THM_PC22 | R_ARM_THM_XPC22 => Self::ThumbCall,
+            _ => Self::Other(r_type),
+        }
+    }
+}
+
+impl Display for RelocationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.class {
+            RelocationClass::Absolute => write!(f, "absolute")?,
+            RelocationClass::ArmBranch => write!(f, "ARM branch")?,
+            RelocationClass::ThumbCall => write!(f, "Thumb call")?,
+            RelocationClass::Other(r_type) => write!(f, "type {r_type}")?,
+        }
+        let (name, addend) = match &self.destination {
+            RelocationDestination::Section(name, addend) => (name, addend),
+            RelocationDestination::Symbol(name, addend) => (name, addend),
+        };
+        write!(f, " to {name}")?;
+        if *addend > 0 {
+            write!(f, "+{addend:#x}")?;
+        } else if *addend < 0 {
+            write!(f, "-{:#x}", addend.abs())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,345 @@
+use std::{fmt::Write as _, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::{Config, ConfigModule},
+    delinks::Delinks,
+    module::ModuleKind,
+    relocations::Relocations,
+    section::{Section, SectionKind, Sections},
+    symbol::{InstructionMode, SymData, SymFunction, Symbol, SymbolKind, SymbolMaps},
+};
+use ds_rom::rom::{Rom, RomLoadOptions};
+
+use crate::{
+    config::{config::ConfigExt, delinks::DelinksExt, relocation::RelocationModuleExt, symbol::SymDataExt},
+    util::{
+        io::{create_dir_all, write_file},
+        parse::parse_hex_address,
+    },
+};
+
+/// Renders data symbols as C initializers.
+#[derive(Args)]
+pub struct DataInitializers {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Name of the data symbol to render.
+    #[arg(long, short = 's', required_unless_present = "file", conflicts_with = "file")]
+    pub symbol: Option<String>,
+
+    /// Name of a file in delinks.txt, to render all of its data symbols.
+    #[arg(long, short = 'f')]
+    pub file: Option<String>,
+
+    /// Name of the module to search in, such as `main`, `itcm` or `ov000`. Searches all modules by default.
+    #[arg(long, short = 'm')]
+    pub module: Option<String>,
+
+    /// Output path for the initializers, or the standard output if not given.
+    #[arg(long, short = 'o')]
+    pub output_path: Option<PathBuf>,
+}
+
+/// A single element of an initializer.
+enum Element {
+    Value(u64),
+    Pointer { name: String, function: bool, addend: i64 },
+}
+
+/// A module with its code and symbols loaded.
+struct LoadedModule<'a> {
+    config: &'a ConfigModule,
+    kind: ModuleKind,
+    delinks: Delinks,
+    relocations: Relocations,
+    code: &'a [u8],
+}
+
+impl DataInitializers {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let rom = Rom::load(
+            config_dir.join(&config.rom_config),
+            RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false },
+        )?;
+        let rom_autoloads = rom.arm9().autoloads()?;
+
+        let modules =
+            config.iter_modules().filter(|(module, _)| self.module.as_ref().map_or(true, |name| &module.name == name));
+
+        let mut output = String::new();
+        let mut found = false;
+        for (module, module_kind) in modules {
+            let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&module.delinks), module_kind)?;
+            let symbol_map = symbol_maps.get(module_kind).unwrap();
+
+            let mut symbols = vec![];
+            if let Some(name) = &self.symbol {
+                if let Some(symbols_by_name) = symbol_map.for_name(name) {
+                    symbols
+                        .extend(symbols_by_name.map(|(_, symbol)| symbol).filter(|s| matches!(s.kind, SymbolKind::Data(_))));
+                }
+            } else if let Some(file_name) = &self.file {
+                let Some(file) = delinks.files.iter().find(|f| &f.name == file_name) else { continue };
+                for section in file.sections.sorted_by_address() {
+                    if section.kind() == SectionKind::Data {
+                        symbols.extend(
+                            symbol_map
+                                .iter_by_address(section.address_range())
+                                .filter(|s| matches!(s.kind, SymbolKind::Data(_))),
+                        );
+                    }
+                }
+            }
+            if symbols.is_empty() {
+                continue;
+            }
+            if found && self.symbol.is_some() {
+                bail!("Symbol is defined in multiple modules, use --module to choose one");
+            }
+            found = true;
+
+            let code = match module_kind {
+                ModuleKind::Arm9 => rom.arm9().code()?,
+                ModuleKind::Autoload(kind) => rom_autoloads
+                    .iter()
+                    .find(|a| a.kind() == kind)
+                    .with_context(|| format!("Autoload {kind} not present in ROM"))?
+                    .code(),
+                ModuleKind::Overlay(id) => {
+                    rom.arm9_overlays().get(id as usize).with_context(|| format!("Overlay {id} not present in ROM"))?.code()
+                }
+            };
+            let relocations = Relocations::from_file(config_dir.join(&module.relocations))?;
+            let module = LoadedModule { config: module, kind: module_kind, delinks, relocations, code };
+
+            for symbol in symbols {
+                let initializer = module.render_initializer(symbol, &symbol_maps)?;
+                writeln!(output, "{initializer}")?;
+            }
+        }
+
+        if !found {
+            bail!("No data symbols found");
+        }
+
+        match &self.output_path {
+            Some(path) => {
+                if let Some(parent) = path.parent() {
+                    create_dir_all(parent)?;
+                }
+                write_file(path, output)?;
+            }
+            None => print!("{output}"),
+        }
+
+        Ok(())
+    }
+}
+
+impl LoadedModule<'_> {
+    fn render_initializer(&self, symbol: &Symbol, symbol_maps: &SymbolMaps) -> Result<String> {
+        let SymbolKind::Data(data) = symbol.kind else { bail!("Symbol {} is not a data symbol", symbol.name) };
+        let section = self.section_of(symbol)?;
+        let symbol_map = symbol_maps.get(self.kind).unwrap();
+
+        let size = data.size().unwrap_or_else(|| {
+            let next_address = symbol_map
+                .iter_by_address(symbol.addr + 1..section.end_address())
+                .next()
+                .map(|s| s.addr)
+                .unwrap_or(section.end_address());
+            symbol.size(next_address)
+        });
+        let base_address = self.delinks.sections.base_address().unwrap();
+        let start = (symbol.addr - base_address) as usize;
+        let Some(bytes) = self.code.get(start..start + size as usize) else {
+            bail!("Symbol {} at {:#010x} is outside of the {} module", symbol.name, symbol.addr, self.config.name);
+        };
+
+        // Vectors and matrices are initialized by their components
+        let value_size = data.value_size();
+        let elements = self.elements(symbol, data, bytes, symbol_maps)?;
+        let rendered = elements.iter().map(|(element, _)| element.to_string()).collect::<Vec<_>>();
+
+        // Verify the initializer against the original bytes of the module. Every rendered element is evaluated, resolving
+        // names as the symbol's file would, and the values are reassembled into bytes which must equal the original ones.
+        let file_sections = self.file_sections_of(symbol)?;
+        let other_sections = Sections::new();
+        let mut reassembled = Vec::with_capacity(bytes.len());
+        for ((_, module_kind), text) in elements.iter().zip(rendered.iter()) {
+            let sections = if *module_kind == self.kind { file_sections } else { &other_sections };
+            let value = Self::evaluate(text, *module_kind, sections, symbol_maps)
+                .with_context(|| format!("Failed to evaluate '{text}' in {}", symbol.name))?;
+            if value_size < 8 && value >> (value_size * 8) != 0 {
+                bail!("Value '{text}' in {} does not fit in {}", symbol.name, data.c_element_type());
+            }
+            let length = (value_size as usize).min(bytes.len() - reassembled.len());
+            reassembled.extend_from_slice(&value.to_le_bytes()[..length]);
+        }
+        if let Some(offset) = reassembled.iter().zip(bytes).position(|(value, original)| value != original) {
+            let text = &rendered[offset / value_size as usize];
+            bail!(
+                "Initializer of {} does not match the original data at {:#010x}: '{text}' differs from the module",
+                symbol.name,
+                symbol.addr + offset as u32
+            );
+        }
+        if reassembled.len() != bytes.len() {
+            bail!("Initializer of {} has {:#x} bytes but the symbol has {:#x}", symbol.name, reassembled.len(), bytes.len());
+        }
+
+        let constant = if section.name() == ".rodata" { "const " } else { "" };
+        let mut initializer = String::new();
+        writeln!(initializer, "// {:#010x}, {}", symbol.addr, section.name())?;
+        if data.count() == Some(1) && value_size == data.element_size() {
+            writeln!(initializer, "{constant}{} = {};", data.c_declaration(&symbol.name), rendered[0])?;
+            return Ok(initializer);
+        }
+
+        writeln!(initializer, "{constant}{} = {{", data.c_declaration(&symbol.name))?;
+        let per_line = (16 / value_size) as usize;
+        for line in rendered.chunks(per_line) {
+            writeln!(initializer, "    {},", line.join(", "))?;
+        }
+        writeln!(initializer, "}};")?;
+        Ok(initializer)
+    }
+
+    /// Splits the bytes into elements, replacing relocated words with pointers. Each pointer also has the module that its
+    /// symbol is in.
+    fn elements(
+        &self,
+        symbol: &Symbol,
+        data: SymData,
+        bytes: &[u8],
+        symbol_maps: &SymbolMaps,
+    ) -> Result<Vec<(Element, ModuleKind)>> {
+        let value_size = data.value_size() as usize;
+        let mut elements = vec![];
+        for (index, chunk) in bytes.chunks(value_size).enumerate() {
+            let address = symbol.addr + (index * value_size) as u32;
+            let mut value = [0u8; 8];
+            value[..chunk.len()].copy_from_slice(chunk);
+            let value = u64::from_le_bytes(value);
+
+            let Some(relocation) = self.relocations.get(address) else {
+                if self.relocations.iter_range(address..address + value_size as u32).next().is_some() {
+                    log::warn!(
+                        "Relocation inside {} at {address:#010x} can't be expressed with {}, change the symbol to a word",
+                        symbol.name,
+                        data.c_element_type()
+                    );
+                }
+                elements.push((Element::Value(value), self.kind));
+                continue;
+            };
+            if value_size != 4 {
+                log::warn!(
+                    "Relocation from {address:#010x} in {} can't be expressed with {}, change the symbol to a word",
+                    symbol.name,
+                    data.c_element_type()
+                );
+                elements.push((Element::Value(value), self.kind));
+                continue;
+            }
+
+            let module_kind = relocation
+                .module()
+                .first_module()
+                .with_context(|| format!("Relocation from {address:#010x} in {} has no destination module", symbol.name))?;
+            if relocation.module().other_modules().is_some() {
+                log::warn!("Ambiguous relocation from {address:#010x} in {}, using {module_kind}", symbol.name);
+            }
+            let symbol_map =
+                symbol_maps.get(module_kind).with_context(|| format!("No symbol map for {module_kind}, does it exist?"))?;
+            let to_address = relocation.to_address();
+            let (target, offset) = if let Some((_, target)) = symbol_map.by_address(to_address)? {
+                (target, 0)
+            } else if let Some((_, target)) = symbol_map.get_function(to_address)? {
+                (target, 0)
+            } else if let Some(target) = symbol_map.get_containing(to_address) {
+                // Relocations into the middle of a symbol, such as an array element, are an offset from the symbol
+                (target, to_address - target.addr)
+            } else {
+                bail!("No symbol found for relocation from {address:#010x} to {to_address:#010x}");
+            };
+
+            let function = matches!(target.kind, SymbolKind::Function(_));
+            let addend = relocation.addend() + offset as i64;
+            elements.push((Element::Pointer { name: target.name.clone(), function, addend }, module_kind));
+        }
+        Ok(elements)
+    }
+
+    /// Evaluates a rendered element, resolving symbols in the given module. Local symbols are only visible if they are in
+    /// `file_sections`.
+    fn evaluate(text: &str, module_kind: ModuleKind, file_sections: &Sections, symbol_maps: &SymbolMaps) -> Result<u64> {
+        let Some(pointer) = text.strip_prefix("(u32)") else {
+            return Ok(u64::from_str_radix(text.trim_start_matches("0x"), 16)?);
+        };
+
+        let (name, addend) = match pointer.split_once(' ') {
+            Some((name, addend)) => {
+                let (sign, value) = addend.split_at(1);
+                let value = parse_hex_address(value)? as i64;
+                (name, if sign == "-" { -value } else { value })
+            }
+            None => (pointer, 0),
+        };
+        let name = name.strip_prefix('&').unwrap_or(name);
+
+        let symbol_map = symbol_maps.get(module_kind).unwrap();
+        let Some((_, symbol)) = symbol_map.by_name_from_file(name, file_sections)? else {
+            bail!("Symbol {name} not found in {module_kind}")
+        };
+        let thumb_bit = match symbol.kind {
+            SymbolKind::Function(SymFunction { mode: InstructionMode::Thumb, .. }) => 1,
+            _ => 0,
+        };
+        Ok((((symbol.addr as i64 + addend) as u32) | thumb_bit).into())
+    }
+
+    fn file_sections_of(&self, symbol: &Symbol) -> Result<&Sections> {
+        self.delinks
+            .files
+            .iter()
+            .find(|file| file.sections.get_by_contained_address(symbol.addr).is_some())
+            .map(|file| &file.sections)
+            .with_context(|| format!("Symbol {} at {:#010x} is not in any file", symbol.name, symbol.addr))
+    }
+
+    fn section_of(&self, symbol: &Symbol) -> Result<&Section> {
+        self.delinks
+            .sections
+            .iter()
+            .find(|s| s.address_range().contains(&symbol.addr))
+            .with_context(|| format!("Symbol {} at {:#010x} is not in any section", symbol.name, symbol.addr))
+    }
+}
+
+impl std::fmt::Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Element::Value(value) => write!(f, "{value:#x}"),
+            Element::Pointer { name, function, addend } => {
+                // Functions decay to pointers, and have the Thumb bit set by the compiler
+                let reference = if *function { "" } else { "&" };
+                write!(f, "(u32){reference}{name}")?;
+                if *addend > 0 {
+                    write!(f, " + {addend:#x}")?;
+                } else if *addend < 0 {
+                    write!(f, " - {:#x}", addend.abs())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
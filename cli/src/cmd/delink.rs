@@ -16,17 +16,17 @@ use ds_decomp::config::{
 };
 use ds_rom::rom::{Rom, RomLoadOptions};
 use object::{Architecture, BinaryFormat, Endianness, RelocationFlags};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     config::{
-        delinks::DelinksExt,
+        delinks::{DelinkFileExt, DelinksExt},
         relocation::{RelocationKindExt, RelocationModuleExt},
         section::SectionExt,
         symbol::{SymbolExt, SymbolKindExt},
     },
     util::{
-        io::{create_dir_all, create_file},
+        io::{create_dir_all, create_file, open_file},
         path::PathExt,
     },
 };
@@ -37,12 +37,30 @@ pub struct Delink {
     /// Path to config.yaml.
     #[arg(long, short = 'c')]
     pub config_path: PathBuf,
+
+    /// Writes every object, even if its inputs have not changed since the last delink.
+    #[arg(long, short = 'f')]
+    pub force: bool,
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, Deserialize)]
 struct DelinkResult {
     num_files: usize,
     num_gaps: usize,
+    /// Number of objects which were written
+    #[serde(default)]
+    num_written: usize,
+    /// Number of objects which were left untouched because their inputs have not changed
+    #[serde(default)]
+    num_unchanged: usize,
+    /// Version of dsd which delinked the objects, as a newer version may produce different objects from the same inputs
+    #[serde(default)]
+    version: String,
+    /// Hashes of the inputs of each object, by object path
+    #[serde(default)]
+    hashes: BTreeMap<String, String>,
+    #[serde(skip)]
+    previous_hashes: BTreeMap<String, String>,
 }
 
 impl Delink {
@@ -57,13 +75,19 @@ impl Delink {
         )?;
 
         let elf_path = config_path.join(config.delinks_path);
-        let mut result = DelinkResult::default();
+        let result_path = elf_path.normalize_join("delink.yaml")?;
+        let mut result = DelinkResult { version: env!("CARGO_PKG_VERSION").to_string(), ..Default::default() };
+        if !self.force {
+            result.previous_hashes =
+                Self::load_previous_result(&result_path).map(|previous| previous.hashes).unwrap_or_default();
+        }
 
         self.delink_arm9(&config.main_module, &rom, &elf_path, &mut symbol_maps, &mut result)?;
         self.delink_autoloads(&config.autoloads, &rom, &elf_path, &mut symbol_maps, &mut result)?;
         self.delink_overlays(&config.overlays, &rom, &elf_path, &mut symbol_maps, &mut result)?;
 
-        serde_yml::to_writer(create_file(elf_path.normalize_join("delink.yaml")?)?, &result)?;
+        serde_yml::to_writer(create_file(result_path)?, &result)?;
+        log::info!("Wrote {} objects, {} were unchanged", result.num_written, result.num_unchanged);
 
         Ok(())
     }
@@ -86,16 +110,7 @@ impl Delink {
         let code = rom.arm9().code()?;
         let module = Module::new_arm9(config.name.clone(), symbol_map, relocations, delinks.sections, code)?;
 
-        for file in &delinks.files {
-            let (file_path, _) = file.split_file_ext();
-            Self::create_elf_file(&module, file, elf_path.join(format!("{file_path}.o")), symbol_maps)?;
-
-            if file.gap() {
-                result.num_gaps += 1;
-            } else {
-                result.num_files += 1;
-            }
-        }
+        self.delink_files(&module, &delinks.files, elf_path, symbol_maps, result)?;
 
         Ok(())
     }
@@ -131,16 +146,7 @@ impl Delink {
                 code,
             )?;
 
-            for file in &delinks.files {
-                let (file_path, _) = file.split_file_ext();
-                Self::create_elf_file(&module, file, elf_path.join(format!("{file_path}.o")), symbol_maps)?;
-
-                if file.gap() {
-                    result.num_gaps += 1;
-                } else {
-                    result.num_files += 1;
-                }
-            }
+            self.delink_files(&module, &delinks.files, elf_path, symbol_maps, result)?;
         }
 
         Ok(())
@@ -166,21 +172,94 @@ impl Delink {
             let module =
                 Module::new_overlay(overlay.module.name.clone(), symbol_map, relocations, delinks.sections, overlay.id, code)?;
 
-            for file in &delinks.files {
-                let (file_path, _) = file.split_file_ext();
-                Self::create_elf_file(&module, file, elf_path.join(format!("{file_path}.o")), symbol_maps)?;
+            self.delink_files(&module, &delinks.files, elf_path, symbol_maps, result)?;
+        }
 
-                if file.gap() {
-                    result.num_gaps += 1;
-                } else {
-                    result.num_files += 1;
-                }
-            }
+        Ok(())
+    }
+
+    /// Returns the result of the previous delink, or nothing if it was done by another version of dsd.
+    fn load_previous_result(result_path: &Path) -> Option<DelinkResult> {
+        if !result_path.exists() {
+            return None;
         }
+        let previous = match Self::read_result(result_path) {
+            Ok(previous) => previous,
+            Err(error) => {
+                log::warn!("Failed to read {}, delinking all objects: {error:#}", result_path.display());
+                return None;
+            }
+        };
+        (previous.version == env!("CARGO_PKG_VERSION")).then_some(previous)
+    }
+
+    fn read_result(result_path: &Path) -> Result<DelinkResult> {
+        Ok(serde_yml::from_reader(open_file(result_path)?)?)
+    }
+
+    fn delink_files(
+        &self,
+        module: &Module,
+        files: &[DelinkFile],
+        elf_path: &Path,
+        symbol_maps: &SymbolMaps,
+        result: &mut DelinkResult,
+    ) -> Result<()> {
+        for file in files {
+            let object_name = Self::object_name(file);
+            let object_path = elf_path.join(&object_name);
+
+            let hash = format!("{:016x}", file.input_hash(module, symbol_maps)?);
+            if result.previous_hashes.get(&object_name) == Some(&hash) && object_path.exists() {
+                result.num_unchanged += 1;
+            } else {
+                Self::create_elf_file(module, file, object_path, symbol_maps)?;
+                result.num_written += 1;
+            }
+            result.hashes.insert(object_name, hash);
 
+            if file.gap() {
+                result.num_gaps += 1;
+            } else {
+                result.num_files += 1;
+            }
+        }
         Ok(())
     }
 
+    /// Delinks some of the files outside of a full delink, such as in `dsd watch`. Their input hashes are recorded in
+    /// delink.yaml like in a full delink, so that the next `dsd delink` doesn't keep an object which was overwritten here.
+    /// Returns the paths of the objects.
+    pub(crate) fn delink_some_files(
+        config: &Config,
+        config_dir: &Path,
+        files: &[(&Module, &DelinkFile)],
+        symbol_maps: &SymbolMaps,
+    ) -> Result<Vec<PathBuf>> {
+        let elf_path = config_dir.join(&config.delinks_path);
+        let result_path = elf_path.normalize_join("delink.yaml")?;
+        let mut result = Self::load_previous_result(&result_path)
+            .unwrap_or_else(|| DelinkResult { version: env!("CARGO_PKG_VERSION").to_string(), ..Default::default() });
+
+        let mut object_paths = vec![];
+        for &(module, file) in files {
+            let object_name = Self::object_name(file);
+            let object_path = elf_path.join(&object_name);
+            let hash = format!("{:016x}", file.input_hash(module, symbol_maps)?);
+            Self::create_elf_file(module, file, &object_path, symbol_maps)?;
+            result.hashes.insert(object_name, hash);
+            object_paths.push(object_path);
+        }
+
+        serde_yml::to_writer(create_file(result_path)?, &result)?;
+        Ok(object_paths)
+    }
+
+    fn object_name(file: &DelinkFile) -> String {
+        let (file_path, _) = file.split_file_ext();
+        format!("{file_path}.o")
+    }
+
     fn create_elf_file<P: AsRef<Path>>(
         module: &Module,
         delink_file: &DelinkFile,
@@ -207,6 +286,8 @@ impl Delink {
         // Maps address to ObjSection/ObjSymbol
         let mut obj_sections = BTreeMap::new();
         let mut obj_symbols = BTreeMap::new();
+        // Maps absolute symbol names to addresses outside of the modules which the file refers to
+        let mut absolute_symbols = BTreeMap::new();
 
         let mut error = false;
 
@@ -220,6 +301,10 @@ impl Delink {
                 SectionKind::Bss => object::SectionKind::UninitializedData,
             };
 
+            for symbol in file_section.absolute_symbols(&code, symbol_maps) {
+                absolute_symbols.insert(symbol.name.clone(), symbol.addr);
+            }
+
             // Create section
             let obj_section_id = object.add_section(vec![], name.clone(), kind);
             let section = object.section_mut(obj_section_id);
@@ -247,7 +332,7 @@ impl Delink {
                 // Get symbol data
                 let max_address = symbols.peek().map(|s| s.addr).unwrap_or(file_section.end_address());
                 let kind = symbol.kind.as_obj_symbol_kind();
-                let scope = symbol.kind.as_obj_symbol_scope();
+                let scope = symbol.as_obj_symbol_scope();
                 let value = (symbol.addr - file_section.start_address()) as u64;
 
                 // Create symbol
@@ -282,6 +367,20 @@ impl Delink {
             obj_sections.insert(file_section.start_address(), obj_section_id);
         }
 
+        // Absolute symbols are local, since the same address can be referred to by many files
+        for (name, address) in absolute_symbols {
+            object.add_symbol(object::write::Symbol {
+                name: name.into_bytes(),
+                value: address as u64,
+                size: 0,
+                kind: object::SymbolKind::Data,
+                scope: object::SymbolScope::Compilation,
+                weak: false,
+                section: object::write::SymbolSection::Absolute,
+                flags: object::SymbolFlags::None,
+            });
+        }
+
         // Must start a new loop here so we can know which section a symbol ID belongs to
         for file_section in delink_file.sections.iter() {
             let obj_section_id = *obj_sections.get(&file_section.start_address()).unwrap();
@@ -315,6 +414,17 @@ impl Delink {
                         error = true;
                         continue;
                     };
+                    if symbol.local {
+                        log::error!(
+                            "Relocation from {:#010x} in {} refers to local symbol '{}' at {:#010x} in another file",
+                            relocation.from_address(),
+                            module.kind(),
+                            symbol.name,
+                            dest_addr,
+                        );
+                        error = true;
+                        continue;
+                    }
 
                     // Add external symbol to section
                     let kind = relocation.kind().as_obj_symbol_kind();
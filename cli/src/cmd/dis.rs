@@ -12,7 +12,7 @@ use ds_decomp::config::{
     module::{Module, ModuleKind},
     relocations::Relocations,
     section::Section,
-    symbol::{InstructionMode, Symbol, SymbolKind, SymbolMaps},
+    symbol::{FunctionStatus, InstructionMode, SymFunction, Symbol, SymbolKind, SymbolMaps},
 };
 use ds_rom::rom::{raw::AutoloadKind, Rom, RomLoadOptions};
 
@@ -22,7 +22,10 @@ use crate::{
         delinks::DelinksExt,
         symbol::{SymDataExt, SymbolLookup},
     },
-    util::io::{create_file, read_file},
+    util::{
+        asm::AsmTarget,
+        io::{create_file, read_file, write_file},
+    },
 };
 
 /// Disassembles an extracted ROM.
@@ -39,6 +42,10 @@ pub struct Disassemble {
     /// Disassemble with Unified Assembler Language (UAL) syntax.
     #[arg(long, short = 'u')]
     pub ual: bool,
+
+    /// Assembler to generate assembly code for.
+    #[arg(long, short = 't', value_enum, default_value_t = AsmTarget::Mwasmarm)]
+    pub target: AsmTarget,
 }
 
 impl Disassemble {
@@ -53,6 +60,10 @@ impl Disassemble {
 
         let mut symbol_maps = SymbolMaps::from_config(config_path, &config)?;
 
+        let macros_path = self.asm_path.join("macros");
+        create_dir_all(&macros_path)?;
+        write_file(macros_path.join("function.inc"), self.target.function_macros())?;
+
         self.disassemble_arm9(&config.main_module, &mut symbol_maps, &rom, extract_path)?;
         self.disassemble_autoloads(&config.autoloads, &mut symbol_maps, &rom, extract_path)?;
         if let Some(arm9_overlays) = &rom.config().arm9_overlays {
@@ -203,22 +214,27 @@ impl Disassemble {
         symbol_maps: &SymbolMaps,
     ) -> Result<()> {
         writeln!(writer, "    .include \"macros/function.inc\"")?;
+        if self.target == AsmTarget::GnuAs && self.ual {
+            writeln!(writer, "    .syntax unified")?;
+        }
         writeln!(writer)?;
 
         let symbol_map = symbol_maps.get(module.kind()).unwrap();
 
         for section in delink_file.sections.sorted_by_address() {
             // write section directive
-            match section.name() {
-                ".text" => writeln!(writer, "    .text")?,
-                _ => writeln!(writer, "    .section {}, 4, 1, 4", section.name())?,
-            }
+            writeln!(writer, "{}", self.target.section_directive(section.name(), section.kind()))?;
 
             let code = section.code_from_module(module)?;
             let mut offset = 0; // offset within section
 
-            let symbol_lookup =
-                SymbolLookup { module_kind: module.kind(), symbol_map, symbol_maps, relocations: module.relocations() };
+            let symbol_lookup = SymbolLookup {
+                module_kind: module.kind(),
+                symbol_map,
+                symbol_maps,
+                relocations: module.relocations(),
+                asm_target: self.target,
+            };
 
             let mut symbol_iter = symbol_map.iter_by_address(section.address_range()).peekable();
             while let Some(symbol) = symbol_iter.next() {
@@ -233,12 +249,13 @@ impl Disassemble {
                                 offset = function_offset;
                             }
 
-                            writeln!(writer, "    .global {}", symbol.name)?;
+                            self.write_function_status(writer, sym_function, delink_file)?;
+                            Self::write_global(symbol, writer)?;
                             match sym_function.mode {
                                 InstructionMode::Arm => writeln!(writer, "    arm_func_start {}", symbol.name)?,
                                 InstructionMode::Thumb => writeln!(writer, "    thumb_func_start {}", symbol.name)?,
                             }
-                            writeln!(writer, "{}: ; {:#010x}", symbol.name, symbol.addr)?;
+                            writeln!(writer, "{}: {} {:#010x}", symbol.name, self.target.comment(), symbol.addr)?;
                         } else {
                             let function = module.get_function(symbol.addr).with_context(|| format!(
                                 "Tried to disassemble function symbol '{}' at {:#010x} but the function was not found in the module",
@@ -252,22 +269,25 @@ impl Disassemble {
                                 writeln!(writer)?;
                             }
 
+                            self.write_function_status(writer, sym_function, delink_file)?;
                             function.write_assembly(writer, &symbol_lookup, module.code(), module.base_address(), self.ual)?;
                             offset = function.end_address() - section.start_address();
                         }
                     }
                     SymbolKind::Data(data) => {
                         let start = (symbol.addr - section.start_address()) as usize;
+                        Self::write_gap(code, offset, start as u32, writer)?;
 
                         let size =
                             data.size().unwrap_or_else(|| Self::size_to_next_symbol(section, symbol, symbol_iter.peek()));
 
                         let end = start + size as usize;
                         let bytes = &code.unwrap()[start..end];
+                        Self::write_global(symbol, writer)?;
                         write!(writer, "{}:", symbol.name)?;
 
                         if symbol.ambiguous {
-                            write!(writer, " ; ambiguous")?;
+                            write!(writer, " {} ambiguous", self.target.comment())?;
                         }
                         writeln!(writer)?;
 
@@ -276,27 +296,59 @@ impl Disassemble {
                     }
                     SymbolKind::Bss(bss) => {
                         let size = bss.size.unwrap_or_else(|| Self::size_to_next_symbol(section, symbol, symbol_iter.peek()));
+                        let start = symbol.addr - section.start_address();
+                        Self::write_gap(code, offset, start, writer)?;
+                        Self::write_global(symbol, writer)?;
                         writeln!(writer, "{}: .space {:#x}", symbol.name, size)?;
-                        offset += size;
+                        offset = start + size;
                     }
                     _ => {}
                 }
             }
 
             let end_offset = section.end_address() - section.start_address();
-            if offset < end_offset {
-                if let Some(code) = code {
-                    Self::dump_bytes(code, offset, end_offset, writer)?;
-                    writeln!(writer)?;
-                } else {
-                    writeln!(writer, "    .space {:#x}", end_offset - offset)?;
-                }
-            }
+            Self::write_gap(code, offset, end_offset, writer)?;
         }
 
         Ok(())
     }
 
+    /// Exports the symbol from the assembled object, unless it's local to the file.
+    fn write_global(symbol: &Symbol, writer: &mut BufWriter<File>) -> Result<()> {
+        if !symbol.local {
+            writeln!(writer, "    .global {}", symbol.name)?;
+        }
+        Ok(())
+    }
+
+    /// Fills the space between two symbols, so that the assembled code has the same layout as the original.
+    fn write_gap(code: Option<&[u8]>, offset: u32, end_offset: u32, writer: &mut BufWriter<File>) -> Result<()> {
+        if offset >= end_offset {
+            return Ok(());
+        }
+        if let Some(code) = code {
+            Self::dump_bytes(code, offset, end_offset, writer)?;
+            writeln!(writer)?;
+        } else {
+            writeln!(writer, "    .space {:#x}", end_offset - offset)?;
+        }
+        Ok(())
+    }
+
+    /// Annotates the function with its status, unless it has not been decompiled.
+    fn write_function_status(
+        &self,
+        writer: &mut BufWriter<File>,
+        function: SymFunction,
+        delink_file: &DelinkFile,
+    ) -> Result<()> {
+        match function.status(delink_file.complete) {
+            FunctionStatus::Asm => {}
+            status => writeln!(writer, "{} {status}", self.target.comment())?,
+        }
+        Ok(())
+    }
+
     fn size_to_next_symbol(section: &Section, symbol: &Symbol, next: Option<&&Symbol>) -> u32 {
         if let Some(next_symbol) = next {
             next_symbol.addr.min(section.end_address()) - symbol.addr
@@ -0,0 +1,187 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::{Config, ConfigModule},
+    delinks::Delinks,
+    module::ModuleKind,
+    section::SectionKind,
+    symbol::{InstructionMode, SymFunction, SymbolKind, SymbolMap, SymbolMaps},
+};
+use ds_rom::rom::{raw::AutoloadKind, Rom, RomLoadOptions};
+use object::{
+    build::elf::{Builder, SectionData},
+    elf, Endianness,
+};
+
+use crate::{
+    config::symbol::{SymbolExt, SymbolKindExt},
+    util::io::{create_dir_all, write_file},
+};
+
+/// Creates a non-relocatable ELF of the whole program for debuggers, built from the original ROM code. Every module is placed
+/// at its real address, and overlays are added as separate sections which overlap each other.
+#[derive(Args)]
+pub struct Elf {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to output ELF file.
+    #[arg(long, short = 'e')]
+    pub elf_path: PathBuf,
+}
+
+impl Elf {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let rom = Rom::load(
+            config_dir.join(&config.rom_config),
+            RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false },
+        )?;
+        let rom_autoloads = rom.arm9().autoloads()?;
+
+        let mut builder = Builder::new(Endianness::Little, false);
+        builder.header.e_type = elf::ET_EXEC;
+        builder.header.e_machine = elf::EM_ARM;
+        builder.header.e_entry = rom.arm9().entry_function() as u64;
+        builder.header.e_flags = elf::EF_ARM_EABI_VER5;
+
+        let mut num_symbols = 0;
+
+        num_symbols +=
+            self.add_module(&mut builder, &config.main_module, ModuleKind::Arm9, rom.arm9().code()?, &symbol_maps)?;
+        for autoload in &config.autoloads {
+            let code = rom_autoloads
+                .iter()
+                .find(|a| a.kind() == autoload.kind)
+                .with_context(|| format!("Autoload {} not present in ROM", autoload.kind))?
+                .code();
+            num_symbols +=
+                self.add_module(&mut builder, &autoload.module, ModuleKind::Autoload(autoload.kind), code, &symbol_maps)?;
+        }
+        for overlay in &config.overlays {
+            let code = rom
+                .arm9_overlays()
+                .get(overlay.id as usize)
+                .with_context(|| format!("Overlay {} not present in ROM", overlay.id))?
+                .code();
+            num_symbols +=
+                self.add_module(&mut builder, &overlay.module, ModuleKind::Overlay(overlay.id), code, &symbol_maps)?;
+        }
+
+        let num_sections = builder.sections.count();
+
+        for (name, data) in
+            [(".symtab", SectionData::Symbol), (".strtab", SectionData::String), (".shstrtab", SectionData::SectionString)]
+        {
+            let section = builder.sections.add();
+            section.name = name.into();
+            section.data = data;
+        }
+
+        let mut buffer = Vec::new();
+        builder.write(&mut buffer)?;
+
+        if let Some(parent) = self.elf_path.parent() {
+            create_dir_all(parent)?;
+        }
+        write_file(&self.elf_path, buffer)?;
+
+        log::info!("Wrote {num_sections} sections and {num_symbols} symbols to {}", self.elf_path.display());
+
+        Ok(())
+    }
+
+    /// Adds the sections and symbols of a module, and returns the number of symbols added.
+    fn add_module<'a>(
+        &self,
+        builder: &mut Builder<'a>,
+        module: &ConfigModule,
+        module_kind: ModuleKind,
+        code: &'a [u8],
+        symbol_maps: &'a SymbolMaps,
+    ) -> Result<usize> {
+        let config_dir = self.config_path.parent().unwrap();
+
+        let module_name = match module_kind {
+            ModuleKind::Arm9 => ".arm9".to_string(),
+            ModuleKind::Overlay(id) => format!(".ov{:03}", id),
+            ModuleKind::Autoload(AutoloadKind::Itcm) => ".itcm".to_string(),
+            ModuleKind::Autoload(AutoloadKind::Dtcm) => ".dtcm".to_string(),
+            ModuleKind::Autoload(_) => bail!("Unknown autoload kind"),
+        };
+
+        let sections = Delinks::from_file(config_dir.join(&module.delinks), module_kind)?.sections;
+        let Some(base_address) = sections.base_address() else {
+            log::warn!("Module {module_kind} has no sections, skipping");
+            return Ok(0);
+        };
+        let symbol_map: &SymbolMap = symbol_maps.get(module_kind).unwrap();
+
+        let mut num_symbols = 0;
+        for section in sections.sorted_by_address() {
+            let obj_section = builder.sections.add();
+            obj_section.name = format!("{module_name}{}", section.name()).into_bytes().into();
+            obj_section.sh_addr = section.start_address() as u64;
+            obj_section.sh_addralign = section.alignment() as u64;
+            obj_section.sh_flags = match section.kind() {
+                SectionKind::Code => (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64,
+                SectionKind::Data | SectionKind::Bss => (elf::SHF_ALLOC | elf::SHF_WRITE) as u64,
+            };
+            match section.code(code, base_address)? {
+                Some(code) => {
+                    obj_section.sh_type = elf::SHT_PROGBITS;
+                    obj_section.data = SectionData::Data(code.to_vec().into());
+                }
+                None => {
+                    obj_section.sh_type = elf::SHT_NOBITS;
+                    obj_section.data = SectionData::UninitializedData(section.size() as u64);
+                }
+            }
+            let obj_section_id = obj_section.id();
+
+            let mut symbols = symbol_map.iter_by_address(section.address_range()).peekable();
+            while let Some(symbol) = symbols.next() {
+                let max_address = symbols.peek().map(|s| s.addr).unwrap_or(section.end_address());
+
+                let st_bind = match symbol.as_obj_symbol_scope() {
+                    object::SymbolScope::Compilation => elf::STB_LOCAL,
+                    _ => elf::STB_GLOBAL,
+                };
+                let st_type = match symbol.kind.as_obj_symbol_kind() {
+                    object::SymbolKind::Text => elf::STT_FUNC,
+                    object::SymbolKind::Data => elf::STT_OBJECT,
+                    _ => elf::STT_NOTYPE,
+                };
+                // Thumb functions have the lowest bit set, as per the ARM ELF specification
+                let thumb_bit = match symbol.kind {
+                    SymbolKind::Function(SymFunction { mode: InstructionMode::Thumb, .. }) => 1,
+                    _ => 0,
+                };
+
+                let obj_symbol = builder.symbols.add();
+                obj_symbol.name = symbol.name.as_str().into();
+                obj_symbol.section = Some(obj_section_id);
+                obj_symbol.set_st_info(st_bind, st_type);
+                obj_symbol.st_value = (symbol.addr | thumb_bit) as u64;
+                obj_symbol.st_size = symbol.size(max_address) as u64;
+                num_symbols += 1;
+
+                if let Some(name) = symbol.mapping_symbol_name() {
+                    let mapping_symbol = builder.symbols.add();
+                    mapping_symbol.name = name.into();
+                    mapping_symbol.section = Some(obj_section_id);
+                    mapping_symbol.set_st_info(elf::STB_LOCAL, elf::STT_NOTYPE);
+                    mapping_symbol.st_value = symbol.addr as u64;
+                }
+            }
+        }
+
+        Ok(num_symbols)
+    }
+}
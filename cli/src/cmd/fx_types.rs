@@ -0,0 +1,154 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::Write as _,
+    path::PathBuf,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::{
+    analysis::fixed_point::FxFunction,
+    config::{
+        config::{Config, ConfigModule},
+        module::ModuleKind,
+        symbol::{SymData, Symbol, SymbolKind, SymbolMaps},
+    },
+};
+
+use crate::config::{
+    config::{ConfigExt, ConfigModuleExt},
+    relocation::RelocationModuleExt,
+};
+
+/// Types data symbols as fixed-point vectors and matrices when their addresses are passed to known NitroSDK math functions,
+/// such as `VEC_Add` or `MTX_Concat43`. Only data symbols of type `any` are changed.
+#[derive(Args)]
+pub struct FxTypes {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Name of the module to analyze, such as `main`, `itcm` or `ov000`. Analyzes all modules by default.
+    #[arg(long, short = 'm')]
+    pub module: Option<String>,
+
+    /// Dry run, print the changes to symbols.txt instead of writing them.
+    #[arg(long, short = 'd')]
+    pub dry: bool,
+}
+
+/// A data symbol to be retyped.
+struct FxRetype {
+    module_kind: ModuleKind,
+    address: u32,
+    data: SymData,
+    /// Name of the function which the symbol was passed to
+    function: &'static str,
+}
+
+impl FxTypes {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let codes = config.load_module_codes(config_dir)?;
+
+        let modules = config
+            .iter_modules()
+            .filter(|(module, _)| self.module.as_ref().map_or(true, |name| &module.name == name))
+            .collect::<Vec<_>>();
+        if modules.is_empty() {
+            bail!("Module '{}' not found", self.module.as_deref().unwrap_or_default());
+        }
+
+        let mut symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let mut retypes = BTreeMap::<(ModuleKind, u32), FxRetype>::new();
+        for (module_config, kind) in modules {
+            let code = codes.get(module_config, kind)?;
+            let (module, _) = module_config.load_module(config_dir, kind, code, &mut symbol_maps)?;
+
+            for function in module.sections().functions() {
+                let arguments =
+                    FxFunction::find_arguments(function, module.code(), module.base_address(), |address, called| {
+                        let callee_module =
+                            module.relocations().get(address).and_then(|r| r.module().first_module()).unwrap_or(kind);
+                        let (_, callee) = symbol_maps.get(callee_module)?.get_function(called.address).ok()??;
+                        FxFunction::find(&callee.name)
+                    });
+
+                for argument in arguments {
+                    // Values which aren't relocated are not pointers into a module
+                    let Some(relocation) = module.relocations().get(argument.pool_address) else { continue };
+                    let Some(module_kind) = relocation.module().first_module() else { continue };
+                    let Some(symbol_map) = symbol_maps.get(module_kind) else { continue };
+                    let Some((SymData::Any, _)) = symbol_map.get_data(argument.pointer)? else { continue };
+
+                    // Unbounded, so that the symbol keeps the size it had as `any`
+                    let data = SymData::Fx { kind: argument.kind, count: None };
+                    match retypes.entry((module_kind, argument.pointer)) {
+                        btree_map::Entry::Vacant(entry) => {
+                            entry.insert(FxRetype {
+                                module_kind,
+                                address: argument.pointer,
+                                data,
+                                function: argument.function.name,
+                            });
+                        }
+                        btree_map::Entry::Occupied(entry) => {
+                            let retype = entry.get();
+                            if retype.data != data {
+                                log::warn!(
+                                    "Data at {:#010x} in {module_kind} is passed to {} as {} and to {} as {}, keeping {}",
+                                    argument.pointer,
+                                    retype.function,
+                                    retype.data,
+                                    argument.function.name,
+                                    data,
+                                    retype.data
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.dry {
+            print!("{}", Self::diff(&config, &symbol_maps, &retypes)?);
+            return Ok(());
+        }
+
+        for retype in retypes.values() {
+            symbol_maps.get_mut(retype.module_kind).retype_data_by_address(retype.address, retype.data)?;
+        }
+        symbol_maps.to_files(&config, config_dir)?;
+        log::info!("Typed {} data symbols as fixed-point data", retypes.len());
+
+        Ok(())
+    }
+
+    fn diff(config: &Config, symbol_maps: &SymbolMaps, retypes: &BTreeMap<(ModuleKind, u32), FxRetype>) -> Result<String> {
+        let mut diff = String::new();
+        let mut current_module = None;
+        for retype in retypes.values() {
+            if current_module != Some(retype.module_kind) {
+                current_module = Some(retype.module_kind);
+                let path = Self::module_config(config, retype.module_kind)?.symbols.display();
+                writeln!(diff, "--- {path}")?;
+                writeln!(diff, "+++ {path}")?;
+            }
+            let (_, symbol) = symbol_maps.get(retype.module_kind).unwrap().get_data(retype.address)?.unwrap();
+            let retyped = Symbol { kind: SymbolKind::Data(retype.data), ..symbol.clone() };
+            writeln!(diff, "-{symbol}")?;
+            writeln!(diff, "+{retyped}")?;
+        }
+        Ok(diff)
+    }
+
+    fn module_config(config: &Config, module_kind: ModuleKind) -> Result<&ConfigModule> {
+        config
+            .iter_modules()
+            .find_map(|(module, kind)| (kind == module_kind).then_some(module))
+            .with_context(|| format!("No config for {module_kind}"))
+    }
+}
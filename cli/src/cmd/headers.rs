@@ -0,0 +1,214 @@
+use std::{collections::HashSet, fmt::Write as _, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+use ds_decomp::{
+    analysis::secure_area::SwiFunction,
+    config::{
+        config::{Config, ConfigModule},
+        delinks::Delinks,
+        module::ModuleKind,
+        section::{Section, SectionKind},
+        symbol::{InstructionMode, SymData, SymbolKind, SymbolMap, SymbolMaps},
+    },
+};
+use unarm::args::Register;
+
+use crate::{
+    config::{delinks::DelinksExt, symbol::SymDataExt},
+    util::io::{create_dir_all, write_file},
+};
+
+/// Generates C headers which declare every global function and data symbol, along with the BIOS function numbers.
+#[derive(Args)]
+pub struct Headers {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Output path for the headers.
+    #[arg(long, short = 'o')]
+    pub output_path: PathBuf,
+
+    /// Generate one header per file in delinks.txt, instead of one header per module.
+    #[arg(long, short = 'f')]
+    pub per_file: bool,
+
+    /// Don't define the fixed-point types, for projects which include the NitroSDK headers before the generated headers.
+    #[arg(long, short = 'x')]
+    pub no_fx_types: bool,
+}
+
+pub(crate) const TYPES: &str = "\
+#ifndef DSD_TYPES
+#define DSD_TYPES
+typedef unsigned char u8;
+typedef unsigned short u16;
+typedef unsigned long u32;
+#endif
+";
+
+const FX_TYPES: &str = "\
+#ifndef DSD_FX_TYPES
+#define DSD_FX_TYPES
+typedef signed short fx16;
+typedef signed long fx32;
+typedef signed long long fx64c;
+typedef struct { fx16 x, y, z; } VecFx16;
+typedef struct { fx32 x, y, z; } VecFx32;
+typedef struct { fx32 m[2][2]; } MtxFx22;
+typedef struct { fx32 m[3][3]; } MtxFx33;
+typedef struct { fx32 m[4][3]; } MtxFx43;
+typedef struct { fx32 m[4][4]; } MtxFx44;
+#endif
+";
+
+impl Headers {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+
+        let mut num_headers = 0;
+        num_headers += self.write_module_headers(&config.main_module, ModuleKind::Arm9, &symbol_maps)?;
+        for autoload in &config.autoloads {
+            num_headers += self.write_module_headers(&autoload.module, ModuleKind::Autoload(autoload.kind), &symbol_maps)?;
+        }
+        for overlay in &config.overlays {
+            num_headers += self.write_module_headers(&overlay.module, ModuleKind::Overlay(overlay.id), &symbol_maps)?;
+        }
+        self.write_bios_header()?;
+        num_headers += 1;
+
+        log::info!("Wrote {num_headers} headers to {}", self.output_path.display());
+
+        Ok(())
+    }
+
+    /// Writes the headers of a module, and returns the number of headers written.
+    fn write_module_headers(&self, module: &ConfigModule, module_kind: ModuleKind, symbol_maps: &SymbolMaps) -> Result<usize> {
+        let config_dir = self.config_path.parent().unwrap();
+        let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&module.delinks), module_kind)?;
+        let symbol_map = symbol_maps.get(module_kind).unwrap();
+
+        if !self.per_file {
+            let sections = delinks.sections.sorted_by_address();
+            self.write_header(&module.name, &sections, symbol_map)?;
+            return Ok(1);
+        }
+
+        for file in &delinks.files {
+            let (file_path, _) = file.split_file_ext();
+            let sections = file.sections.sorted_by_address();
+            self.write_header(file_path, &sections, symbol_map)?;
+        }
+        Ok(delinks.files.len())
+    }
+
+    /// Writes `bios.h`, which defines the number of each BIOS function for calling it with `swi`.
+    fn write_bios_header(&self) -> Result<()> {
+        let mut header = String::new();
+        writeln!(header, "// Generated by dsd headers")?;
+        writeln!(header)?;
+        writeln!(header, "#ifndef DSD_BIOS_H")?;
+        writeln!(header, "#define DSD_BIOS_H")?;
+        writeln!(header)?;
+        writeln!(header, "// In ARM mode, the BIOS reads the function number from bits 16-23 of the swi comment field")?;
+        for function in SwiFunction::ALL {
+            let name = function.name(Register::R0);
+            writeln!(header, "#define SWI_{name} {:#x} // {}", function.interrupt_value(), function.signature())?;
+        }
+        writeln!(header)?;
+        writeln!(header, "#endif // DSD_BIOS_H")?;
+
+        let path = self.output_path.join("bios.h");
+        create_dir_all(&self.output_path)?;
+        write_file(&path, header)?;
+
+        Ok(())
+    }
+
+    fn write_header(&self, name: &str, sections: &[&Section], symbol_map: &SymbolMap) -> Result<()> {
+        let guard = format!(
+            "DSD_{}_H",
+            name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect::<String>()
+        );
+
+        let mut header = String::new();
+        writeln!(header, "// Generated by dsd headers")?;
+        writeln!(header)?;
+        writeln!(header, "#ifndef {guard}")?;
+        writeln!(header, "#define {guard}")?;
+        writeln!(header)?;
+        writeln!(header, "{TYPES}")?;
+
+        // The fixed-point types are only defined if they are used, as they conflict with the NitroSDK headers
+        let mut declarations = String::new();
+        let mut uses_fx = false;
+        let mut declared = HashSet::new();
+        for section in sections {
+            let constant = section.name() == ".rodata" || section.kind() == SectionKind::Code;
+            let mut symbols = symbol_map.iter_by_address(section.address_range()).peekable();
+            while let Some(symbol) = symbols.next() {
+                let declarable = matches!(symbol.kind, SymbolKind::Function(_) | SymbolKind::Data(_) | SymbolKind::Bss(_));
+                // Local symbols are private to their file, so only global symbols are declared
+                if symbol.local || !declarable || !declared.insert(symbol.name.as_str()) {
+                    continue;
+                }
+                match symbol.kind {
+                    SymbolKind::Function(function) => {
+                        let mode = match function.mode {
+                            InstructionMode::Arm => "arm",
+                            InstructionMode::Thumb => "thumb",
+                        };
+                        writeln!(declarations, "void {}(void); // {:#010x}, {mode}", symbol.name, symbol.addr)?;
+                    }
+                    SymbolKind::Data(data) => {
+                        uses_fx |= matches!(data, SymData::Fx { .. });
+                        let constant = if constant { "const " } else { "" };
+                        writeln!(
+                            declarations,
+                            "extern {constant}{}; // {:#010x}, {}",
+                            data.c_declaration(&symbol.name),
+                            symbol.addr,
+                            section.name()
+                        )?;
+                    }
+                    SymbolKind::Bss(bss) => {
+                        let declaration = match bss.size {
+                            Some(size) => format!("u8 {}[{size:#x}]", symbol.name),
+                            None => {
+                                let max_address = symbols.peek().map(|s| s.addr).unwrap_or(section.end_address());
+                                format!("u8 {}[{:#x}]", symbol.name, symbol.size(max_address))
+                            }
+                        };
+                        writeln!(declarations, "extern {declaration}; // {:#010x}, {}", symbol.addr, section.name())?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if uses_fx && !self.no_fx_types {
+            writeln!(header, "{FX_TYPES}")?;
+        }
+        writeln!(header, "#ifdef __cplusplus")?;
+        writeln!(header, "extern \"C\" {{")?;
+        writeln!(header, "#endif")?;
+        writeln!(header)?;
+        header.push_str(&declarations);
+        writeln!(header)?;
+        writeln!(header, "#ifdef __cplusplus")?;
+        writeln!(header, "}}")?;
+        writeln!(header, "#endif")?;
+        writeln!(header)?;
+        writeln!(header, "#endif // {guard}")?;
+
+        let path = self.output_path.join(format!("{name}.h"));
+        create_dir_all(path.parent().unwrap())?;
+        write_file(&path, header)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+
+use super::names::{import_names, parse_module_kind, ImportReport, ImportedKind, ImportedName};
+use crate::util::{
+    io::read_to_string,
+    parse::{parse_hex_address, split_csv_line},
+};
+
+/// Imports symbol names from a CSV file with the columns: address, name, type, module.
+#[derive(Args, Clone)]
+pub struct ImportCsv {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    config_path: PathBuf,
+
+    /// Path to CSV file. The type (function, data, bss) and module (main, itcm, dtcm, ovXXX) columns may be left empty.
+    #[arg(long, short = 'i')]
+    csv_path: PathBuf,
+
+    /// Dry run, do not write any files.
+    #[arg(long, short = 'd')]
+    dry: bool,
+}
+
+impl ImportCsv {
+    pub fn run(&self) -> Result<()> {
+        let text = read_to_string(&self.csv_path)?;
+
+        let mut report = ImportReport::default();
+        let mut names = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let row = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let columns = split_csv_line(line);
+            let column = |i: usize| columns.get(i).map(|s| s.as_str()).unwrap_or("");
+
+            let address = match parse_hex_address(column(0)) {
+                Ok(address) => address,
+                Err(_) if row == 1 && column(0).eq_ignore_ascii_case("address") => continue, // header
+                Err(error) => {
+                    report.conflict(row, column(1), format!("invalid address '{}': {error}", column(0)));
+                    continue;
+                }
+            };
+            let name = column(1).to_string();
+            if name.is_empty() {
+                report.conflict(row, &name, format!("no name for address {address:#010x}"));
+                continue;
+            }
+            let kind = match ImportedKind::parse(column(2)) {
+                Ok(kind) => kind,
+                Err(error) => {
+                    report.conflict(row, &name, error.to_string());
+                    continue;
+                }
+            };
+            let module = match parse_module_kind(column(3)) {
+                Ok(module) => module,
+                Err(error) => {
+                    report.conflict(row, &name, error.to_string());
+                    continue;
+                }
+            };
+
+            names.push(ImportedName { row, address, name, kind, module });
+        }
+
+        import_names(&self.config_path, names, report, self.dry)
+    }
+}
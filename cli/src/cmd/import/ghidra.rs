@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use super::names::{import_names, parse_module_kind, ImportReport, ImportedKind, ImportedName};
+use crate::util::{
+    io::read_to_string,
+    parse::{parse_hex_address, split_csv_line},
+};
+
+/// Default name prefixes generated by Ghidra's auto analysis.
+const DEFAULT_NAME_PREFIXES: &[&str] =
+    &["FUN_", "DAT_", "LAB_", "PTR_", "SUB_", "BYTE_", "WORD_", "DWORD_", "switchD_", "caseD_", "thunk_FUN_"];
+
+/// Imports symbol names from a Ghidra symbol table, exported as CSV from the Symbol Table window.
+#[derive(Args, Clone)]
+pub struct ImportGhidra {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    config_path: PathBuf,
+
+    /// Path to exported symbol table. Must have a header with at least the columns "Name" and "Location".
+    #[arg(long, short = 'i')]
+    csv_path: PathBuf,
+
+    /// Includes symbols with default names like `FUN_0211514c`.
+    #[arg(long, short = 'D')]
+    include_default_names: bool,
+
+    /// Dry run, do not write any files.
+    #[arg(long, short = 'd')]
+    dry: bool,
+}
+
+impl ImportGhidra {
+    pub fn run(&self) -> Result<()> {
+        let text = read_to_string(&self.csv_path)?;
+        let mut lines = text.lines().enumerate();
+
+        let (_, header) = lines.next().context("Symbol table is empty")?;
+        let header = split_csv_line(header);
+        let find_column = |name: &str| header.iter().position(|column| column.eq_ignore_ascii_case(name));
+        let name_column = find_column("Name").context("Symbol table has no 'Name' column")?;
+        let location_column = find_column("Location").context("Symbol table has no 'Location' column")?;
+        let type_column = find_column("Type");
+        let source_column = find_column("Source");
+
+        let report = ImportReport::default();
+        let mut names = vec![];
+        for (index, line) in lines {
+            let row = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let columns = split_csv_line(line);
+            let column = |i: Option<usize>| i.and_then(|i| columns.get(i)).map(|s| s.as_str()).unwrap_or("");
+
+            let name = column(Some(name_column));
+            let symbol_type = column(type_column);
+            let kind = match symbol_type {
+                "Function" => Some(ImportedKind::Function),
+                "Label" => Some(ImportedKind::Label),
+                "Data" | "" => None,
+                // Parameters, local variables, namespaces, classes, external symbols etc.
+                _ => continue,
+            };
+            if !self.include_default_names
+                && (column(source_column) == "Default" || DEFAULT_NAME_PREFIXES.iter().any(|prefix| name.starts_with(prefix)))
+            {
+                continue;
+            }
+
+            // Locations look like "02000c30", "ram:02000c30" or "ov012::0211514c" for overlay blocks
+            let location = column(Some(location_column));
+            let (block, address) = location.rsplit_once(':').unwrap_or(("", location));
+            let address = match parse_hex_address(address) {
+                Ok(address) => address,
+                Err(_) => {
+                    log::debug!("Skipping '{name}' at non-memory location '{location}'");
+                    continue;
+                }
+            };
+            let block = block.trim_end_matches(':');
+            let module = parse_module_kind(block).unwrap_or(None);
+
+            names.push(ImportedName { row, address, name: name.to_string(), kind, module });
+        }
+
+        import_names(&self.config_path, names, report, self.dry)
+    }
+}
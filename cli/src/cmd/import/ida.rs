@@ -0,0 +1,84 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+
+use super::names::{import_names, parse_module_kind, ImportReport, ImportedName};
+use crate::util::{io::read_to_string, parse::parse_hex_address};
+
+/// Default name prefixes generated by IDA's auto analysis.
+const DEFAULT_NAME_PREFIXES: &[&str] =
+    &["sub_", "loc_", "locret_", "unk_", "byte_", "word_", "dword_", "off_", "nullsub_", "j_", "def_", "jpt_", "algn_"];
+
+/// Imports symbol names from an IDA MAP file, created with "File > Produce file > Create MAP file".
+#[derive(Args, Clone)]
+pub struct ImportIda {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    config_path: PathBuf,
+
+    /// Path to MAP file.
+    #[arg(long, short = 'i')]
+    map_path: PathBuf,
+
+    /// Includes symbols with default names like `sub_0211514c`.
+    #[arg(long, short = 'D')]
+    include_default_names: bool,
+
+    /// Dry run, do not write any files.
+    #[arg(long, short = 'd')]
+    dry: bool,
+}
+
+struct IdaSegment {
+    start: u32,
+    name: String,
+}
+
+impl ImportIda {
+    pub fn run(&self) -> Result<()> {
+        let text = read_to_string(&self.map_path)?;
+
+        let report = ImportReport::default();
+        let mut names = vec![];
+        let mut segments = HashMap::new();
+        let mut in_publics = false;
+        for (index, line) in text.lines().enumerate() {
+            let row = index + 1;
+            if line.contains("Publics by Value") {
+                in_publics = true;
+                continue;
+            }
+
+            // Segment lines:  0001:02000000 000A4F80H ARM9    CODE
+            // Public lines:   0001:02000C30       main
+            let mut words = line.split_whitespace();
+            let Some((segment, offset)) = words.next().and_then(|word| word.split_once(':')) else { continue };
+            let (Ok(segment), Ok(offset)) = (u16::from_str_radix(segment, 16), parse_hex_address(offset)) else { continue };
+
+            if !in_publics {
+                let (Some(_length), Some(name)) = (words.next(), words.next()) else { continue };
+                segments.insert(segment, IdaSegment { start: offset, name: name.to_string() });
+                continue;
+            }
+
+            let Some(name) = words.next() else { continue };
+            if !self.include_default_names && DEFAULT_NAME_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+                continue;
+            }
+
+            // Offsets are absolute when the segment is based at 0, which is the case for DS binaries loaded at their real
+            // address. Otherwise the offset is relative to the segment start.
+            let segment = segments.get(&segment);
+            let address = match segment {
+                Some(segment) if offset < segment.start => segment.start + offset,
+                _ => offset,
+            };
+            let module = segment.and_then(|segment| parse_module_kind(&segment.name).ok().flatten());
+
+            names.push(ImportedName { row, address, name: name.to_string(), kind: None, module });
+        }
+
+        import_names(&self.config_path, names, report, self.dry)
+    }
+}
@@ -1,5 +1,12 @@
+mod csv;
+mod ghidra;
+mod ida;
+mod names;
 mod symbols;
 
+use csv::*;
+use ghidra::*;
+use ida::*;
 use symbols::*;
 
 use anyhow::Result;
@@ -16,6 +23,9 @@ impl ImportArgs {
     pub fn run(&self) -> Result<()> {
         match &self.command {
             ImportCommand::Symbols(symbols) => symbols.run(),
+            ImportCommand::Csv(csv) => csv.run(),
+            ImportCommand::Ghidra(ghidra) => ghidra.run(),
+            ImportCommand::Ida(ida) => ida.run(),
         }
     }
 }
@@ -23,4 +33,7 @@ impl ImportArgs {
 #[derive(Subcommand)]
 enum ImportCommand {
     Symbols(ImportSymbols),
+    Csv(ImportCsv),
+    Ghidra(ImportGhidra),
+    Ida(ImportIda),
 }
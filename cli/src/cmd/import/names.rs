@@ -0,0 +1,334 @@
+use std::{fmt::Display, path::Path};
+
+use anyhow::{bail, Context, Result};
+use ds_decomp::{
+    analysis::functions::{Function, FunctionParseOptions, ParseFunctionOptions, ParseFunctionResult},
+    config::{
+        config::Config,
+        delinks::{DelinkFile, Delinks},
+        module::ModuleKind,
+        section::{SectionKind, Sections},
+        symbol::{SymBss, SymData, SymbolMaps},
+    },
+};
+use ds_rom::rom::{raw::AutoloadKind, Rom, RomLoadOptions};
+
+/// A symbol name read from an external source, such as a CSV file or a Ghidra/IDA export.
+pub struct ImportedName {
+    /// Line number in the imported file
+    pub row: usize,
+    pub address: u32,
+    pub name: String,
+    /// Kind of symbol to create if there is no symbol at the address, or `None` to decide based on the section kind
+    pub kind: Option<ImportedKind>,
+    /// Module containing the symbol, or `None` to find it by address
+    pub module: Option<ModuleKind>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ImportedKind {
+    Function,
+    Data,
+    Bss,
+    /// Renames the symbol at its address, or creates a data or .bss symbol. Labels in code are skipped, as they are mostly
+    /// branch targets within functions.
+    Label,
+}
+
+impl ImportedKind {
+    pub fn parse(text: &str) -> Result<Option<Self>> {
+        match text.to_lowercase().as_str() {
+            "" | "any" => Ok(None),
+            "function" | "func" | "code" => Ok(Some(Self::Function)),
+            "data" => Ok(Some(Self::Data)),
+            "bss" => Ok(Some(Self::Bss)),
+            "label" => Ok(Some(Self::Label)),
+            _ => bail!("unknown symbol type '{text}', must be one of: function, data, bss, label"),
+        }
+    }
+
+    fn section_kind(self) -> Option<SectionKind> {
+        match self {
+            Self::Function => Some(SectionKind::Code),
+            Self::Data => Some(SectionKind::Data),
+            Self::Bss => Some(SectionKind::Bss),
+            Self::Label => None,
+        }
+    }
+}
+
+impl Display for ImportedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Function => write!(f, "function"),
+            Self::Data => write!(f, "data"),
+            Self::Bss => write!(f, "bss"),
+            Self::Label => write!(f, "label"),
+        }
+    }
+}
+
+/// Parses module names as they appear in dsd configs, linked ELFs and Ghidra/IDA memory blocks, such as `main`, `ARM9`,
+/// `itcm`, `ov012` and `overlay(12)`.
+pub fn parse_module_kind(text: &str) -> Result<Option<ModuleKind>> {
+    let text = text.trim().to_lowercase();
+    match text.as_str() {
+        "" => Ok(None),
+        "main" | "arm9" => Ok(Some(ModuleKind::Arm9)),
+        "itcm" => Ok(Some(ModuleKind::Autoload(AutoloadKind::Itcm))),
+        "dtcm" => Ok(Some(ModuleKind::Autoload(AutoloadKind::Dtcm))),
+        _ => {
+            let overlay_id = text
+                .strip_prefix("overlay(")
+                .and_then(|id| id.strip_suffix(')'))
+                .or_else(|| text.strip_prefix("ov"))
+                .with_context(|| format!("unknown module '{text}'"))?;
+            let overlay_id = overlay_id.parse().with_context(|| format!("invalid overlay ID in module '{text}'"))?;
+            Ok(Some(ModuleKind::Overlay(overlay_id)))
+        }
+    }
+}
+
+pub struct ImportConflict {
+    pub row: usize,
+    pub name: String,
+    pub reason: String,
+}
+
+impl Display for ImportConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: '{}': {}", self.row, self.name, self.reason)
+    }
+}
+
+#[derive(Default)]
+pub struct ImportReport {
+    pub num_renamed: usize,
+    pub num_created: usize,
+    pub num_unchanged: usize,
+    pub num_skipped: usize,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+impl ImportReport {
+    pub fn conflict<S: Into<String>>(&mut self, row: usize, name: &str, reason: S) {
+        self.conflicts.push(ImportConflict { row, name: name.to_string(), reason: reason.into() });
+    }
+
+    fn print(&self) {
+        for conflict in &self.conflicts {
+            log::warn!("{conflict}");
+        }
+        log::info!(
+            "Renamed {} symbols, created {} symbols, {} already up to date, {} skipped, {} conflicts",
+            self.num_renamed,
+            self.num_created,
+            self.num_unchanged,
+            self.num_skipped,
+            self.conflicts.len()
+        );
+    }
+}
+
+struct ImportModule<'a> {
+    kind: ModuleKind,
+    sections: Sections,
+    files: Vec<DelinkFile>,
+    code: &'a [u8],
+}
+
+impl ImportModule<'_> {
+    /// Returns the name of the delink file containing the address, if any.
+    fn file_name(&self, address: u32) -> Option<&str> {
+        self.files.iter().find(|file| file.sections.get_by_contained_address(address).is_some()).map(|file| file.name.as_str())
+    }
+}
+
+/// Applies the imported names to the symbol maps of the given dsd config. Conflicts are added to the report instead of
+/// aborting the import.
+pub fn import_names(config_path: &Path, names: Vec<ImportedName>, mut report: ImportReport, dry: bool) -> Result<()> {
+    let config = Config::from_file(config_path)?;
+    let config_dir = config_path.parent().unwrap();
+
+    let mut symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+    let rom = Rom::load(
+        config_dir.join(&config.rom_config),
+        RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false },
+    )?;
+    let rom_autoloads = rom.arm9().autoloads()?;
+
+    let delinks = Delinks::from_file(config_dir.join(&config.main_module.delinks), ModuleKind::Arm9)?;
+    let mut modules = vec![ImportModule {
+        kind: ModuleKind::Arm9,
+        sections: delinks.sections,
+        files: delinks.files,
+        code: rom.arm9().code()?,
+    }];
+    for autoload in &config.autoloads {
+        let kind = ModuleKind::Autoload(autoload.kind);
+        let code = rom_autoloads
+            .iter()
+            .find(|a| a.kind() == autoload.kind)
+            .with_context(|| format!("Autoload {} not present in ROM", autoload.kind))?
+            .code();
+        let delinks = Delinks::from_file(config_dir.join(&autoload.module.delinks), kind)?;
+        modules.push(ImportModule { kind, sections: delinks.sections, files: delinks.files, code });
+    }
+    for overlay in &config.overlays {
+        let kind = ModuleKind::Overlay(overlay.id);
+        let code = rom
+            .arm9_overlays()
+            .get(overlay.id as usize)
+            .with_context(|| format!("Overlay {} not present in ROM", overlay.id))?
+            .code();
+        let delinks = Delinks::from_file(config_dir.join(&overlay.module.delinks), kind)?;
+        modules.push(ImportModule { kind, sections: delinks.sections, files: delinks.files, code });
+    }
+
+    for name in names {
+        import_name(&modules, &mut symbol_maps, name, &mut report)?;
+    }
+
+    report.print();
+
+    if !dry {
+        symbol_maps.to_files(&config, config_dir)?;
+    }
+
+    Ok(())
+}
+
+fn import_name(
+    modules: &[ImportModule],
+    symbol_maps: &mut SymbolMaps,
+    imported: ImportedName,
+    report: &mut ImportReport,
+) -> Result<()> {
+    let ImportedName { row, address, name, kind, module: module_kind } = imported;
+
+    let candidates = modules
+        .iter()
+        .filter(|module| module_kind.map_or(true, |kind| module.kind == kind))
+        .filter(|module| module.sections.get_by_contained_address(address & !1).is_some())
+        .collect::<Vec<_>>();
+    let module = match candidates.as_slice() {
+        [] => {
+            let reason = match module_kind {
+                Some(kind) => format!("address {address:#010x} is not in {kind}"),
+                None => format!("address {address:#010x} is not in any module"),
+            };
+            report.conflict(row, &name, reason);
+            return Ok(());
+        }
+        [module] => *module,
+        _ => {
+            let kinds = candidates.iter().map(|module| module.kind.to_string()).collect::<Vec<_>>().join(", ");
+            report.conflict(
+                row,
+                &name,
+                format!("address {address:#010x} is ambiguous between {kinds}, please specify a module"),
+            );
+            return Ok(());
+        }
+    };
+    let symbol_map = symbol_maps.get_mut(module.kind);
+
+    // Symbols are stored without the Thumb bit, but the imported address may include it
+    let existing_address = [address, address & !1].into_iter().find(|&address| symbol_map.for_address(address).is_some());
+
+    // Local symbols may share their name with local symbols in other files
+    let local_file = existing_address
+        .and_then(|address| symbol_map.for_address(address)?.next())
+        .filter(|(_, symbol)| symbol.local)
+        .map(|(_, symbol)| module.file_name(symbol.addr));
+    let name_taken_at = symbol_map.for_name(&name).and_then(|mut symbols| {
+        symbols
+            .find(|(_, symbol)| {
+                let other_file_local = symbol.local && local_file.is_some_and(|file| file != module.file_name(symbol.addr));
+                Some(symbol.addr) != existing_address && !other_file_local
+            })
+            .map(|(_, symbol)| symbol.addr)
+    });
+    if let Some(other_address) = name_taken_at {
+        report.conflict(row, &name, format!("name is already used by the symbol at {other_address:#010x} in {}", module.kind));
+        return Ok(());
+    }
+
+    if let Some(existing_address) = existing_address {
+        let num_symbols = symbol_map.for_address(existing_address).map(|symbols| symbols.count()).unwrap_or(0);
+        if num_symbols > 1 {
+            report.conflict(row, &name, format!("there are {num_symbols} symbols at {existing_address:#010x}"));
+            return Ok(());
+        }
+
+        let (_, symbol) = symbol_map.by_address(existing_address)?.unwrap();
+        if symbol.name == name {
+            report.num_unchanged += 1;
+        } else {
+            log::debug!("Renaming '{}' at {existing_address:#010x} to '{name}'", symbol.name);
+            symbol_map.rename_by_address(existing_address, &name)?;
+            report.num_renamed += 1;
+        }
+        return Ok(());
+    }
+
+    let (_, section) = module.sections.get_by_contained_address(address & !1).unwrap();
+    let kind = match kind {
+        Some(ImportedKind::Label) if section.kind() == SectionKind::Code => {
+            log::debug!("Skipping label '{name}' at {address:#010x}, as there is no symbol to rename");
+            report.num_skipped += 1;
+            return Ok(());
+        }
+        Some(ImportedKind::Label) | None => match section.kind() {
+            SectionKind::Code => ImportedKind::Function,
+            SectionKind::Data => ImportedKind::Data,
+            SectionKind::Bss => ImportedKind::Bss,
+        },
+        Some(kind) => kind,
+    };
+    if kind.section_kind() != Some(section.kind()) {
+        report.conflict(
+            row,
+            &name,
+            format!("cannot create {kind} symbol at {address:#010x} in {} section {}", section.kind(), section.name()),
+        );
+        return Ok(());
+    }
+
+    match kind {
+        ImportedKind::Function => {
+            let base_address = module.sections.base_address().unwrap();
+            let end_address = module.sections.end_address().unwrap();
+            let thumb = (address & 1) != 0;
+            let parse_result = Function::parse_function(FunctionParseOptions {
+                name: name.clone(),
+                start_address: address & !1,
+                base_address,
+                module_code: module.code,
+                known_end_address: None,
+                module_start_address: base_address,
+                module_end_address: end_address,
+                parse_options: ParseFunctionOptions { thumb: thumb.then_some(true) },
+                ..Default::default()
+            })?;
+            let function = match parse_result {
+                ParseFunctionResult::Found(function) => function,
+                _ => {
+                    report.conflict(row, &name, format!("failed to analyze function at {address:#010x}: {parse_result:x?}"));
+                    return Ok(());
+                }
+            };
+            symbol_map.add_function(&function);
+        }
+        ImportedKind::Data => {
+            symbol_map.add_data(Some(name), address, SymData::Any)?;
+        }
+        ImportedKind::Bss => {
+            symbol_map.add_bss(Some(name), address, SymBss { size: None })?;
+        }
+        ImportedKind::Label => unreachable!("labels are created as the kind of their section"),
+    }
+    report.num_created += 1;
+
+    Ok(())
+}
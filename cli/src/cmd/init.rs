@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{bail, Result};
 use clap::Args;
@@ -13,6 +16,7 @@ use path_slash::PathBufExt;
 use pathdiff::diff_paths;
 
 use crate::{
+    analysis::overlay_groups::OverlayGroups,
     config::program::Program,
     util::io::{create_dir_all, create_file, open_file},
 };
@@ -97,10 +101,13 @@ impl Init {
         rom_config.arm9_overlays = Some(self.build_path.join("build/arm9_overlays.yaml"));
         let rom_config = rom_config;
 
+        // Overlays which aren't placed after other modules get an explicit origin
+        let overlay_groups = OverlayGroups::analyze(rom.arm9().end_address()?, rom.arm9_overlays(), &BTreeMap::new());
         let overlay_configs = self.overlay_configs(
             &arm9_output_path,
             &arm9_overlays_output_path,
             program.overlays(),
+            &overlay_groups,
             "arm9",
             program.symbol_maps(),
         )?;
@@ -143,7 +150,7 @@ impl Init {
         let relocations_path = path.join("relocs.txt");
 
         if !self.dry {
-            Delinks::to_file(&delinks_path, module.sections())?;
+            Delinks::sections_to_file(&delinks_path, module.sections())?;
             symbol_maps.get(module.kind()).unwrap().to_file(&symbols_path)?;
             module.relocations().to_file(&relocations_path)?;
         }
@@ -162,6 +169,8 @@ impl Init {
             },
             autoloads,
             overlays,
+            new_overlays: vec![],
+            new_files: vec![],
         })
     }
 
@@ -196,7 +205,7 @@ impl Init {
             let relocs_path = autoload_path.join("relocs.txt");
 
             if !self.dry {
-                Delinks::to_file(&delinks_path, module.sections())?;
+                Delinks::sections_to_file(&delinks_path, module.sections())?;
                 symbol_maps.get(module.kind()).unwrap().to_file(&symbols_path)?;
                 module.relocations().to_file(&relocs_path)?;
             }
@@ -222,6 +231,7 @@ impl Init {
         root: &Path,
         path: &Path,
         modules: &[Module],
+        overlay_groups: &OverlayGroups,
         processor: &str,
         symbol_maps: &SymbolMaps,
     ) -> Result<Vec<ConfigOverlay>> {
@@ -244,7 +254,7 @@ impl Init {
             let relocs_path = overlay_config_path.join("relocs.txt");
 
             if !self.dry {
-                Delinks::to_file(&delinks_path, module.sections())?;
+                Delinks::sections_to_file(&delinks_path, module.sections())?;
                 symbol_maps.get(module.kind()).unwrap().to_file(&symbols_path)?;
                 module.relocations().to_file(&relocs_path)?;
             }
@@ -259,6 +269,7 @@ impl Init {
                     relocations: Self::make_path(relocs_path, root),
                 },
                 id,
+                origin: overlay_groups.fixed_origin(id).map(|origin| format!("{origin:#010x}")),
             });
         }
 
@@ -0,0 +1,65 @@
+use std::{fmt::Write as _, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    cmd::{IoRegProcessor, TYPES},
+    util::{
+        asm::AsmTarget,
+        io::{create_dir_all, write_file},
+    },
+};
+
+/// Generates io_reg.h and io_reg.inc, which name the hardware I/O registers for C and assembly code.
+#[derive(Args)]
+pub struct IoRegHeaders {
+    /// Output path for io_reg.h and io_reg.inc.
+    #[arg(long, short = 'o')]
+    pub output_path: PathBuf,
+
+    /// Assembler to generate io_reg.inc for.
+    #[arg(long, short = 't', value_enum, default_value_t = AsmTarget::Mwasmarm)]
+    pub target: AsmTarget,
+
+    /// Processor whose registers to generate.
+    #[arg(long, short = 'p', value_enum, default_value_t = IoRegProcessor::Arm9)]
+    pub processor: IoRegProcessor,
+}
+
+impl IoRegHeaders {
+    pub fn run(&self) -> Result<()> {
+        let registers = self.processor.io_register_map().registers();
+
+        let mut header = String::new();
+        writeln!(header, "#ifndef IO_REG_H")?;
+        writeln!(header, "#define IO_REG_H")?;
+        writeln!(header)?;
+        writeln!(header, "{TYPES}")?;
+        for register in registers {
+            let address = register.address;
+            writeln!(header, "#define {register}_ADDR {address:#010x}")?;
+            match register.size {
+                1 => writeln!(header, "#define {register} (*(volatile u8 *){address:#010x})")?,
+                2 => writeln!(header, "#define {register} (*(volatile u16 *){address:#010x})")?,
+                4 => writeln!(header, "#define {register} (*(volatile u32 *){address:#010x})")?,
+                // Arrays and registers larger than a word are accessed through a pointer
+                _ => writeln!(header, "#define {register} ((volatile u8 *){address:#010x})")?,
+            }
+        }
+        writeln!(header)?;
+        writeln!(header, "#endif")?;
+
+        let mut include = String::new();
+        for register in registers {
+            writeln!(include, "{}", self.target.equ(&register.to_string(), register.address))?;
+        }
+
+        create_dir_all(&self.output_path)?;
+        write_file(self.output_path.join("io_reg.h"), header)?;
+        write_file(self.output_path.join("io_reg.inc"), include)?;
+        log::info!("Wrote {} registers to {}", registers.len(), self.output_path.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+mod headers;
+mod report;
+
+pub use headers::*;
+pub use report::*;
+
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use ds_decomp::analysis::io_registers::IoRegisterMap;
+
+/// Subcommands for the DS hardware I/O registers.
+#[derive(Args)]
+pub struct IoRegArgs {
+    #[command(subcommand)]
+    command: IoRegCommand,
+}
+
+impl IoRegArgs {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            IoRegCommand::Headers(headers) => headers.run(),
+            IoRegCommand::Report(report) => report.run(),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum IoRegCommand {
+    Headers(IoRegHeaders),
+    Report(IoRegReport),
+}
+
+/// Processor whose I/O registers to use.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum IoRegProcessor {
+    Arm9,
+    Arm7,
+}
+
+impl IoRegProcessor {
+    pub fn io_register_map(self) -> IoRegisterMap {
+        match self {
+            Self::Arm9 => IoRegisterMap::Arm9,
+            Self::Arm7 => IoRegisterMap::Arm7,
+        }
+    }
+}
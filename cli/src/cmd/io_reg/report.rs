@@ -0,0 +1,77 @@
+use std::{collections::BTreeSet, fmt::Write as _, path::PathBuf};
+
+use anyhow::{bail, Result};
+use clap::Args;
+use ds_decomp::{
+    analysis::io_registers::IoRegisterMap,
+    config::{config::Config, symbol::SymbolMaps},
+};
+
+use crate::config::config::{ConfigExt, ConfigModuleExt};
+
+/// Lists which functions load the addresses of hardware I/O registers.
+#[derive(Args)]
+pub struct IoRegReport {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Name of the module to report, such as `main`, `itcm` or `ov000`. Reports all modules by default.
+    #[arg(long, short = 'm')]
+    pub module: Option<String>,
+
+    /// Only list functions which use this register, such as `REG_POWCNT1`.
+    #[arg(long, short = 'r')]
+    pub register: Option<String>,
+}
+
+impl IoRegReport {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let codes = config.load_module_codes(config_dir)?;
+
+        let modules = config
+            .iter_modules()
+            .filter(|(module, _)| self.module.as_ref().map_or(true, |name| &module.name == name))
+            .collect::<Vec<_>>();
+        if modules.is_empty() {
+            bail!("Module '{}' not found", self.module.as_deref().unwrap_or_default());
+        }
+
+        let mut symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let mut output = String::new();
+        let mut num_functions = 0;
+        for (module_config, kind) in modules {
+            let code = codes.get(module_config, kind)?;
+            let (module, _) = module_config.load_module(config_dir, kind, code, &mut symbol_maps)?;
+
+            let mut functions = module.sections().functions().collect::<Vec<_>>();
+            functions.sort_unstable_by_key(|function| function.start_address());
+            for function in functions {
+                let registers = function
+                    .iter_pool_constants(module.code(), module.base_address())
+                    .filter_map(|pool_constant| IoRegisterMap::Arm9.find(pool_constant.value))
+                    .map(|(register, _)| register.to_string())
+                    .collect::<BTreeSet<_>>();
+                if registers.is_empty() {
+                    continue;
+                }
+                if let Some(register) = &self.register {
+                    if !registers.contains(register) {
+                        continue;
+                    }
+                }
+
+                let registers = registers.into_iter().collect::<Vec<_>>();
+                writeln!(output, "{} {}: {}", module_config.name, function.name(), registers.join(", "))?;
+                num_functions += 1;
+            }
+        }
+        print!("{output}");
+        log::info!("Found {num_functions} functions which use I/O registers");
+
+        Ok(())
+    }
+}
@@ -6,17 +6,21 @@ use std::{
 };
 
 use anyhow::{bail, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use ds_decomp::config::{
-    config::{Config, ConfigModule},
-    delinks::Delinks,
+    config::{Config, ConfigModule, ConfigNewOverlay},
+    delinks::{DelinkFile, Delinks},
     module::ModuleKind,
 };
 use ds_rom::rom::{raw::AutoloadKind, Rom, RomLoadOptions};
 
 use crate::{
     analysis::overlay_groups::OverlayGroups,
-    config::{delinks::DelinksExt, section::SectionExt},
+    config::{
+        config::{new_section_boundary_name, ConfigExt, ConfigNewOverlayExt, NEW_OVERLAY_SECTIONS},
+        delinks::DelinksExt,
+        section::SectionExt,
+    },
     util::{
         io::{create_dir_all, create_file_and_dirs},
         path::PathExt,
@@ -37,8 +41,23 @@ pub struct Lcf {
     /// Path to object list file.
     #[arg(long, short = 'o')]
     pub objects_file: PathBuf,
+
+    /// Linker script format to generate.
+    #[arg(long, short = 'f', value_enum, default_value_t = LcfFormat::Mwld)]
+    pub format: LcfFormat,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum LcfFormat {
+    /// Linker command file for mwldarm
+    Mwld,
+    /// Linker script for GNU ld, such as arm-none-eabi-ld
+    Gnu,
+}
+
+/// Sections which must not be removed by dead stripping.
+const KEEP_SECTIONS: [&str; 2] = [".init", ".ctor"];
+
 impl Lcf {
     pub fn run(&self) -> Result<()> {
         let config = Config::from_file(&self.config_path)?;
@@ -52,7 +71,8 @@ impl Lcf {
         let build_path = config_dir.normalize_join(&config.build_path)?;
         let delinks_path = config_dir.normalize_join(&config.delinks_path)?;
 
-        let overlay_groups = OverlayGroups::analyze(rom.arm9().end_address()?, rom.arm9_overlays())?;
+        let overlay_groups =
+            OverlayGroups::analyze(rom.arm9().end_address()?, rom.arm9_overlays(), &config.overlay_origins()?);
 
         let lcf_file = create_file_and_dirs(&self.lcf_file)?;
         let mut lcf = BufWriter::new(lcf_file);
@@ -60,9 +80,16 @@ impl Lcf {
         let objects_file = create_file_and_dirs(&self.objects_file)?;
         let mut objects = BufWriter::new(objects_file);
 
-        self.write_memory_section(&mut lcf, rom, overlay_groups, &config, &build_path)?;
-        self.write_keep_section_section(&mut lcf)?;
-        self.write_sections_section(&mut lcf, &mut objects, config_dir, &config, &build_path, &delinks_path)?;
+        match self.format {
+            LcfFormat::Mwld => {
+                self.write_memory_section(&mut lcf, rom, overlay_groups, &config, &build_path)?;
+                self.write_keep_section_section(&mut lcf)?;
+                self.write_sections_section(&mut lcf, &mut objects, config_dir, &config, &build_path, &delinks_path)?;
+            }
+            LcfFormat::Gnu => {
+                self.write_gnu_script(&mut lcf, &mut objects, &rom, &overlay_groups, &config, &build_path, &delinks_path)?;
+            }
+        }
 
         Ok(())
     }
@@ -100,14 +127,16 @@ impl Lcf {
                 delinks_path,
             )?;
         }
+        for overlay in &config.new_overlays {
+            self.write_new_overlay_section(lcf, objects, overlay, build_path)?;
+        }
         writeln!(lcf, "}}\n")?;
         Ok(())
     }
 
     fn write_keep_section_section(&self, lcf: &mut BufWriter<File>) -> Result<()> {
         writeln!(lcf, "KEEP_SECTION {{")?;
-        writeln!(lcf, "    .init,")?;
-        writeln!(lcf, "    .ctor")?;
+        writeln!(lcf, "    {}", KEEP_SECTIONS.join(",\n    "))?;
         writeln!(lcf, "}}\n")?;
         Ok(())
     }
@@ -147,11 +176,12 @@ impl Lcf {
 
                 let memory_name = format!("OV{:03}", overlay.id());
 
-                write!(lcf, "    {memory_name} : ORIGIN = AFTER(")?;
-
-                if group.after.is_empty() {
-                    write!(lcf, "ARM9")?;
+                if group.fixed {
+                    write!(lcf, "    {memory_name} : ORIGIN = {:#x}", group.start_address)?;
+                } else if group.after.is_empty() {
+                    write!(lcf, "    {memory_name} : ORIGIN = AFTER(ARM9)")?;
                 } else {
+                    write!(lcf, "    {memory_name} : ORIGIN = AFTER(")?;
                     for (i, id) in group.after.iter().enumerate() {
                         if i > 0 {
                             write!(lcf, ",")?;
@@ -159,16 +189,26 @@ impl Lcf {
                         let memory_name = format!("OV{:03}", id);
                         write!(lcf, "{memory_name}")?;
                     }
+                    write!(lcf, ")")?;
                 }
 
                 let config = config.overlays.iter().find(|o| o.id == overlay_id).unwrap();
                 writeln!(
                     lcf,
-                    ") > {}",
+                    " > {}",
                     config_dir.normalize_join(&config.module.object)?.strip_prefix_ext(build_path)?.display()
                 )?;
             }
         }
+        for overlay in &config.new_overlays {
+            writeln!(
+                lcf,
+                "    {} : ORIGIN = AFTER({}) > {}",
+                overlay.memory_name(),
+                overlay.after_memory_name(),
+                config_dir.normalize_join(&overlay.object)?.strip_prefix_ext(build_path)?.display()
+            )?;
+        }
         writeln!(lcf, "}}\n")?;
         Ok(())
     }
@@ -184,13 +224,7 @@ impl Lcf {
         build_path: &Path,
         delinks_path: &Path,
     ) -> Result<()> {
-        let (module_name, memory_name): (Cow<str>, Cow<str>) = match module_kind {
-            ModuleKind::Arm9 => (".arm9".into(), "ARM9".into()),
-            ModuleKind::Overlay(id) => (format!(".ov{:03}", id).into(), format!("OV{:03}", id).into()),
-            ModuleKind::Autoload(AutoloadKind::Itcm) => (".itcm".into(), "ITCM".into()),
-            ModuleKind::Autoload(AutoloadKind::Dtcm) => (".dtcm".into(), "DTCM".into()),
-            ModuleKind::Autoload(_) => bail!("Unknown autoload kind"),
-        };
+        let (module_name, memory_name) = module_names(module_kind)?;
 
         writeln!(lcf, "    {module_name} : {{")?;
         let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&module.delinks), module_kind)?;
@@ -211,12 +245,292 @@ impl Lcf {
         writeln!(lcf, "    }} > {memory_name}\n")?;
 
         for file in &delinks.files {
-            let (file_path, _) = file.split_file_ext();
-            let base_path = if file.complete { build_path } else { delinks_path };
-            let file_path = base_path.join(file_path);
-            writeln!(objects, "{}.o", file_path.display())?;
+            writeln!(objects, "{}", object_path(file, build_path, delinks_path).display())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the output section of an overlay which is not in the original ROM. Its source files are always linked from the
+    /// build directory, and every file may contribute to every section.
+    fn write_new_overlay_section(
+        &self,
+        lcf: &mut BufWriter<File>,
+        objects: &mut BufWriter<File>,
+        overlay: &ConfigNewOverlay,
+        build_path: &Path,
+    ) -> Result<()> {
+        let memory_name = overlay.memory_name();
+        let object_paths = overlay.object_paths(build_path);
+
+        writeln!(lcf, "    .{} : {{", memory_name.to_lowercase())?;
+        for (section_name, _, alignment) in NEW_OVERLAY_SECTIONS {
+            writeln!(lcf, "        . = ALIGN({alignment});")?;
+            let section_boundary_name = new_section_boundary_name(section_name);
+            writeln!(lcf, "        {memory_name}_{section_boundary_name}_START = .;")?;
+            for object_path in &object_paths {
+                writeln!(lcf, "        {}({section_name})", object_path.file_name().unwrap().to_string_lossy())?;
+            }
+            writeln!(lcf, "        {memory_name}_{section_boundary_name}_END = .;")?;
+        }
+        writeln!(lcf, "    }} > {memory_name}\n")?;
+
+        for object_path in &object_paths {
+            writeln!(objects, "{}", object_path.display())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a GNU ld script equivalent to the MWLD LCF. Each module gets an output section named after its memory region
+    /// for the initialized sections, followed by a `NOLOAD` section for .bss, so that `objcopy -O binary -j <name>` extracts
+    /// the module binary. Overlay groups which share an address are placed with `OVERLAY` statements.
+    ///
+    /// The .bss of an overlay in a group overlaps the other overlays of the group, and new overlays overlap the original
+    /// overlays which follow the same module, just like they do at runtime. `OVERLAY` statements can't contain `NOLOAD`
+    /// sections, and putting .bss inside the overlay's section would add it to the module binary, so the script must be
+    /// linked with `--no-check-sections` instead.
+    #[allow(clippy::too_many_arguments)]
+    fn write_gnu_script(
+        &self,
+        lcf: &mut BufWriter<File>,
+        objects: &mut BufWriter<File>,
+        rom: &Rom<'_>,
+        overlay_groups: &OverlayGroups,
+        config: &Config,
+        build_path: &Path,
+        delinks_path: &Path,
+    ) -> Result<()> {
+        let config_dir = self.config_path.parent().unwrap();
+
+        writeln!(
+            lcf,
+            "/* Link with --no-check-sections, as overlays which are loaded at the same address overlap each other */"
+        )?;
+        writeln!(lcf, "OUTPUT_ARCH(arm)")?;
+        writeln!(lcf, "ENTRY(Entry)\n")?;
+        writeln!(lcf, "SECTIONS {{")?;
+
+        let main = GnuModule::new(config_dir, &config.main_module, ModuleKind::Arm9)?;
+        main.write_code_section(lcf, &format!("{:#x} ", rom.arm9().base_address()), "", build_path, delinks_path)?;
+        main.write_bss_section(lcf, build_path, delinks_path)?;
+        main.write_objects(objects, build_path, delinks_path)?;
+
+        for autoload in rom.arm9().autoloads()?.iter() {
+            let config = config.autoloads.iter().find(|a| a.kind == autoload.kind()).unwrap();
+            let module = GnuModule::new(config_dir, &config.module, ModuleKind::Autoload(autoload.kind()))?;
+            module.write_code_section(lcf, &format!("{:#x} ", autoload.base_address()), "", build_path, delinks_path)?;
+            module.write_bss_section(lcf, build_path, delinks_path)?;
+            module.write_objects(objects, build_path, delinks_path)?;
+        }
+
+        // Overlays in the same group share their run address, so they need different load addresses to keep ld from
+        // reporting overlapping sections. The load addresses are placed in an unused part of the address space and are
+        // not meaningful otherwise.
+        let mut load_address = "0x80000000".to_string();
+        for group in overlay_groups.iter() {
+            let origin = if group.fixed {
+                format!("{:#x}", group.start_address)
+            } else {
+                group
+                    .after
+                    .iter()
+                    .map(|id| format!("OV{:03}_END", id))
+                    .reduce(|a, b| format!("MAX({a}, {b})"))
+                    .unwrap_or_else(|| "ARM9_END".to_string())
+            };
+
+            let modules = group
+                .overlays
+                .iter()
+                .map(|&overlay_id| {
+                    let config = config.overlays.iter().find(|o| o.id == overlay_id).unwrap();
+                    GnuModule::new(config_dir, &config.module, ModuleKind::Overlay(overlay_id))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            writeln!(lcf, "    OVERLAY {origin} : AT({load_address}) {{")?;
+            for module in &modules {
+                module.write_code_section(lcf, "", "    ", build_path, delinks_path)?;
+            }
+            writeln!(lcf, "    }}\n")?;
+
+            for module in &modules {
+                module.write_bss_section(lcf, build_path, delinks_path)?;
+                module.write_objects(objects, build_path, delinks_path)?;
+            }
+
+            if let Some(last) = modules.last() {
+                let memory_name = &last.memory_name;
+                load_address = format!("LOADADDR({memory_name}) + SIZEOF({memory_name})");
+            }
+        }
+
+        for overlay in &config.new_overlays {
+            self.write_gnu_new_overlay(lcf, objects, overlay, &load_address, build_path)?;
+            let memory_name = overlay.memory_name();
+            load_address = format!("LOADADDR({memory_name}) + SIZEOF({memory_name})");
         }
 
+        writeln!(lcf, "}}")?;
         Ok(())
     }
+
+    /// Writes the output sections of an overlay which is not in the original ROM to a GNU ld script.
+    fn write_gnu_new_overlay(
+        &self,
+        lcf: &mut BufWriter<File>,
+        objects: &mut BufWriter<File>,
+        overlay: &ConfigNewOverlay,
+        load_address: &str,
+        build_path: &Path,
+    ) -> Result<()> {
+        let memory_name = overlay.memory_name();
+        let object_paths = overlay.object_paths(build_path);
+        let write_input_sections = |lcf: &mut BufWriter<File>, initialized: bool| -> Result<()> {
+            for (section_name, kind, alignment) in NEW_OVERLAY_SECTIONS {
+                if kind.is_initialized() != initialized {
+                    continue;
+                }
+                writeln!(lcf, "        . = ALIGN({alignment});")?;
+                let section_boundary_name = new_section_boundary_name(section_name);
+                writeln!(lcf, "        {memory_name}_{section_boundary_name}_START = .;")?;
+                for object_path in &object_paths {
+                    let input = format!("\"{}\"({section_name})", object_path.display());
+                    if KEEP_SECTIONS.contains(&section_name) {
+                        writeln!(lcf, "        KEEP({input})")?;
+                    } else {
+                        writeln!(lcf, "        {input}")?;
+                    }
+                }
+                writeln!(lcf, "        {memory_name}_{section_boundary_name}_END = .;")?;
+            }
+            Ok(())
+        };
+
+        let origin = format!("{}_END", overlay.after_memory_name());
+        writeln!(lcf, "    {memory_name} {origin} : AT({load_address}) {{")?;
+        write_input_sections(lcf, true)?;
+        writeln!(lcf, "    }}\n")?;
+
+        writeln!(lcf, "    {memory_name}.bss ADDR({memory_name}) + SIZEOF({memory_name}) (NOLOAD) : {{")?;
+        write_input_sections(lcf, false)?;
+        writeln!(lcf, "        {memory_name}_END = .;")?;
+        writeln!(lcf, "    }}\n")?;
+
+        for object_path in &object_paths {
+            writeln!(objects, "{}", object_path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// A module as it appears in a GNU ld script.
+struct GnuModule {
+    memory_name: String,
+    delinks: Delinks,
+}
+
+impl GnuModule {
+    fn new(config_dir: &Path, module: &ConfigModule, module_kind: ModuleKind) -> Result<Self> {
+        let (_, memory_name) = module_names(module_kind)?;
+        let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&module.delinks), module_kind)?;
+        Ok(Self { memory_name: memory_name.into_owned(), delinks })
+    }
+
+    fn has_bss(&self) -> bool {
+        self.delinks.sections.iter().any(|s| !s.kind().is_initialized())
+    }
+
+    /// Writes the output section containing all initialized sections. `address` is empty for sections inside an `OVERLAY`
+    /// statement, otherwise it ends with a space.
+    fn write_code_section(
+        &self,
+        lcf: &mut BufWriter<File>,
+        address: &str,
+        indent: &str,
+        build_path: &Path,
+        delinks_path: &Path,
+    ) -> Result<()> {
+        writeln!(lcf, "{indent}    {} {address}: {{", self.memory_name)?;
+        self.write_input_sections(lcf, true, indent, build_path, delinks_path)?;
+        writeln!(lcf, "{indent}    }}")?;
+        if indent.is_empty() {
+            writeln!(lcf)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `NOLOAD` output section for .bss right after the initialized sections, along with the module's end symbol.
+    fn write_bss_section(&self, lcf: &mut BufWriter<File>, build_path: &Path, delinks_path: &Path) -> Result<()> {
+        let memory_name = &self.memory_name;
+        let code_end = format!("ADDR({memory_name}) + SIZEOF({memory_name})");
+        if !self.has_bss() {
+            writeln!(lcf, "    {memory_name}_END = {code_end};\n")?;
+            return Ok(());
+        }
+
+        writeln!(lcf, "    {memory_name}.bss {code_end} (NOLOAD) : {{")?;
+        self.write_input_sections(lcf, false, "", build_path, delinks_path)?;
+        writeln!(lcf, "        {memory_name}_END = .;")?;
+        writeln!(lcf, "    }}\n")?;
+        Ok(())
+    }
+
+    fn write_input_sections(
+        &self,
+        lcf: &mut BufWriter<File>,
+        initialized: bool,
+        indent: &str,
+        build_path: &Path,
+        delinks_path: &Path,
+    ) -> Result<()> {
+        let memory_name = &self.memory_name;
+        for section in self.delinks.sections.sorted_by_address() {
+            if section.kind().is_initialized() != initialized {
+                continue;
+            }
+            writeln!(lcf, "{indent}        . = ALIGN({});", section.alignment())?;
+            let section_boundary_name = section.boundary_name();
+            writeln!(lcf, "{indent}        {memory_name}_{section_boundary_name}_START = .;")?;
+            for file in &self.delinks.files {
+                if file.sections.by_name(section.name()).is_none() {
+                    continue;
+                }
+                let input = format!("\"{}\"({})", object_path(file, build_path, delinks_path).display(), section.name());
+                if KEEP_SECTIONS.contains(&section.name()) {
+                    writeln!(lcf, "{indent}        KEEP({input})")?;
+                } else {
+                    writeln!(lcf, "{indent}        {input}")?;
+                }
+            }
+            writeln!(lcf, "{indent}        {memory_name}_{section_boundary_name}_END = .;")?;
+        }
+        Ok(())
+    }
+
+    fn write_objects(&self, objects: &mut BufWriter<File>, build_path: &Path, delinks_path: &Path) -> Result<()> {
+        for file in &self.delinks.files {
+            writeln!(objects, "{}", object_path(file, build_path, delinks_path).display())?;
+        }
+        Ok(())
+    }
+}
+
+fn module_names(module_kind: ModuleKind) -> Result<(Cow<'static, str>, Cow<'static, str>)> {
+    Ok(match module_kind {
+        ModuleKind::Arm9 => (".arm9".into(), "ARM9".into()),
+        ModuleKind::Overlay(id) => (format!(".ov{:03}", id).into(), format!("OV{:03}", id).into()),
+        ModuleKind::Autoload(AutoloadKind::Itcm) => (".itcm".into(), "ITCM".into()),
+        ModuleKind::Autoload(AutoloadKind::Dtcm) => (".dtcm".into(), "DTCM".into()),
+        ModuleKind::Autoload(_) => bail!("Unknown autoload kind"),
+    })
+}
+
+/// Path to the object file of a delink file, which is in the build directory if the file is complete, or in the delinks
+/// directory otherwise.
+fn object_path(file: &DelinkFile, build_path: &Path, delinks_path: &Path) -> PathBuf {
+    let (file_path, _) = file.split_file_ext();
+    let base_path = if file.complete { build_path } else { delinks_path };
+    PathBuf::from(format!("{}.o", base_path.join(file_path).display()))
 }
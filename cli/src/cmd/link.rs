@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use ds_decomp::config::{config::Config, module::ModuleKind};
+use ds_rom::rom::{Rom, RomLoadOptions};
+
+use crate::{
+    config::config::ConfigExt,
+    linker::{LinkModuleOptions, Linker},
+    util::path::PathExt,
+};
+
+/// Links delinked and compiled objects into module binaries and a linked ELF, without mwldarm.
+#[derive(Args)]
+pub struct Link {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to output ELF file.
+    #[arg(long, short = 'e')]
+    pub elf_path: PathBuf,
+
+    /// Name of the entry function.
+    #[arg(long, short = 'm', default_value = "Entry")]
+    pub entry: String,
+}
+
+impl Link {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let rom = Rom::load(
+            config_dir.join(&config.rom_config),
+            RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false },
+        )?;
+
+        let build_path = config_dir.normalize_join(&config.build_path)?;
+        let delinks_path = config_dir.normalize_join(&config.delinks_path)?;
+        let options = LinkModuleOptions { config_dir, build_path: &build_path, delinks_path: &delinks_path };
+
+        let mut linker = Linker::new();
+        linker.add_module(&config.main_module, ModuleKind::Arm9, &options)?;
+        for autoload in &config.autoloads {
+            linker.add_module(&autoload.module, ModuleKind::Autoload(autoload.kind), &options)?;
+        }
+        for overlay in &config.overlays {
+            linker.add_module(&overlay.module, ModuleKind::Overlay(overlay.id), &options)?;
+        }
+        for overlay in &config.new_overlays {
+            linker.add_new_overlay(overlay, &options)?;
+        }
+
+        linker.layout(&rom, &config.overlay_origins()?)?;
+        linker.resolve_symbols()?;
+        linker.relocate()?;
+
+        linker.write_binaries()?;
+        linker.write_elf(&self.elf_path, &self.entry)?;
+
+        for module in linker.modules() {
+            log::debug!(
+                "{}: {:#010x}..{:#010x}, .bss ends at {:#010x}",
+                module.memory_name,
+                module.start_address,
+                module.code_end_address,
+                module.end_address
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,17 +1,43 @@
 mod check;
+mod data;
 mod delink;
 mod dis;
+mod elf;
+mod fx_types;
+mod headers;
 mod import;
 mod init;
+mod io_reg;
 mod lcf;
+mod link;
 mod objdiff;
+mod patch;
+mod query;
+mod rename;
 mod rom;
+mod scaffold;
+mod serve;
+mod split;
+mod watch;
 
 pub use check::*;
+pub use data::*;
 pub use delink::*;
 pub use dis::*;
+pub use elf::*;
+pub use fx_types::*;
+pub use headers::*;
 pub use import::*;
 pub use init::*;
+pub use io_reg::*;
 pub use lcf::*;
+pub use link::*;
 pub use objdiff::*;
+pub use patch::*;
+pub use query::*;
+pub use rename::*;
 pub use rom::*;
+pub use scaffold::*;
+pub use serve::*;
+pub use split::*;
+pub use watch::*;
@@ -22,35 +22,35 @@ const MIN_OBJDIFF_VERSION: &str = "2.3.2";
 pub struct Objdiff {
     /// Path to config.yaml.
     #[arg(long, short = 'c')]
-    config_path: PathBuf,
+    pub config_path: PathBuf,
 
     /// Path to directory to generate objdiff.json.
     #[arg(long, short = 'o')]
-    output_path: Option<PathBuf>,
+    pub output_path: Option<PathBuf>,
 
     /// Includes decomp.me scratches.
     #[arg(long, short = 's')]
-    scratch: bool,
+    pub scratch: bool,
 
     /// See https://decomp.me/api/compiler with compilers for the `nds_arm9` platform.
     #[arg(long, short = 'C')]
-    compiler: Option<String>,
+    pub compiler: Option<String>,
 
     /// Flags to pass to the compiler in decomp.me.
     #[arg(long, short = 'f', allow_hyphen_values = true)]
-    c_flags: Option<String>,
+    pub c_flags: Option<String>,
 
     /// Preset ID to use in decomp.me.
     #[arg(long, short = 'p')]
-    preset_id: Option<u32>,
+    pub preset_id: Option<u32>,
 
     /// Custom build command.
     #[arg(long, short = 'm')]
-    custom_make: Option<String>,
+    pub custom_make: Option<String>,
 
     /// Arguments to custom build command.
     #[arg(long, short = 'M', allow_hyphen_values = true)]
-    custom_args: Vec<String>,
+    pub custom_args: Vec<String>,
 }
 
 impl Objdiff {
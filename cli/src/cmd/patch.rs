@@ -0,0 +1,195 @@
+use std::{path::PathBuf, process};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{config::Config, module::ModuleKind, symbol::SymbolMaps};
+use ds_rom::{
+    crypto::blowfish::BlowfishKey,
+    rom::{raw::AutoloadKind, OverlayConfig, Rom, RomLoadOptions, RomSaveError},
+};
+
+use crate::{
+    cmd::ConfigRom,
+    patch::{PatchConfig, PatchModule, PatchRegion, Patcher},
+    util::{
+        io::{create_dir_all, create_file, open_file, write_file},
+        path::PathExt,
+    },
+};
+
+/// Links patch objects into the original ROM and hooks them into the game's code, without rebuilding the game.
+#[derive(Args)]
+pub struct Patch {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to patch YAML file.
+    #[arg(long, short = 'p')]
+    pub patch_file: PathBuf,
+
+    /// Output ROM.
+    #[arg(long, short = 'o')]
+    pub rom: PathBuf,
+
+    /// Nintendo DS ARM7 BIOS file.
+    #[arg(long, short = '7')]
+    pub arm7_bios: Option<PathBuf>,
+}
+
+impl Patch {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+        let patch_config = PatchConfig::from_file(&self.patch_file)?;
+        let patch_dir = self.patch_file.parent().unwrap();
+
+        if let Some((program, args)) = patch_config.build.split_first() {
+            let status = process::Command::new(program)
+                .args(args)
+                .current_dir(patch_dir)
+                .status()
+                .with_context(|| format!("Failed to run build command '{program}'"))?;
+            if !status.success() {
+                bail!("Build command '{program}' failed with {status}");
+            }
+        }
+
+        let rom_config_path = config_dir.join(&config.rom_config);
+        let extract_path = rom_config_path.parent().unwrap();
+        let rom =
+            Rom::load(&rom_config_path, RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false })?;
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+
+        let (overlays_path, mut overlay_configs) = match &rom.config().arm9_overlays {
+            Some(arm9_overlays) => {
+                let yaml_path = extract_path.join(arm9_overlays);
+                let overlay_configs: Vec<OverlayConfig> = serde_yml::from_reader(open_file(&yaml_path)?)?;
+                (yaml_path.parent().unwrap().to_path_buf(), overlay_configs)
+            }
+            None => (extract_path.to_path_buf(), vec![]),
+        };
+
+        let mut modules = vec![];
+        modules.push(PatchModule::from_file(
+            ModuleKind::Arm9,
+            rom.arm9().base_address(),
+            extract_path.join(&rom.config().arm9_bin),
+        )?);
+        for autoload in rom.arm9().autoloads()?.iter() {
+            let binary_path = match autoload.kind() {
+                AutoloadKind::Itcm => &rom.config().itcm.bin,
+                AutoloadKind::Dtcm => &rom.config().dtcm.bin,
+                AutoloadKind::Unknown(_) => continue,
+            };
+            modules.push(PatchModule::from_file(
+                ModuleKind::Autoload(autoload.kind()),
+                autoload.base_address(),
+                extract_path.join(binary_path),
+            )?);
+        }
+        for (overlay, overlay_config) in rom.arm9_overlays().iter().zip(&overlay_configs) {
+            modules.push(PatchModule::from_file(
+                ModuleKind::Overlay(overlay.id()),
+                overlay.base_address(),
+                overlays_path.join(&overlay_config.file_name),
+            )?);
+        }
+
+        let mut patcher = Patcher::new(&config, &symbol_maps, modules);
+        for object in &patch_config.objects {
+            patcher.add_object(patch_dir.join(object))?;
+        }
+        patcher.layout(&patch_config.region)?;
+        patcher.resolve_symbols()?;
+        patcher.relocate()?;
+        for hook in &patch_config.hooks {
+            patcher.apply_hook(hook)?;
+        }
+
+        // Write the patched binaries and a ROM config which uses them in place of the original binaries
+        let patch_path = config_dir.normalize_join(&config.build_path)?.join("patch");
+        create_dir_all(&patch_path)?;
+
+        let mut rom_paths = rom.config().clone();
+        ConfigRom::update_relative_paths(&mut rom_paths, extract_path, &patch_path);
+        rom_paths.arm9_bin = ConfigRom::make_path(extract_path.join(&rom_paths.arm9_bin), &patch_path);
+        rom_paths.arm9_config = ConfigRom::make_path(extract_path.join(&rom_paths.arm9_config), &patch_path);
+        rom_paths.itcm.bin = ConfigRom::make_path(extract_path.join(&rom_paths.itcm.bin), &patch_path);
+        rom_paths.itcm.config = ConfigRom::make_path(extract_path.join(&rom_paths.itcm.config), &patch_path);
+        rom_paths.dtcm.bin = ConfigRom::make_path(extract_path.join(&rom_paths.dtcm.bin), &patch_path);
+        rom_paths.dtcm.config = ConfigRom::make_path(extract_path.join(&rom_paths.dtcm.config), &patch_path);
+        for overlay_config in &mut overlay_configs {
+            let file_name = ConfigRom::make_path(overlays_path.join(&overlay_config.file_name), &patch_path);
+            overlay_config.file_name = file_name.to_string_lossy().into_owned();
+        }
+
+        for module in patcher.modules().filter(|module| module.changed) {
+            let file_name = module.binary_path.file_name().unwrap();
+            write_file(patch_path.join(file_name), &module.binary)?;
+            let file_name = PathBuf::from(file_name);
+            match module.kind {
+                ModuleKind::Arm9 => rom_paths.arm9_bin = file_name,
+                ModuleKind::Autoload(AutoloadKind::Itcm) => rom_paths.itcm.bin = file_name,
+                ModuleKind::Autoload(AutoloadKind::Dtcm) => rom_paths.dtcm.bin = file_name,
+                ModuleKind::Autoload(AutoloadKind::Unknown(_)) => {}
+                ModuleKind::Overlay(id) => {
+                    let index = rom.arm9_overlays().iter().position(|overlay| overlay.id() == id).unwrap();
+                    overlay_configs[index].file_name = file_name.to_string_lossy().into_owned();
+                }
+            }
+            log::info!("Patched {}", module.kind);
+        }
+
+        if let (Some(new_overlay), PatchRegion::NewOverlay { id, .. }) = (patcher.new_overlay(), &patch_config.region) {
+            if rom_paths.arm7_overlays.is_some() {
+                bail!("Cannot add an overlay to a ROM with ARM7 overlays, as their file IDs would change");
+            }
+            let Some(template) = rom.arm9_overlays().last() else {
+                bail!("Cannot add an overlay to a ROM without overlays");
+            };
+            let expected_id = overlay_configs.len() as u16;
+            if *id != expected_id {
+                bail!("New overlay has ID {id}, expected {expected_id} to follow the last overlay");
+            }
+
+            let file_name = format!("arm9_ov{id:03}.bin");
+            write_file(patch_path.join(&file_name), &new_overlay.binary)?;
+
+            // The patch objects have no static initializers, so the constructor range is empty
+            let mut info = template.info().clone();
+            info.id = (*id).into();
+            info.file_id = (*id).into();
+            info.base_address = new_overlay.base_address;
+            info.code_size = new_overlay.binary.len() as u32;
+            info.bss_size = 0;
+            info.ctor_start = new_overlay.base_address;
+            info.ctor_end = new_overlay.base_address;
+            info.compressed = false;
+            overlay_configs.push(OverlayConfig { info, file_name });
+            log::info!("Added overlay {id} at {:#010x}", new_overlay.base_address);
+        }
+
+        if !overlay_configs.is_empty() {
+            let yaml_path = patch_path.join("arm9_overlays.yaml");
+            serde_yml::to_writer(create_file(&yaml_path)?, &overlay_configs)?;
+            rom_paths.arm9_overlays = Some(PathBuf::from("arm9_overlays.yaml"));
+        }
+
+        let rom_paths_path = patch_path.join("rom_config.yaml");
+        serde_yml::to_writer(create_file(&rom_paths_path)?, &rom_paths)?;
+
+        let key =
+            if let Some(arm7_bios) = &self.arm7_bios { Some(BlowfishKey::from_arm7_bios_path(arm7_bios)?) } else { None };
+        let rom = match Rom::load(&rom_paths_path, RomLoadOptions { key: key.as_ref(), ..Default::default() }) {
+            Err(RomSaveError::BlowfishKeyNeeded) => {
+                bail!("The ROM is encrypted, please provide ARM7 BIOS");
+            }
+            result => result?,
+        };
+        let raw_rom = rom.build(key.as_ref())?;
+        raw_rom.save(&self.rom)?;
+
+        Ok(())
+    }
+}
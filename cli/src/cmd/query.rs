@@ -0,0 +1,207 @@
+use std::{fmt::Write as _, ops::Range, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::{Config, ConfigModule},
+    delinks::Delinks,
+    module::ModuleKind,
+    relocations::Relocations,
+    section::Section,
+    symbol::{Symbol, SymbolKind, SymbolMap, SymbolMaps},
+};
+
+use crate::{
+    config::{config::ConfigExt, relocation::RelocationModuleExt},
+    util::parse::parse_hex_address,
+};
+
+/// Shows what is at an address or symbol, and where it is referenced from.
+#[derive(Args)]
+pub struct Query {
+    /// Symbol name or address to query.
+    pub target: String,
+
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Name of the module to search in, such as `main`, `itcm` or `ov000`. Searches all modules by default.
+    #[arg(long, short = 'm')]
+    pub module: Option<String>,
+}
+
+/// A module with its delinks and relocations loaded.
+struct LoadedModule<'a> {
+    config: &'a ConfigModule,
+    kind: ModuleKind,
+    delinks: Delinks,
+    relocations: Relocations,
+}
+
+impl Query {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let mut modules = vec![];
+        for (module, kind) in config.iter_modules() {
+            modules.push(LoadedModule {
+                config: module,
+                kind,
+                delinks: Delinks::from_file(config_dir.join(&module.delinks), kind)?,
+                relocations: Relocations::from_file(config_dir.join(&module.relocations))?,
+            });
+        }
+
+        let matches = self.resolve(&modules, &symbol_maps)?;
+        if matches.is_empty() {
+            bail!("No symbol or module found for '{}'", self.target);
+        }
+
+        let mut output = String::new();
+        for (i, (module, address)) in matches.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(output)?;
+            }
+            Self::describe(&mut output, module, address, &modules, &symbol_maps)?;
+        }
+        print!("{output}");
+
+        Ok(())
+    }
+
+    /// Finds the modules and addresses that the target refers to. A symbol name may be defined in several modules, and an
+    /// address may be in several overlays which share the same memory.
+    fn resolve<'a, 'b>(
+        &self,
+        modules: &'b [LoadedModule<'a>],
+        symbol_maps: &SymbolMaps,
+    ) -> Result<Vec<(&'b LoadedModule<'a>, u32)>> {
+        let modules = modules.iter().filter(|module| self.module.as_ref().map_or(true, |name| &module.config.name == name));
+
+        let mut matches = vec![];
+        for module in modules.clone() {
+            let symbol_map = symbol_maps.get(module.kind).with_context(|| format!("No symbol map for {}", module.kind))?;
+            if let Some(symbols) = symbol_map.for_name(&self.target) {
+                matches.extend(symbols.map(|(_, symbol)| (module, symbol.addr)));
+            }
+        }
+        if !matches.is_empty() {
+            return Ok(matches);
+        }
+
+        let Ok(address) = parse_hex_address(&self.target) else { return Ok(matches) };
+        matches.extend(
+            modules
+                .filter(|module| module.delinks.sections.get_by_contained_address(address).is_some())
+                .map(|module| (module, address)),
+        );
+        Ok(matches)
+    }
+
+    fn describe(
+        output: &mut String,
+        module: &LoadedModule,
+        address: u32,
+        modules: &[LoadedModule],
+        symbol_maps: &SymbolMaps,
+    ) -> Result<()> {
+        let symbol_map = symbol_maps.get(module.kind).unwrap();
+        writeln!(output, "{:#010x} in {} ({})", address, module.config.name, module.kind)?;
+
+        let overlapping = modules
+            .iter()
+            .filter(|other| other.kind != module.kind && other.delinks.sections.get_by_contained_address(address).is_some())
+            .map(|other| other.config.name.as_str())
+            .collect::<Vec<_>>();
+        if !overlapping.is_empty() {
+            writeln!(output, "  Also loaded here: {}", overlapping.join(", "))?;
+        }
+
+        let Some((_, section)) = module.delinks.sections.get_by_contained_address(address) else {
+            writeln!(output, "  Outside of the module's sections")?;
+            return Ok(());
+        };
+        let file = module
+            .delinks
+            .files
+            .iter()
+            .find(|file| file.sections.iter().any(|s| s.address_range().contains(&address)))
+            .map(|file| file.name.as_str())
+            .unwrap_or("none");
+        writeln!(output, "  Section: {}", section.name())?;
+        writeln!(output, "  File: {file}")?;
+
+        if let Some(symbols) = symbol_map.for_address(address) {
+            for (_, symbol) in symbols {
+                writeln!(output, "  Symbol: {symbol}")?;
+            }
+        }
+
+        let containing = Self::containing_symbol(symbol_map, section, address);
+        if let Some((symbol, _)) = containing {
+            if symbol.addr != address {
+                writeln!(output, "  Inside: {}+{:#x}", symbol.name, address - symbol.addr)?;
+            }
+        }
+        if let Some((_, function)) = symbol_map.get_function_containing(address) {
+            writeln!(output, "  Function: {}", function.name)?;
+        }
+
+        let (name, range) = match containing {
+            Some((symbol, range)) => (symbol.name.as_str(), range),
+            None => ("address", address..address + 1),
+        };
+        writeln!(output, "  References to {name}:")?;
+        let mut found = false;
+        for other in modules {
+            let other_symbol_map = symbol_maps.get(other.kind).unwrap();
+            for relocation in other.relocations.iter() {
+                let to = relocation.to_address();
+                if !range.contains(&to) && !range.contains(&(to & !1)) {
+                    continue;
+                }
+                if !relocation.module().points_to(module.kind) {
+                    continue;
+                }
+
+                let from = relocation.from_address();
+                write!(output, "    {:#010x} in {}, {}", from, other.config.name, relocation.kind())?;
+                if let Some((_, function)) = other_symbol_map.get_function_containing(from) {
+                    write!(output, " from {}", function.name)?;
+                } else if let Some((_, from_section)) = other.delinks.sections.get_by_contained_address(from) {
+                    if let Some((symbol, _)) = Self::containing_symbol(other_symbol_map, from_section, from) {
+                        write!(output, " from {}", symbol.name)?;
+                    }
+                }
+                writeln!(output)?;
+                found = true;
+            }
+        }
+        if !found {
+            writeln!(output, "    none")?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the function, data or .bss symbol which contains the address, along with its address range.
+    pub(crate) fn containing_symbol<'a>(
+        symbol_map: &'a SymbolMap,
+        section: &Section,
+        address: u32,
+    ) -> Option<(&'a Symbol, Range<u32>)> {
+        let is_sized =
+            |symbol: &&Symbol| matches!(symbol.kind, SymbolKind::Function(_) | SymbolKind::Data(_) | SymbolKind::Bss(_));
+        let symbol = symbol_map.iter_by_address(section.start_address()..address + 1).filter(is_sized).last()?;
+        let next_address = symbol_map
+            .iter_by_address(symbol.addr + 1..section.end_address())
+            .find(is_sized)
+            .map(|next| next.addr)
+            .unwrap_or(section.end_address());
+        let end_address = symbol.addr + symbol.size(next_address).min(next_address - symbol.addr).max(1);
+        (address < end_address).then_some((symbol, symbol.addr..end_address))
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::{Config, ConfigModule},
+    delinks::Delinks,
+    module::ModuleKind,
+    symbol::{Symbol, SymbolIndex, SymbolKind, SymbolMaps},
+};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+
+use crate::{
+    config::config::ConfigExt,
+    util::{io::read_to_string, parse::parse_hex_address},
+};
+
+/// Renames a symbol, or renames symbols in bulk with a rules file.
+#[derive(Args)]
+pub struct Rename {
+    /// Name or address of the symbol to rename.
+    #[arg(required_unless_present = "rules", conflicts_with = "rules", requires = "new_name")]
+    pub symbol: Option<String>,
+
+    /// New name of the symbol.
+    pub new_name: Option<String>,
+
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to a rules file, to rename every symbol matching the rules.
+    #[arg(long, short = 'r')]
+    pub rules: Option<PathBuf>,
+
+    /// Name of the module to rename in, such as `main`, `itcm` or `ov000`. Searches all modules by default.
+    #[arg(long, short = 'm')]
+    pub module: Option<String>,
+
+    /// Dry run, print the changes to symbols.txt instead of writing them.
+    #[arg(long, short = 'd')]
+    pub dry: bool,
+}
+
+/// A symbol to be renamed.
+struct SymbolRename<'a> {
+    module: &'a ConfigModule,
+    module_kind: ModuleKind,
+    index: SymbolIndex,
+    symbol: &'a Symbol,
+    new_name: String,
+}
+
+/// A line in a rules file. Every function, data and .bss symbol which matches all of the rule's filters is renamed by its
+/// action.
+struct RenameRule {
+    /// Glob pattern of the symbol name
+    name: Option<GlobMatcher>,
+    /// Regex pattern of the symbol name
+    regex: Option<Regex>,
+    /// Name of the module containing the symbol
+    module: Option<String>,
+    /// Glob pattern of the delink file containing the symbol
+    file: Option<GlobMatcher>,
+    action: RenameAction,
+}
+
+enum RenameAction {
+    /// Adds a prefix to the name, unless the name already has it
+    Prefix(String),
+    /// Replaces the first regex match with a replacement string, which can refer to capture groups like `$1`
+    Replace(String),
+}
+
+impl Rename {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let delinks = config
+            .iter_modules()
+            .map(|(module, module_kind)| Ok((module_kind, Delinks::from_file(config_dir.join(&module.delinks), module_kind)?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let renames = match &self.rules {
+            Some(rules_path) => {
+                let rules = RenameRule::parse_file(rules_path)?;
+                self.rename_by_rules(&config, &delinks, &symbol_maps, &rules)?
+            }
+            None => vec![self.rename_single(&config, &symbol_maps)?],
+        };
+        Self::check_collisions(&config, &delinks, &symbol_maps, &renames)?;
+
+        if self.dry {
+            print!("{}", Self::diff(&renames)?);
+            return Ok(());
+        }
+
+        // Symbols are renamed by index, as there may be multiple symbols at an address
+        let num_renames = renames.len();
+        let mut renames_by_module = BTreeMap::<ModuleKind, Vec<(SymbolIndex, String)>>::new();
+        for rename in renames {
+            renames_by_module.entry(rename.module_kind).or_default().push((rename.index, rename.new_name));
+        }
+        let mut symbol_maps = symbol_maps;
+        for (module_kind, renames) in &renames_by_module {
+            let renames = renames.iter().map(|(index, new_name)| (*index, new_name.as_str())).collect::<Vec<_>>();
+            symbol_maps.get_mut(*module_kind).rename_many(&renames)?;
+        }
+        symbol_maps.to_files(&config, config_dir)?;
+        log::info!("Renamed {num_renames} symbols");
+
+        Ok(())
+    }
+
+    fn modules<'a>(&'a self, config: &'a Config) -> impl Iterator<Item = (&'a ConfigModule, ModuleKind)> + 'a {
+        config.iter_modules().filter(|(module, _)| self.module.as_ref().map_or(true, |name| &module.name == name))
+    }
+
+    fn rename_single<'a>(&'a self, config: &'a Config, symbol_maps: &'a SymbolMaps) -> Result<SymbolRename<'a>> {
+        let target = self.symbol.as_ref().unwrap();
+        let new_name = self.new_name.clone().unwrap();
+
+        let mut found = vec![];
+        for (module, module_kind) in self.modules(config) {
+            let symbol_map = symbol_maps.get(module_kind).with_context(|| format!("No symbol map for {module_kind}"))?;
+            if let Some(symbols) = symbol_map.for_name(target) {
+                found.extend(symbols.map(|(index, symbol)| (module, module_kind, index, symbol)));
+            }
+        }
+        if found.is_empty() {
+            if let Ok(address) = parse_hex_address(target) {
+                for (module, module_kind) in self.modules(config) {
+                    let symbol_map = symbol_maps.get(module_kind).unwrap();
+                    if let Some((index, symbol)) = symbol_map.by_address(address)? {
+                        found.push((module, module_kind, index, symbol));
+                    }
+                }
+            }
+        }
+
+        match found.as_slice() {
+            [] => bail!("Symbol '{target}' not found"),
+            &[(module, module_kind, index, symbol)] => Ok(SymbolRename { module, module_kind, index, symbol, new_name }),
+            _ => {
+                let modules = found.iter().map(|(module, _, _, _)| module.name.as_str()).collect::<Vec<_>>().join(", ");
+                bail!("'{target}' matches {} symbols ({modules}), use --module or an address to choose one", found.len())
+            }
+        }
+    }
+
+    fn rename_by_rules<'a>(
+        &'a self,
+        config: &'a Config,
+        delinks: &BTreeMap<ModuleKind, Delinks>,
+        symbol_maps: &'a SymbolMaps,
+        rules: &[RenameRule],
+    ) -> Result<Vec<SymbolRename<'a>>> {
+        let mut renames = vec![];
+        for (module, module_kind) in self.modules(config) {
+            let rules =
+                rules.iter().filter(|rule| rule.module.as_ref().map_or(true, |name| &module.name == name)).collect::<Vec<_>>();
+            if rules.is_empty() {
+                continue;
+            }
+
+            let delinks = &delinks[&module_kind];
+            let symbol_map = symbol_maps.get(module_kind).with_context(|| format!("No symbol map for {module_kind}"))?;
+            let mut symbols = symbol_map
+                .iter()
+                .filter(|(_, symbol)| {
+                    matches!(symbol.kind, SymbolKind::Function(_) | SymbolKind::Data(_) | SymbolKind::Bss(_))
+                })
+                .collect::<Vec<_>>();
+            symbols.sort_by_key(|(_, symbol)| symbol.addr);
+            for (index, symbol) in symbols {
+                let file_name = Self::file_name(delinks, symbol);
+
+                let mut name = symbol.name.clone();
+                for rule in &rules {
+                    name = rule.apply(&name, file_name);
+                }
+                if name != symbol.name {
+                    renames.push(SymbolRename { module, module_kind, index, symbol, new_name: name });
+                }
+            }
+        }
+        Ok(renames)
+    }
+
+    /// Fails if any new name is already used by another symbol, or if two symbols are renamed to the same name. Local symbols
+    /// only collide with symbols in the same file, while global symbols also collide with each other.
+    fn check_collisions(
+        config: &Config,
+        delinks: &BTreeMap<ModuleKind, Delinks>,
+        symbol_maps: &SymbolMaps,
+        renames: &[SymbolRename],
+    ) -> Result<()> {
+        let scope = |module_kind: ModuleKind, symbol: &Symbol| (module_kind, Self::file_name(&delinks[&module_kind], symbol));
+        let collides = |rename: &SymbolRename, module_kind: ModuleKind, symbol: &Symbol| {
+            (!rename.symbol.local && !symbol.local) || scope(rename.module_kind, rename.symbol) == scope(module_kind, symbol)
+        };
+
+        let renamed = renames.iter().map(|rename| (rename.module_kind, rename.index)).collect::<BTreeSet<_>>();
+        let mut new_names = HashMap::<&str, Vec<&SymbolRename>>::new();
+        let mut conflicts = vec![];
+        for rename in renames {
+            let others = new_names.entry(&rename.new_name).or_default();
+            for other in others.iter().filter(|other| collides(rename, other.module_kind, other.symbol)) {
+                conflicts.push(format!(
+                    "'{}' and '{}' would both be renamed to '{}'",
+                    other.symbol.name, rename.symbol.name, rename.new_name
+                ));
+            }
+            others.push(rename);
+
+            for (module, module_kind) in config.iter_modules() {
+                let Some(symbols) = symbol_maps.get(module_kind).and_then(|symbol_map| symbol_map.for_name(&rename.new_name))
+                else {
+                    continue;
+                };
+                for (index, symbol) in symbols {
+                    // Symbols being renamed free up their names
+                    if renamed.contains(&(module_kind, index)) || !collides(rename, module_kind, symbol) {
+                        continue;
+                    }
+                    conflicts.push(format!(
+                        "Cannot rename '{}' to '{}', the name is already used by the symbol at {:#010x} in {}",
+                        rename.symbol.name, rename.new_name, symbol.addr, module.name
+                    ));
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            for conflict in &conflicts {
+                log::error!("{conflict}");
+            }
+            bail!("Found {} naming conflicts, no symbols were renamed", conflicts.len());
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the delink file containing a symbol, if any.
+    fn file_name<'a>(delinks: &'a Delinks, symbol: &Symbol) -> Option<&'a str> {
+        delinks
+            .files
+            .iter()
+            .find(|file| file.sections.get_by_contained_address(symbol.addr).is_some())
+            .map(|file| file.name.as_str())
+    }
+
+    /// Renders the renames as a diff of the symbols.txt files.
+    fn diff(renames: &[SymbolRename]) -> Result<String> {
+        let mut diff = String::new();
+        let mut current_module = None;
+        for rename in renames {
+            if current_module != Some(rename.module_kind) {
+                current_module = Some(rename.module_kind);
+                let path = rename.module.symbols.display();
+                writeln!(diff, "--- {path}")?;
+                writeln!(diff, "+++ {path}")?;
+            }
+            let line = rename.symbol.to_string();
+            writeln!(diff, "-{line}")?;
+            writeln!(diff, "+{}{}", rename.new_name, &line[rename.symbol.name.len()..])?;
+        }
+        Ok(diff)
+    }
+}
+
+impl RenameRule {
+    fn parse_file(path: &Path) -> Result<Vec<Self>> {
+        let text = read_to_string(path)?;
+        let mut rules = vec![];
+        for (row, line) in text.lines().enumerate() {
+            let line = line.split_once("//").map_or(line, |(line, _)| line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let rule = Self::parse(line).with_context(|| format!("{}:{}: failed to parse rule", path.display(), row + 1))?;
+            rules.push(rule);
+        }
+        Ok(rules)
+    }
+
+    fn parse(line: &str) -> Result<Self> {
+        let mut name = None;
+        let mut regex = None;
+        let mut module = None;
+        let mut file = None;
+        let mut action = None;
+        for pair in line.split_whitespace() {
+            let Some((key, value)) = pair.split_once(':') else {
+                bail!("expected 'key:value' but got '{pair}'");
+            };
+            match key {
+                "name" => name = Some(Glob::new(value)?.compile_matcher()),
+                "regex" => regex = Some(Regex::new(value)?),
+                "module" => module = Some(value.to_string()),
+                "file" => file = Some(Glob::new(value)?.compile_matcher()),
+                "prefix" | "replace" if action.is_some() => bail!("a rule can only have one of 'prefix' and 'replace'"),
+                "prefix" => action = Some(RenameAction::Prefix(value.to_string())),
+                "replace" => action = Some(RenameAction::Replace(value.to_string())),
+                _ => bail!("unknown key '{key}', must be one of: name, regex, module, file, prefix, replace"),
+            }
+        }
+
+        let Some(action) = action else { bail!("a rule must have one of 'prefix' and 'replace'") };
+        if matches!(action, RenameAction::Replace(_)) && regex.is_none() {
+            bail!("'replace' requires a 'regex' pattern");
+        }
+        Ok(Self { name, regex, module, file, action })
+    }
+
+    /// Returns the new name of a symbol, which is unchanged if the rule doesn't match.
+    fn apply(&self, name: &str, file_name: Option<&str>) -> String {
+        let matches = self.name.as_ref().map_or(true, |glob| glob.is_match(name))
+            && self.regex.as_ref().map_or(true, |regex| regex.is_match(name))
+            && self.file.as_ref().map_or(true, |glob| file_name.is_some_and(|file_name| glob.is_match(file_name)));
+        if !matches {
+            return name.to_string();
+        }
+
+        match &self.action {
+            RenameAction::Prefix(prefix) if name.starts_with(prefix.as_str()) => name.to_string(),
+            RenameAction::Prefix(prefix) => format!("{prefix}{name}"),
+            RenameAction::Replace(replacement) => {
+                self.regex.as_ref().unwrap().replace(name, replacement.as_str()).into_owned()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rules() {
+        let rule = RenameRule::parse("name:func_* module:main file:src/foo.c prefix:Foo_").unwrap();
+        assert!(rule.name.is_some());
+        assert!(rule.regex.is_none());
+        assert_eq!(rule.module.as_deref(), Some("main"));
+        assert!(rule.file.is_some());
+        assert!(matches!(&rule.action, RenameAction::Prefix(prefix) if prefix == "Foo_"));
+
+        let rule = RenameRule::parse("regex:^func_(.*)$ replace:Foo_$1").unwrap();
+        assert!(rule.regex.is_some());
+        assert!(matches!(&rule.action, RenameAction::Replace(replacement) if replacement == "Foo_$1"));
+    }
+
+    #[test]
+    fn parse_invalid_rules() {
+        let error = |line: &str| RenameRule::parse(line).err().unwrap().to_string();
+        assert!(error("name:func_*").contains("must have one of"));
+        assert!(error("replace:Foo_").contains("requires a 'regex'"));
+        assert!(error("prefix:Foo_ replace:Bar_").contains("only have one of"));
+        assert!(error("prefix:Foo_ kind:function").contains("unknown key"));
+        assert!(error("prefix").contains("expected 'key:value'"));
+        assert!(RenameRule::parse("regex:( replace:Foo_").is_err());
+    }
+
+    #[test]
+    fn apply_prefix() {
+        let rule = RenameRule::parse("name:func_* prefix:Foo_").unwrap();
+        assert_eq!(rule.apply("func_02000000", None), "Foo_func_02000000");
+        assert_eq!(rule.apply("data_02000000", None), "data_02000000");
+
+        let rule = RenameRule::parse("prefix:Foo_").unwrap();
+        assert_eq!(rule.apply("Foo_Init", None), "Foo_Init");
+    }
+
+    #[test]
+    fn apply_replace() {
+        let rule = RenameRule::parse("regex:^func_(.*)$ replace:Foo_$1").unwrap();
+        assert_eq!(rule.apply("func_02000000", None), "Foo_02000000");
+        assert_eq!(rule.apply("data_02000000", None), "data_02000000");
+    }
+
+    #[test]
+    fn apply_file_filter() {
+        let rule = RenameRule::parse("file:src/foo/* prefix:Foo_").unwrap();
+        assert_eq!(rule.apply("Init", Some("src/foo/init.c")), "Foo_Init");
+        assert_eq!(rule.apply("Init", Some("src/bar/init.c")), "Init");
+        assert_eq!(rule.apply("Init", None), "Init");
+    }
+}
@@ -1,9 +1,10 @@
 use std::{
+    fs,
     ops::Range,
     path::{Path, PathBuf},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Args;
 use ds_decomp::config::{
     config::Config,
@@ -17,8 +18,11 @@ use path_slash::PathExt;
 use pathdiff::diff_paths;
 
 use crate::{
-    config::section::SectionExt,
-    util::io::{create_file, read_file},
+    config::{
+        config::{new_section_boundary_name, ConfigNewOverlayExt, NEW_OVERLAY_SECTIONS},
+        section::SectionExt,
+    },
+    util::io::{create_dir_all, create_file, mirror_dir, read_file, read_to_string, write_file},
 };
 
 /// Creates a configuration to build a ROM from linked binaries.
@@ -48,7 +52,7 @@ impl ConfigRom {
         let main_module_path = config_path.join(&config.main_module.object);
         let new_rom_paths_dir = main_module_path.parent().unwrap();
 
-        self.update_relative_paths(&mut rom_paths, old_rom_paths_dir, new_rom_paths_dir);
+        Self::update_relative_paths(&mut rom_paths, old_rom_paths_dir, new_rom_paths_dir);
 
         let file = read_file(&self.elf)?;
         let object = object::File::parse(&*file)?;
@@ -56,13 +60,16 @@ impl ConfigRom {
         self.config_arm9(&object, &config, &rom, &mut rom_paths, new_rom_paths_dir)?;
         self.config_autoloads(&object, &config, &rom, &mut rom_paths, new_rom_paths_dir)?;
         self.config_overlays(&object, &config, &rom, &mut rom_paths, new_rom_paths_dir)?;
+        self.config_new_files(&config, &mut rom_paths, new_rom_paths_dir)?;
 
         serde_yml::to_writer(create_file(new_rom_paths_dir.join("rom_config.yaml"))?, &rom_paths)?;
 
         Ok(())
     }
 
-    fn update_relative_paths(&self, rom_paths: &mut RomConfig, old: &Path, new: &Path) {
+    /// Updates the paths in `rom_paths` which are relative to `old`, so that they are relative to `new` instead. Paths to
+    /// the ARM9 program, autoloads and overlays are not updated, as they are replaced by the linked binaries.
+    pub(crate) fn update_relative_paths(rom_paths: &mut RomConfig, old: &Path, new: &Path) {
         let RomConfig {
             // Update these paths
             arm7_bin,
@@ -108,6 +115,7 @@ impl ConfigRom {
     ) -> Result<()> {
         let config_path = self.config.parent().unwrap();
 
+        let yaml_path = config_path.join(&config.main_module.object).parent().unwrap().join("arm9_overlays.yaml");
         let mut overlay_configs = vec![];
         for overlay in &config.overlays {
             let delinks = Delinks::from_file(config_path.join(&overlay.module.delinks), ModuleKind::Overlay(overlay.id))?;
@@ -142,8 +150,8 @@ impl ConfigRom {
             info.compressed = rom_overlay.originally_compressed();
             overlay_configs.push(OverlayConfig { info, file_name });
         }
+        self.config_new_overlays(object, config, rom, rom_paths, &yaml_path, &mut overlay_configs)?;
 
-        let yaml_path = config_path.join(&config.main_module.object).parent().unwrap().join("arm9_overlays.yaml");
         serde_yml::to_writer(create_file(&yaml_path)?, &overlay_configs)?;
 
         rom_paths.arm9_overlays = Some(Self::make_path(yaml_path, rom_paths_dir));
@@ -151,6 +159,126 @@ impl ConfigRom {
         Ok(())
     }
 
+    /// Adds the overlays which are not in the original ROM to the overlay table. Their IDs must continue from the last
+    /// overlay in the ROM, and they are given the file IDs right after the original overlays.
+    fn config_new_overlays(
+        &self,
+        object: &object::File<'_>,
+        config: &Config,
+        rom: &Rom<'_>,
+        rom_paths: &RomConfig,
+        yaml_path: &Path,
+        overlay_configs: &mut Vec<OverlayConfig>,
+    ) -> Result<()> {
+        if config.new_overlays.is_empty() {
+            return Ok(());
+        }
+        if rom_paths.arm7_overlays.is_some() {
+            bail!("Cannot add overlays to a ROM with ARM7 overlays, as their file IDs would change");
+        }
+        let Some(template) = rom.arm9_overlays().last() else {
+            bail!("Cannot add overlays to a ROM without overlays");
+        };
+        let config_path = self.config.parent().unwrap();
+
+        let num_overlays = rom.arm9_overlays().len();
+        for (i, overlay) in config.new_overlays.iter().enumerate() {
+            let expected_id = (num_overlays + i) as u16;
+            if overlay.id != expected_id {
+                bail!(
+                    "New overlay '{}' has ID {}, expected {expected_id} to follow the previous overlay",
+                    overlay.name,
+                    overlay.id
+                );
+            }
+
+            let memory_name = overlay.memory_name();
+            let section_range = |initialized: bool| -> Result<Option<Range<u32>>> {
+                let mut range: Option<Range<u32>> = None;
+                for (section_name, kind, _) in NEW_OVERLAY_SECTIONS {
+                    if kind.is_initialized() != initialized {
+                        continue;
+                    }
+                    let boundary_name = new_section_boundary_name(section_name);
+                    let start = Self::symbol_address(object, &format!("{memory_name}_{boundary_name}_START"))?;
+                    let end = Self::symbol_address(object, &format!("{memory_name}_{boundary_name}_END"))?;
+                    range = Some(match range {
+                        Some(range) => range.start.min(start)..range.end.max(end),
+                        None => start..end,
+                    });
+                }
+                Ok(range)
+            };
+            let code_range = section_range(true)?.unwrap();
+            let bss_range = section_range(false)?.unwrap();
+
+            let mut info = template.info().clone();
+            info.id = overlay.id.into();
+            info.file_id = expected_id.into();
+            info.base_address = code_range.start;
+            info.code_size = code_range.len() as u32;
+            info.bss_size = bss_range.len() as u32;
+            info.ctor_start = Self::symbol_address(object, &format!("{memory_name}_CTOR_START"))?;
+            info.ctor_end = Self::symbol_address(object, &format!("{memory_name}_CTOR_END"))?;
+            info.compressed = false;
+
+            let file_name = Self::make_path(config_path.join(&overlay.object), yaml_path.parent().unwrap());
+            overlay_configs.push(OverlayConfig { info, file_name: file_name.to_string_lossy().into_owned() });
+        }
+
+        Ok(())
+    }
+
+    /// Adds new files to the file system. The original files are mirrored into a new directory next to the linked binaries,
+    /// so that the extracted ROM is left untouched. A new file replaces an original file at the same path.
+    fn config_new_files(&self, config: &Config, rom_paths: &mut RomConfig, rom_paths_dir: &Path) -> Result<()> {
+        if config.new_files.is_empty() {
+            return Ok(());
+        }
+        let config_path = self.config.parent().unwrap();
+
+        let old_files_dir = rom_paths_dir.join(&rom_paths.files_dir);
+        let new_files_dir = rom_paths_dir.join("files");
+        if new_files_dir.exists() {
+            if old_files_dir.canonicalize()?.starts_with(new_files_dir.canonicalize()?) {
+                bail!("Cannot add files to {}, as it contains the original files", new_files_dir.display());
+            }
+            // Start over, so that files added by earlier runs don't linger
+            fs::remove_dir_all(&new_files_dir)?;
+        }
+        mirror_dir(&old_files_dir, &new_files_dir)?;
+
+        let old_path_order = read_to_string(rom_paths_dir.join(&rom_paths.path_order))?;
+        let mut path_order = old_path_order.lines().map(|line| line.to_string()).collect::<Vec<_>>();
+        let leading_slash = path_order.first().is_some_and(|line| line.starts_with('/'));
+        for file in &config.new_files {
+            let file_path = file.path.trim_start_matches('/');
+            let target_path = new_files_dir.join(file_path);
+            if old_files_dir.join(file_path).exists() {
+                log::info!("Replacing {file_path} in the file system");
+                // The mirrored file may be hard linked to the original, so it must not be overwritten in place
+                fs::remove_file(&target_path)?;
+            } else {
+                path_order.push(if leading_slash { format!("/{file_path}") } else { file_path.to_string() });
+            }
+            create_dir_all(target_path.parent().unwrap())?;
+            fs::copy(config_path.join(&file.source), &target_path)
+                .with_context(|| format!("Failed to copy {} to the file system", file.source.display()))?;
+        }
+
+        let path_order_path = rom_paths_dir.join("path_order.txt");
+        write_file(&path_order_path, path_order.join("\n") + "\n")?;
+
+        rom_paths.files_dir = Self::make_path(new_files_dir, rom_paths_dir);
+        rom_paths.path_order = Self::make_path(path_order_path, rom_paths_dir);
+
+        Ok(())
+    }
+
+    fn symbol_address(object: &object::File<'_>, name: &str) -> Result<u32> {
+        Ok(object.symbol_by_name(name).with_context(|| format!("Failed to find symbol {name}"))?.address() as u32)
+    }
+
     fn config_autoloads(
         &self,
         object: &object::File<'_>,
@@ -265,7 +393,7 @@ impl ConfigRom {
             .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end)))
     }
 
-    fn make_path<P: AsRef<Path>, B: AsRef<Path>>(path: P, base: B) -> PathBuf {
+    pub(crate) fn make_path<P: AsRef<Path>, B: AsRef<Path>>(path: P, base: B) -> PathBuf {
         PathBuf::from(diff_paths(path, &base).unwrap().to_slash_lossy().as_ref())
     }
 }
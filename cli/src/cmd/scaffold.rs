@@ -0,0 +1,381 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::{Config, ConfigModule},
+    delinks::{DelinkFile, Delinks},
+    module::{Module, ModuleKind},
+    section::{Section, SectionKind},
+    symbol::{InstructionMode, Symbol, SymbolKind, SymbolMaps},
+};
+use ds_rom::rom::{Rom, RomLoadOptions};
+
+use crate::{
+    analysis::functions::FunctionExt,
+    config::{
+        config::{ConfigExt, ConfigModuleExt},
+        relocation::RelocationModuleExt,
+        symbol::SymbolLookup,
+    },
+    util::{
+        asm::AsmTarget,
+        io::{create_dir_all, write_file},
+    },
+};
+
+/// Generates a C/C++ source file for a delink file, with every function as an `asm` function.
+#[derive(Args)]
+pub struct Scaffold {
+    /// Name of the file in delinks.txt, such as `main/Foo.cpp`.
+    pub file_name: String,
+
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to the source directory, where the file will be created.
+    #[arg(long, short = 's')]
+    pub source_path: PathBuf,
+
+    /// Overwrite the source file if it already exists.
+    #[arg(long, short = 'f')]
+    pub force: bool,
+}
+
+/// C declaration of a data symbol.
+struct DataDeclaration {
+    element_type: &'static str,
+    length: u32,
+    constant: bool,
+    /// Whether the symbol is local to the file, which makes it `static`
+    local: bool,
+}
+
+impl Scaffold {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let mut symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let rom = Rom::load(
+            config_dir.join(&config.rom_config),
+            RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false },
+        )?;
+
+        let (module_config, module_kind) = self.find_file(config_dir, &config)?;
+        let rom_autoloads = rom.arm9().autoloads()?;
+        let code = match module_kind {
+            ModuleKind::Arm9 => rom.arm9().code()?,
+            ModuleKind::Autoload(kind) => rom_autoloads
+                .iter()
+                .find(|a| a.kind() == kind)
+                .with_context(|| format!("Autoload {kind} not present in ROM"))?
+                .code(),
+            ModuleKind::Overlay(id) => {
+                rom.arm9_overlays().get(id as usize).with_context(|| format!("Overlay {id} not present in ROM"))?.code()
+            }
+        };
+
+        let (module, files) = module_config.load_module(config_dir, module_kind, code, &mut symbol_maps)?;
+        let file = files.iter().find(|f| f.name == self.file_name).unwrap();
+
+        let (file_path, extension) = file.split_file_ext();
+        let extension = if extension.is_empty() { "c" } else { extension };
+        let cpp = extension == "cpp" || extension == "cc" || extension == "cxx";
+        let path = self.source_path.join(format!("{file_path}.{extension}"));
+        if path.exists() && !self.force {
+            bail!("Source file '{}' already exists, use --force to overwrite it", path.display());
+        }
+
+        let source = self.scaffold(&module, file, &symbol_maps, cpp)?;
+
+        create_dir_all(path.parent().unwrap())?;
+        write_file(&path, source)?;
+        log::info!("Created '{}'", path.display());
+
+        Ok(())
+    }
+
+    fn find_file<'a>(&self, config_dir: &Path, config: &'a Config) -> Result<(&'a ConfigModule, ModuleKind)> {
+        for (module, module_kind) in config.iter_modules() {
+            let delinks = Delinks::from_file(config_dir.join(&module.delinks), module_kind)?;
+            if delinks.files.iter().any(|f| f.name == self.file_name) {
+                return Ok((module, module_kind));
+            }
+        }
+        bail!("File '{}' not found in any delinks.txt", self.file_name);
+    }
+
+    fn scaffold(&self, module: &Module, file: &DelinkFile, symbol_maps: &SymbolMaps, cpp: bool) -> Result<String> {
+        let symbol_map = symbol_maps.get(module.kind()).unwrap();
+        let symbol_lookup = SymbolLookup {
+            module_kind: module.kind(),
+            symbol_map,
+            symbol_maps,
+            relocations: module.relocations(),
+            asm_target: AsmTarget::MwccInline,
+        };
+
+        let mut source = String::new();
+        writeln!(source, "// Generated by dsd scaffold from {}", file.name)?;
+        writeln!(source)?;
+        if cpp {
+            // Keeps the symbol names from symbols.txt instead of mangling them again
+            writeln!(source, "extern \"C\" {{")?;
+            writeln!(source)?;
+        }
+
+        // Declare external symbols
+        let externs = self.external_symbols(module, file, symbol_maps)?;
+        if !externs.is_empty() {
+            for declaration in externs.values() {
+                writeln!(source, "{declaration}")?;
+            }
+            writeln!(source)?;
+        }
+
+        // Declare the file's own symbols, so that functions can refer to symbols defined after them
+        let sections = file.sections.sorted_by_address();
+        let mut data_declarations = BTreeMap::new();
+        for section in &sections {
+            let code = section.code_from_module(module)?;
+            let mut symbols = symbol_map.iter_by_address(section.address_range()).peekable();
+            while let Some(symbol) = symbols.next() {
+                let max_address = symbols.peek().map(|s| s.addr).unwrap_or(section.end_address());
+                match symbol.kind {
+                    SymbolKind::Function(_) => writeln!(source, "{}void {}(void);", Self::storage(symbol), symbol.name)?,
+                    // Data in code sections are inline tables, which are written as part of the functions
+                    SymbolKind::Data(_) | SymbolKind::Bss(_) if section.kind() != SectionKind::Code => {
+                        let declaration = Self::data_declaration(module, section, symbol, max_address, code.is_some());
+                        writeln!(source, "{};", declaration.display(&symbol.name, false))?;
+                        data_declarations.insert(symbol.addr, declaration);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        writeln!(source)?;
+
+        for section in &sections {
+            let code = section.code_from_module(module)?;
+            match (section.kind(), section.name()) {
+                (SectionKind::Code, ".text") => {
+                    for symbol in symbol_map.iter_by_address(section.address_range()) {
+                        let SymbolKind::Function(function) = symbol.kind else { continue };
+                        self.write_function(&mut source, module, symbol, function.mode, &symbol_lookup)?;
+                    }
+                }
+                (SectionKind::Data, ".data" | ".rodata") | (SectionKind::Bss, ".bss") => {
+                    for symbol in symbol_map.iter_by_address(section.address_range()) {
+                        let Some(declaration) = data_declarations.get(&symbol.addr) else { continue };
+                        let bytes = code.map(|code| {
+                            let start = (symbol.addr - section.start_address()) as usize;
+                            &code[start..start + declaration.size() as usize]
+                        });
+                        self.write_data(&mut source, module, symbol, declaration, bytes, symbol_maps)?;
+                    }
+                }
+                _ => {
+                    log::warn!("Section {} of {} must be added manually", section.name(), file.name);
+                    writeln!(source, "// TODO: section {} at {:#010x}", section.name(), section.start_address())?;
+                    writeln!(source)?;
+                }
+            }
+        }
+
+        if cpp {
+            writeln!(source, "}}")?;
+        }
+
+        Ok(source)
+    }
+
+    /// Returns declarations of all symbols outside the file which are referenced by it, sorted by name.
+    fn external_symbols(
+        &self,
+        module: &Module,
+        file: &DelinkFile,
+        symbol_maps: &SymbolMaps,
+    ) -> Result<BTreeMap<String, String>> {
+        let mut externs = BTreeMap::new();
+        for section in file.sections.iter() {
+            for (_, relocation) in module.relocations().iter_range(section.address_range()) {
+                let Some(module_kind) = relocation.module().first_module() else { continue };
+                let internal = module_kind == module.kind()
+                    && file.sections.iter().any(|s| s.address_range().contains(&relocation.to_address()));
+                if internal {
+                    continue;
+                }
+
+                let (symbol, _) = Self::relocation_symbol(symbol_maps, module_kind, relocation.to_address())?;
+                let declaration = match symbol.kind {
+                    SymbolKind::Function(_) => format!("void {}(void);", symbol.name),
+                    _ => format!("extern unsigned char {}[];", symbol.name),
+                };
+                externs.insert(symbol.name.clone(), declaration);
+            }
+        }
+        Ok(externs)
+    }
+
+    /// Returns the symbol which a relocation points to, and the offset into it for relocations into the middle of a symbol.
+    fn relocation_symbol(symbol_maps: &SymbolMaps, module_kind: ModuleKind, address: u32) -> Result<(&Symbol, u32)> {
+        let symbol_map = symbol_maps
+            .get(module_kind)
+            .with_context(|| format!("No symbol map for {module_kind}, does that module exist?"))?;
+        if let Some((_, symbol)) = symbol_map.by_address(address)? {
+            Ok((symbol, 0))
+        } else if let Some((_, symbol)) = symbol_map.get_function(address)? {
+            Ok((symbol, 0))
+        } else if let Some(symbol) = symbol_map.get_containing(address) {
+            Ok((symbol, address - symbol.addr))
+        } else {
+            bail!("No symbol found for relocation to {address:#010x} in {module_kind}");
+        }
+    }
+
+    fn write_function(
+        &self,
+        source: &mut String,
+        module: &Module,
+        symbol: &Symbol,
+        mode: InstructionMode,
+        symbol_lookup: &SymbolLookup,
+    ) -> Result<()> {
+        let Some(function) = module.get_function(symbol.addr) else {
+            log::warn!("Function {} at {:#010x} was not analyzed and must be added manually", symbol.name, symbol.addr);
+            writeln!(source, "// TODO: function {} at {:#010x}", symbol.name, symbol.addr)?;
+            writeln!(source)?;
+            return Ok(());
+        };
+
+        let mut body = Vec::new();
+        function.write_assembly(&mut body, symbol_lookup, module.code(), module.base_address(), false)?;
+        let body = String::from_utf8(body)?;
+
+        let thumb = mode == InstructionMode::Thumb;
+        if thumb {
+            writeln!(source, "#pragma thumb on")?;
+        }
+        writeln!(source, "{}asm void {}(void) {{", Self::storage(symbol), symbol.name)?;
+        source.push_str(&body);
+        writeln!(source, "}}")?;
+        if thumb {
+            writeln!(source, "#pragma thumb off")?;
+        }
+        writeln!(source)?;
+        Ok(())
+    }
+
+    fn data_declaration(
+        module: &Module,
+        section: &Section,
+        symbol: &Symbol,
+        max_address: u32,
+        initialized: bool,
+    ) -> DataDeclaration {
+        let size = symbol.size(max_address);
+        let constant = section.name() == ".rodata";
+
+        // Words are needed to initialize pointers
+        let has_relocations = module.relocations().iter_range(symbol.addr..symbol.addr + size).next().is_some();
+        let (element_type, length) = if initialized && has_relocations && symbol.addr % 4 == 0 && size % 4 == 0 {
+            ("unsigned long", size / 4)
+        } else {
+            ("unsigned char", size)
+        };
+
+        DataDeclaration { element_type, length, constant, local: symbol.local }
+    }
+
+    /// Returns the storage class of a symbol's declarations. Local symbols are `static`, so that they don't collide with
+    /// symbols of the same name in other files.
+    fn storage(symbol: &Symbol) -> &'static str {
+        if symbol.local {
+            "static "
+        } else {
+            ""
+        }
+    }
+
+    fn write_data(
+        &self,
+        source: &mut String,
+        module: &Module,
+        symbol: &Symbol,
+        declaration: &DataDeclaration,
+        bytes: Option<&[u8]>,
+        symbol_maps: &SymbolMaps,
+    ) -> Result<()> {
+        let Some(bytes) = bytes else {
+            writeln!(source, "{};", declaration.display(&symbol.name, true))?;
+            writeln!(source)?;
+            return Ok(());
+        };
+
+        writeln!(source, "{} = {{", declaration.display(&symbol.name, true))?;
+        let element_size = declaration.size() / declaration.length.max(1);
+        for (row, chunk) in bytes.chunks(16).enumerate() {
+            write!(source, "   ")?;
+            for (column, element) in chunk.chunks(element_size as usize).enumerate() {
+                let address = symbol.addr + (row * 16 + column * element_size as usize) as u32;
+                if element_size == 1 {
+                    write!(source, " 0x{:02x},", element[0])?;
+                    continue;
+                }
+
+                let word = u32::from_le_bytes([element[0], element[1], element[2], element[3]]);
+                match module.relocations().get(address) {
+                    Some(relocation) => {
+                        let module_kind = relocation.module().first_module().with_context(|| {
+                            format!("Relocation from {address:#010x} in {} has no destination module", symbol.name)
+                        })?;
+                        if relocation.module().other_modules().is_some() {
+                            log::warn!("Ambiguous relocation from {address:#010x} in {}, using {module_kind}", symbol.name);
+                        }
+                        let (target, offset) = Self::relocation_symbol(symbol_maps, module_kind, relocation.to_address())?;
+                        write!(source, " (unsigned long)&{}", target.name)?;
+                        let addend = relocation.addend() + offset as i64;
+                        if addend > 0 {
+                            write!(source, " + {addend:#x}")?;
+                        } else if addend < 0 {
+                            write!(source, " - {:#x}", addend.abs())?;
+                        }
+                        write!(source, ",")?;
+                    }
+                    None => write!(source, " {word:#010x},")?,
+                }
+            }
+            writeln!(source)?;
+        }
+        writeln!(source, "}};")?;
+        writeln!(source)?;
+        Ok(())
+    }
+}
+
+impl DataDeclaration {
+    fn size(&self) -> u32 {
+        match self.element_type {
+            "unsigned long" => self.length * 4,
+            _ => self.length,
+        }
+    }
+
+    /// Returns the declaration of the symbol, or its definition without the initializer. Local symbols are forward
+    /// declared as `static` with their length, as they can't be declared `extern`.
+    fn display(&self, name: &str, definition: bool) -> String {
+        let constant = if self.constant { "const " } else { "" };
+        if definition || self.local {
+            let storage = if self.local { "static " } else { "" };
+            format!("{storage}{constant}{} {name}[{:#x}]", self.element_type, self.length)
+        } else {
+            format!("extern {constant}{} {name}[]", self.element_type)
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::Config,
+    delinks::DelinkFile,
+    module::Module,
+    symbol::{Symbol, SymbolKind, SymbolMaps},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    analysis::functions::FunctionExt,
+    cmd::{Delink, Query, Rename},
+    config::{
+        config::{ConfigExt, ConfigModuleExt, ModuleCodes},
+        relocation::RelocationModuleExt,
+        symbol::SymbolLookup,
+    },
+    util::{asm::AsmTarget, io::modified_time, parse::parse_hex_address},
+};
+
+/// Keeps a project loaded and answers JSON-RPC requests on the standard input, for editor integration.
+#[derive(Args)]
+pub struct Serve {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Disassemble with Unified Assembler Language (UAL) syntax.
+    #[arg(long, short = 'u')]
+    pub ual: bool,
+
+    /// Assembler to generate assembly code for.
+    #[arg(long, short = 't', value_enum, default_value_t = AsmTarget::Mwasmarm)]
+    pub target: AsmTarget,
+}
+
+#[derive(Deserialize)]
+struct Request {
+    /// Request ID, or `None` for notifications which have no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    const PARSE_ERROR: i64 = -32700;
+    const METHOD_NOT_FOUND: i64 = -32601;
+    const INVALID_PARAMS: i64 = -32602;
+    const INTERNAL_ERROR: i64 = -32603;
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(error: anyhow::Error) -> Self {
+        Self { code: Self::INTERNAL_ERROR, message: format!("{error:#}") }
+    }
+}
+
+#[derive(Deserialize)]
+struct TargetParams {
+    /// Symbol name or address
+    target: String,
+    module: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenameParams {
+    target: String,
+    new_name: String,
+    module: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DelinkParams {
+    /// Name of the file in delinks.txt
+    file: String,
+    module: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AddressInfo {
+    module: String,
+    address: u32,
+    section: Option<String>,
+    file: Option<String>,
+    symbols: Vec<SymbolInfo>,
+    function: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SymbolInfo {
+    name: String,
+    kind: &'static str,
+    address: u32,
+    local: bool,
+    ambiguous: bool,
+}
+
+#[derive(Serialize)]
+struct ReferenceInfo {
+    module: String,
+    from: u32,
+    kind: String,
+    function: Option<String>,
+}
+
+/// A project with its modules analyzed, which is reloaded when its config files change.
+struct Project<'a> {
+    serve: &'a Serve,
+    codes: &'a ModuleCodes,
+    config: Config,
+    symbol_maps: SymbolMaps,
+    modules: Vec<ProjectModule<'a>>,
+    /// Modification times of the config files when they were loaded
+    modified_times: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+struct ProjectModule<'a> {
+    module: Module<'a>,
+    files: Vec<DelinkFile>,
+}
+
+impl Serve {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let codes = config.load_module_codes(config_dir)?;
+
+        let mut project = Project::load(self, &codes)?;
+        log::info!("Serving {} on the standard input", self.config_path.display());
+
+        let mut reader = io::stdin().lock();
+        let mut writer = io::stdout().lock();
+        while let Some(message) = Self::read_message(&mut reader)? {
+            let request = match serde_json::from_str::<Request>(&message) {
+                Ok(request) => request,
+                Err(error) => {
+                    let error = RpcError { code: RpcError::PARSE_ERROR, message: error.to_string() };
+                    Self::write_response(&mut writer, Value::Null, Err(error))?;
+                    continue;
+                }
+            };
+            if request.method == "exit" {
+                break;
+            }
+
+            let result = project.handle(&request.method, request.params);
+            if let Some(id) = request.id {
+                Self::write_response(&mut writer, id, result)?;
+            }
+            if request.method == "shutdown" {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a message with a `Content-Length` header, like in the Language Server Protocol. Returns `None` at the end of the
+    /// input.
+    fn read_message(reader: &mut impl BufRead) -> Result<Option<String>> {
+        let mut content_length = None;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                if key.eq_ignore_ascii_case("Content-Length") {
+                    content_length = Some(value.trim().parse::<usize>()?);
+                }
+            }
+        }
+
+        let Some(content_length) = content_length else { bail!("Message has no Content-Length header") };
+        let mut content = vec![0; content_length];
+        reader.read_exact(&mut content)?;
+        Ok(Some(String::from_utf8(content)?))
+    }
+
+    fn write_response(writer: &mut impl Write, id: Value, result: Result<Value, RpcError>) -> Result<()> {
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": error.code, "message": error.message } }),
+        };
+        let content = response.to_string();
+        write!(writer, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<'a> Project<'a> {
+    fn load(serve: &'a Serve, codes: &'a ModuleCodes) -> Result<Self> {
+        let config = Config::from_file(&serve.config_path)?;
+        let config_dir = serve.config_path.parent().unwrap();
+
+        let mut symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let mut modified_times = vec![(serve.config_path.clone(), modified_time(&serve.config_path))];
+        let mut modules = vec![];
+        for (module_config, kind) in config.iter_modules() {
+            for path in module_config.config_files(config_dir) {
+                modified_times.push((path.clone(), modified_time(path)));
+            }
+
+            let code = codes.get(module_config, kind)?;
+            let (module, files) = module_config.load_module(config_dir, kind, code, &mut symbol_maps)?;
+            modules.push(ProjectModule { module, files });
+        }
+
+        Ok(Self { serve, codes, config, symbol_maps, modules, modified_times })
+    }
+
+    fn reload(&mut self) -> Result<()> {
+        *self = Self::load(self.serve, self.codes)?;
+        Ok(())
+    }
+
+    /// Reloads the project if any of its config files have changed since they were loaded.
+    fn reload_if_changed(&mut self) -> Result<bool> {
+        let changed = self.modified_times.iter().any(|(path, time)| modified_time(path) != *time);
+        if changed {
+            log::info!("Config files changed, reloading");
+            self.reload()?;
+        }
+        Ok(changed)
+    }
+
+    fn handle(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let reloaded = self.reload_if_changed()?;
+        let result = match method {
+            "reload" => {
+                if !reloaded {
+                    self.reload()?;
+                }
+                Value::Null
+            }
+            "lookup" => Self::to_value(self.lookup(Self::params(params)?)?)?,
+            "references" => Self::to_value(self.references(Self::params(params)?)?)?,
+            "disassemble" => self.disassemble(Self::params(params)?)?,
+            "rename" => Self::to_value(self.rename(Self::params(params)?)?)?,
+            "delink" => self.delink(Self::params(params)?)?,
+            "shutdown" => Value::Null,
+            _ => return Err(RpcError { code: RpcError::METHOD_NOT_FOUND, message: format!("Unknown method '{method}'") }),
+        };
+        Ok(result)
+    }
+
+    fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+        serde_json::from_value(params).map_err(|error| RpcError { code: RpcError::INVALID_PARAMS, message: error.to_string() })
+    }
+
+    fn to_value<T: Serialize>(value: T) -> Result<Value> {
+        Ok(serde_json::to_value(value)?)
+    }
+
+    /// Finds the modules and addresses that a symbol name or address refers to.
+    fn resolve(&self, target: &str, module_name: Option<&str>) -> Result<Vec<(&ProjectModule<'a>, u32)>> {
+        let modules = self.modules.iter().filter(|module| module_name.map_or(true, |name| module.module.name() == name));
+
+        let mut matches = vec![];
+        for module in modules.clone() {
+            let symbol_map = self.symbol_maps.get(module.module.kind()).unwrap();
+            if let Some(symbols) = symbol_map.for_name(target) {
+                matches.extend(symbols.map(|(_, symbol)| (module, symbol.addr)));
+            }
+        }
+        if matches.is_empty() {
+            if let Ok(address) = parse_hex_address(target) {
+                matches.extend(
+                    modules
+                        .filter(|module| module.module.sections().get_by_contained_address(address).is_some())
+                        .map(|module| (module, address)),
+                );
+            }
+        }
+
+        if matches.is_empty() {
+            bail!("No symbol or module found for '{target}'");
+        }
+        Ok(matches)
+    }
+
+    fn lookup(&self, params: TargetParams) -> Result<Vec<AddressInfo>> {
+        let mut infos = vec![];
+        for (module, address) in self.resolve(&params.target, params.module.as_deref())? {
+            let symbol_map = self.symbol_maps.get(module.module.kind()).unwrap();
+            let section = module.module.sections().get_by_contained_address(address).map(|(_, section)| section.name());
+            let file = module
+                .files
+                .iter()
+                .find(|file| !file.gap() && file.sections.get_by_contained_address(address).is_some())
+                .map(|file| file.name.clone());
+            let symbols = symbol_map
+                .for_address(address)
+                .map(|symbols| symbols.map(|(_, symbol)| SymbolInfo::new(symbol)).collect())
+                .unwrap_or_default();
+            let function = symbol_map.get_function_containing(address).map(|(_, function)| function.name.clone());
+
+            infos.push(AddressInfo {
+                module: module.module.name().to_string(),
+                address,
+                section: section.map(|name| name.to_string()),
+                file,
+                symbols,
+                function,
+            });
+        }
+        Ok(infos)
+    }
+
+    fn references(&self, params: TargetParams) -> Result<Vec<ReferenceInfo>> {
+        let mut references = vec![];
+        for (module, address) in self.resolve(&params.target, params.module.as_deref())? {
+            let module_kind = module.module.kind();
+            let symbol_map = self.symbol_maps.get(module_kind).unwrap();
+            let range = module
+                .module
+                .sections()
+                .get_by_contained_address(address)
+                .and_then(|(_, section)| Query::containing_symbol(symbol_map, section, address))
+                .map_or(address..address + 1, |(_, range)| range);
+
+            for other in &self.modules {
+                let other_symbol_map = self.symbol_maps.get(other.module.kind()).unwrap();
+                for relocation in other.module.relocations().iter() {
+                    let to = relocation.to_address();
+                    if !range.contains(&to) && !range.contains(&(to & !1)) {
+                        continue;
+                    }
+                    if !relocation.module().points_to(module_kind) {
+                        continue;
+                    }
+
+                    let from = relocation.from_address();
+                    references.push(ReferenceInfo {
+                        module: other.module.name().to_string(),
+                        from,
+                        kind: relocation.kind().to_string(),
+                        function: other_symbol_map.get_function_containing(from).map(|(_, function)| function.name.clone()),
+                    });
+                }
+            }
+        }
+        Ok(references)
+    }
+
+    fn disassemble(&self, params: TargetParams) -> Result<Value> {
+        let matches = self.resolve(&params.target, params.module.as_deref())?;
+        let [(module, address)] = matches.as_slice() else {
+            bail!("'{}' matches {} symbols, specify a module", params.target, matches.len());
+        };
+        let module_kind = module.module.kind();
+        let symbol_map = self.symbol_maps.get(module_kind).unwrap();
+        let (_, symbol) = symbol_map
+            .get_function_containing(*address)
+            .with_context(|| format!("No function at {address:#010x} in {}", module.module.name()))?;
+        let function = module
+            .module
+            .get_function(symbol.addr)
+            .with_context(|| format!("Function {} was not found in {}", symbol.name, module.module.name()))?;
+
+        let symbol_lookup = SymbolLookup {
+            module_kind,
+            symbol_map,
+            symbol_maps: &self.symbol_maps,
+            relocations: module.module.relocations(),
+            asm_target: self.serve.target,
+        };
+        let mut assembly = vec![];
+        function.write_assembly(
+            &mut assembly,
+            &symbol_lookup,
+            module.module.code(),
+            module.module.base_address(),
+            self.serve.ual,
+        )?;
+
+        Ok(json!({
+            "module": module.module.name(),
+            "name": symbol.name,
+            "address": symbol.addr,
+            "assembly": String::from_utf8(assembly)?,
+        }))
+    }
+
+    fn rename(&mut self, params: RenameParams) -> Result<Vec<AddressInfo>> {
+        let rename = Rename {
+            symbol: Some(params.target),
+            new_name: Some(params.new_name.clone()),
+            config_path: self.serve.config_path.clone(),
+            rules: None,
+            module: params.module.clone(),
+            dry: false,
+        };
+        rename.run()?;
+        self.reload()?;
+        self.lookup(TargetParams { target: params.new_name, module: params.module })
+    }
+
+    fn delink(&self, params: DelinkParams) -> Result<Value> {
+        let (module, file) = self
+            .modules
+            .iter()
+            .filter(|module| params.module.as_ref().map_or(true, |name| module.module.name() == name))
+            .find_map(|module| module.files.iter().find(|file| file.name == params.file).map(|file| (module, file)))
+            .with_context(|| format!("File '{}' not found", params.file))?;
+
+        let config_dir = self.serve.config_path.parent().unwrap();
+        let paths = Delink::delink_some_files(&self.config, config_dir, &[(&module.module, file)], &self.symbol_maps)?;
+
+        Ok(json!({ "path": paths[0] }))
+    }
+}
+
+impl SymbolInfo {
+    fn new(symbol: &Symbol) -> Self {
+        let kind = match symbol.kind {
+            SymbolKind::Function(_) => "function",
+            SymbolKind::Label(_) => "label",
+            SymbolKind::PoolConstant => "pool_constant",
+            SymbolKind::JumpTable(_) => "jump_table",
+            SymbolKind::Data(_) => "data",
+            SymbolKind::Bss(_) => "bss",
+            SymbolKind::Absolute => "abs",
+        };
+        Self { name: symbol.name.clone(), kind, address: symbol.addr, local: symbol.local, ambiguous: symbol.ambiguous }
+    }
+}
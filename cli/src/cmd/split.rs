@@ -0,0 +1,234 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ds_decomp::config::{
+    config::Config,
+    delinks::{DelinkFile, Delinks},
+    module::ModuleKind,
+    relocations::Relocations,
+    section::{Section, SectionKind, Sections},
+    symbol::{SymFunction, Symbol, SymbolKind, SymbolMap, SymbolMaps},
+};
+
+use crate::{
+    config::{config::ConfigExt, delinks::DelinksExt, relocation::RelocationModuleExt},
+    util::parse::parse_hex_address,
+};
+
+/// Adds a file to delinks.txt from a range of functions, along with the data that only those functions refer to.
+#[derive(Args)]
+pub struct Split {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Name of the module to split, such as `main`, `itcm` or `ov000`.
+    #[arg(long, short = 'm')]
+    pub module: String,
+
+    /// First function of the new file, as a symbol name or an address.
+    #[arg(long, short = 'f')]
+    pub from: String,
+
+    /// Last function of the new file, as a symbol name or an address. Defaults to the first function.
+    #[arg(long, short = 't')]
+    pub to: Option<String>,
+
+    /// Name of the new file, such as `src/Foo.cpp`.
+    #[arg(long, short = 'n')]
+    pub name: String,
+
+    /// Dry run, print the new file instead of writing delinks.txt.
+    #[arg(long, short = 'd')]
+    pub dry: bool,
+}
+
+/// A data or .bss symbol which is not in any file yet.
+struct Candidate<'a> {
+    symbol: &'a Symbol,
+    section: &'a Section,
+    end_address: u32,
+}
+
+impl Split {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let (module, module_kind) =
+            config.module_by_name(&self.module).with_context(|| format!("Module '{}' not found", self.module))?;
+        let symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        let symbol_map = symbol_maps.get(module_kind).with_context(|| format!("No symbol map for {module_kind}"))?;
+        let delinks_path = config_dir.join(&module.delinks);
+        let mut delinks = Delinks::from_file(&delinks_path, module_kind)?;
+        let gaps = Delinks::from_file_and_generate_gaps(&delinks_path, module_kind)?;
+
+        let (_, first) = self.find_function(symbol_map, &gaps, &self.from)?;
+        let (last_function, last) = match &self.to {
+            Some(to) => self.find_function(symbol_map, &gaps, to)?,
+            None => self.find_function(symbol_map, &gaps, &self.from)?,
+        };
+        let text_range = first.addr..last.addr + last_function.size;
+        if text_range.is_empty() {
+            bail!("Function {} must come before {}", first.name, last.name);
+        }
+        let (_, text_section) = delinks
+            .sections
+            .get_by_contained_address(text_range.start)
+            .with_context(|| format!("Function {} is not in any section", first.name))?;
+        if text_section.kind() != SectionKind::Code || text_range.end > text_section.end_address() {
+            bail!("Functions {} to {} are not in the same code section", first.name, last.name);
+        }
+
+        let candidates = Self::candidates(&delinks, symbol_map);
+        let references = Self::references(&config, config_dir, module_kind, &candidates)?;
+        let included = Self::included_symbols(module_kind, &text_range, &candidates, &references);
+
+        // Span every section over the symbols to include
+        let mut ranges = BTreeMap::<&str, (&Section, Range<u32>)>::new();
+        for address in &included {
+            let candidate = &candidates[address];
+            ranges
+                .entry(candidate.section.name())
+                .and_modify(|(_, range)| {
+                    range.start = range.start.min(candidate.symbol.addr);
+                    range.end = range.end.max(candidate.end_address);
+                })
+                .or_insert((candidate.section, candidate.symbol.addr..candidate.end_address));
+        }
+
+        let mut sections = Sections::new();
+        sections.add(Section::inherit(text_section, text_range.start, text_range.end)?)?;
+        for (section, range) in ranges.values() {
+            for address in references.keys().filter(|&address| range.contains(address) && !included.contains(address)) {
+                let symbol = candidates[address].symbol;
+                log::warn!(
+                    "{} at {:#010x} is within {} of the new file, but is also referenced from outside of it",
+                    symbol.name,
+                    symbol.addr,
+                    section.name()
+                );
+            }
+            sections.add(Section::inherit(section, range.start, range.end)?)?;
+        }
+
+        let file = DelinkFile::new(self.name.clone(), sections, false);
+        let index = delinks.files.iter().position(|file| {
+            file.sections.by_name(text_section.name()).is_some_and(|(_, section)| section.start_address() >= text_range.end)
+        });
+        delinks.add_file(file, index)?;
+
+        if self.dry {
+            print!("{}", delinks.file_by_name(&self.name).unwrap());
+        } else {
+            delinks.to_file(&delinks_path)?;
+            log::info!("Added {} with {} data symbols to {}", self.name, included.len(), delinks_path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Finds a function by name or address. The functions to split are not in any file yet, so names are resolved as seen
+    /// from the gap files, which tells apart local symbols with the same name in other files.
+    fn find_function<'a>(&self, symbol_map: &'a SymbolMap, gaps: &Delinks, text: &str) -> Result<(SymFunction, &'a Symbol)> {
+        let mut found = None;
+        for gap in gaps.files.iter().filter(|file| file.gap()) {
+            let Some((index, symbol)) = symbol_map.by_name_from_file(text, &gap.sections)? else { continue };
+            if found.is_some_and(|(other, _)| other != index) {
+                bail!("Multiple symbols named {text} are not in any file, pass an address instead");
+            }
+            found = Some((index, symbol));
+        }
+        let found = match found {
+            Some(found) => Some(found),
+            None => symbol_map.by_name(text)?,
+        };
+        if let Some((_, symbol)) = found {
+            let SymbolKind::Function(function) = symbol.kind else { bail!("Symbol {text} is not a function") };
+            return Ok((function, symbol));
+        }
+        let Ok(address) = parse_hex_address(text) else { bail!("Symbol {text} not found in module {}", self.module) };
+        symbol_map.get_function(address)?.with_context(|| format!("No function at {address:#010x} in module {}", self.module))
+    }
+
+    /// Returns the data and .bss symbols which are not in any file yet, by address.
+    fn candidates<'a>(delinks: &'a Delinks, symbol_map: &'a SymbolMap) -> BTreeMap<u32, Candidate<'a>> {
+        let mut candidates = BTreeMap::new();
+        for section in delinks.sections.iter().filter(|section| section.kind() != SectionKind::Code) {
+            let symbols = symbol_map
+                .iter_by_address(section.address_range())
+                .filter(|symbol| matches!(symbol.kind, SymbolKind::Data(_) | SymbolKind::Bss(_)))
+                .collect::<Vec<_>>();
+            for (i, &symbol) in symbols.iter().enumerate() {
+                let in_file = delinks.files.iter().any(|file| {
+                    file.sections.by_name(section.name()).is_some_and(|(_, s)| s.address_range().contains(&symbol.addr))
+                });
+                if in_file {
+                    continue;
+                }
+                let next_address = symbols.get(i + 1).map(|next| next.addr).unwrap_or(section.end_address());
+                let end_address = symbol.addr + symbol.size(next_address).min(next_address - symbol.addr);
+                candidates.insert(symbol.addr, Candidate { symbol, section, end_address });
+            }
+        }
+        candidates
+    }
+
+    /// Returns the addresses of relocations in all modules which point to each candidate, by candidate address.
+    fn references(
+        config: &Config,
+        config_dir: &Path,
+        module_kind: ModuleKind,
+        candidates: &BTreeMap<u32, Candidate>,
+    ) -> Result<BTreeMap<u32, Vec<(ModuleKind, u32)>>> {
+        let mut references = BTreeMap::<u32, Vec<_>>::new();
+        for (module, kind) in config.iter_modules() {
+            let relocations = Relocations::from_file(config_dir.join(&module.relocations))?;
+            for relocation in relocations.iter() {
+                if !relocation.module().points_to(module_kind) {
+                    continue;
+                }
+                let Some(candidate) = Self::candidate_containing(candidates, relocation.to_address()) else { continue };
+                references.entry(candidate.symbol.addr).or_default().push((kind, relocation.from_address()));
+            }
+        }
+        Ok(references)
+    }
+
+    /// Returns the candidates which are only referenced from the text range, or from other included candidates.
+    fn included_symbols(
+        module_kind: ModuleKind,
+        text_range: &Range<u32>,
+        candidates: &BTreeMap<u32, Candidate>,
+        references: &BTreeMap<u32, Vec<(ModuleKind, u32)>>,
+    ) -> BTreeSet<u32> {
+        let mut included = BTreeSet::new();
+        loop {
+            let is_inside = |&(kind, from): &(ModuleKind, u32)| {
+                kind == module_kind
+                    && (text_range.contains(&from)
+                        || Self::candidate_containing(candidates, from)
+                            .is_some_and(|candidate| included.contains(&candidate.symbol.addr)))
+            };
+            let new_symbols = references
+                .iter()
+                .filter(|(address, refs)| !included.contains(*address) && refs.iter().all(is_inside))
+                .map(|(&address, _)| address)
+                .collect::<Vec<_>>();
+            if new_symbols.is_empty() {
+                break;
+            }
+            included.extend(new_symbols);
+        }
+        included
+    }
+
+    fn candidate_containing<'a, 'b>(candidates: &'b BTreeMap<u32, Candidate<'a>>, address: u32) -> Option<&'b Candidate<'a>> {
+        candidates.range(..=address).next_back().map(|(_, candidate)| candidate).filter(|c| address < c.end_address)
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use clap::Args;
+use ds_decomp::config::{
+    config::Config,
+    delinks::DelinkFile,
+    module::{Module, ModuleKind},
+    symbol::{SymbolMap, SymbolMaps},
+};
+
+use crate::{
+    cmd::{Delink, Lcf, LcfFormat, Objdiff},
+    config::{
+        config::{ConfigExt, ConfigModuleExt, ModuleCodes},
+        delinks::DelinkFileExt,
+    },
+    util::io::modified_time,
+};
+
+/// Watches the config files and regenerates the delinked objects, linker script and objdiff.json when they change.
+#[derive(Args)]
+pub struct Watch {
+    /// Path to config.yaml.
+    #[arg(long, short = 'c')]
+    pub config_path: PathBuf,
+
+    /// Path to output LCF file. The linker script is not generated if not given.
+    #[arg(long, short = 'l', requires = "objects_file")]
+    pub lcf_file: Option<PathBuf>,
+
+    /// Path to object list file.
+    #[arg(long, short = 'o', requires = "lcf_file")]
+    pub objects_file: Option<PathBuf>,
+
+    /// Linker script format to generate.
+    #[arg(long, short = 'f', value_enum, default_value_t = LcfFormat::Mwld)]
+    pub format: LcfFormat,
+
+    /// Path to directory to generate objdiff.json. objdiff.json is not generated if not given.
+    #[arg(long, short = 'O')]
+    pub objdiff_path: Option<PathBuf>,
+
+    /// Milliseconds between checking the config files for changes.
+    #[arg(long, short = 'i', default_value_t = 500)]
+    pub interval: u64,
+}
+
+/// A module which has been analyzed, and is only analyzed again when its config files change.
+struct WatchedModule<'a> {
+    module: Module<'a>,
+    files: Vec<DelinkFile>,
+}
+
+struct Watcher<'a> {
+    watch: &'a Watch,
+    codes: &'a ModuleCodes,
+    /// Symbol maps of all modules, including the symbols added by analyzing them
+    symbol_maps: SymbolMaps,
+    modules: BTreeMap<ModuleKind, WatchedModule<'a>>,
+    /// Hashes of the inputs of each delinked object
+    hashes: BTreeMap<(ModuleKind, String), u64>,
+    /// Modification times of the config files when they were last read
+    modified_times: BTreeMap<PathBuf, Option<SystemTime>>,
+    lcf_outdated: bool,
+    objdiff_outdated: bool,
+}
+
+impl Watch {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::from_file(&self.config_path)?;
+        let config_dir = self.config_path.parent().unwrap();
+
+        let codes = config.load_module_codes(config_dir)?;
+
+        let mut watcher = Watcher {
+            watch: self,
+            codes: &codes,
+            symbol_maps: SymbolMaps::new(),
+            modules: BTreeMap::new(),
+            hashes: BTreeMap::new(),
+            modified_times: BTreeMap::new(),
+            lcf_outdated: true,
+            objdiff_outdated: true,
+        };
+        watcher.update(&BTreeSet::new(), true)?;
+        log::info!("Watching {} for changes", self.config_path.display());
+
+        let interval = Duration::from_millis(self.interval);
+        loop {
+            thread::sleep(interval);
+            let changed = watcher.changed_files();
+            if changed.is_empty() {
+                continue;
+            }
+
+            // Give editors time to finish writing the files
+            thread::sleep(interval);
+            let full = changed.contains(&self.config_path);
+            if let Err(error) = watcher.update(&changed, full) {
+                log::error!("{error:#}");
+            }
+        }
+    }
+}
+
+impl Watcher<'_> {
+    fn changed_files(&self) -> BTreeSet<PathBuf> {
+        self.modified_times.iter().filter(|(path, time)| modified_time(path) != **time).map(|(path, _)| path.clone()).collect()
+    }
+
+    /// Analyzes the modules whose config files have changed, or all modules if `full` is true, and regenerates the
+    /// outputs which are affected by the changes.
+    fn update(&mut self, changed: &BTreeSet<PathBuf>, full: bool) -> Result<()> {
+        let config_path = &self.watch.config_path;
+        let config_dir = config_path.parent().unwrap();
+
+        self.modified_times.clear();
+        self.modified_times.insert(config_path.clone(), modified_time(config_path));
+        let config = Config::from_file(config_path)?;
+        for (module_config, _) in config.iter_modules() {
+            for path in module_config.config_files(config_dir) {
+                let time = modified_time(&path);
+                self.modified_times.insert(path, time);
+            }
+        }
+
+        if full {
+            self.symbol_maps = SymbolMaps::from_config(config_dir, &config)?;
+        }
+        let mut modules = BTreeMap::new();
+        for (module_config, kind) in config.iter_modules() {
+            let [delinks_path, symbols_path, relocations_path] = module_config.config_files(config_dir);
+            self.lcf_outdated |= full || changed.contains(&delinks_path);
+            self.objdiff_outdated |= full || changed.contains(&delinks_path) || changed.contains(&symbols_path);
+
+            let module_changed =
+                full || [&delinks_path, &symbols_path, &relocations_path].into_iter().any(|path| changed.contains(path));
+            let module = match self.modules.remove(&kind) {
+                Some(module) if !module_changed => module,
+                _ => {
+                    log::debug!("Analyzing {}", module_config.name);
+                    if !full {
+                        *self.symbol_maps.get_mut(kind) = SymbolMap::from_file(&symbols_path)?;
+                    }
+                    let code = self.codes.get(module_config, kind)?;
+                    let (module, files) = module_config.load_module(config_dir, kind, code, &mut self.symbol_maps)?;
+                    WatchedModule { module, files }
+                }
+            };
+            modules.insert(kind, module);
+        }
+        self.modules = modules;
+
+        // Symbols in one module can be referenced by objects in other modules, so every object is checked for changes
+        let mut changed_files = vec![];
+        let mut new_hashes = vec![];
+        for (&kind, module) in &self.modules {
+            for file in &module.files {
+                let hash = file.input_hash(&module.module, &self.symbol_maps)?;
+                let key = (kind, file.name.clone());
+                if self.hashes.get(&key) == Some(&hash) {
+                    continue;
+                }
+                changed_files.push((&module.module, file));
+                new_hashes.push((key, hash));
+            }
+        }
+        if !changed_files.is_empty() {
+            Delink::delink_some_files(&config, config_dir, &changed_files, &self.symbol_maps)?;
+            log::info!("Delinked {} files", changed_files.len());
+        }
+        // Only remember the hashes once delinking has succeeded, so that failed files are retried on the next update
+        self.hashes.extend(new_hashes);
+
+        if self.lcf_outdated {
+            if let (Some(lcf_file), Some(objects_file)) = (&self.watch.lcf_file, &self.watch.objects_file) {
+                let lcf = Lcf {
+                    config_path: config_path.clone(),
+                    lcf_file: lcf_file.clone(),
+                    objects_file: objects_file.clone(),
+                    format: self.watch.format,
+                };
+                lcf.run()?;
+                log::info!("Generated {}", lcf_file.display());
+            }
+            self.lcf_outdated = false;
+        }
+
+        if self.objdiff_outdated {
+            if let Some(objdiff_path) = &self.watch.objdiff_path {
+                let objdiff = Objdiff {
+                    config_path: config_path.clone(),
+                    output_path: Some(objdiff_path.clone()),
+                    scratch: false,
+                    compiler: None,
+                    c_flags: None,
+                    preset_id: None,
+                    custom_make: None,
+                    custom_args: vec![],
+                };
+                objdiff.run()?;
+                log::info!("Generated {}", objdiff_path.join("objdiff.json").display());
+            }
+            self.objdiff_outdated = false;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use ds_decomp::config::{
+    config::{Config, ConfigModule, ConfigNewOverlay},
+    delinks::{DelinkFile, Delinks},
+    module::{Module, ModuleKind},
+    relocations::Relocations,
+    section::SectionKind,
+    symbol::SymbolMaps,
+};
+use ds_rom::rom::{Rom, RomLoadOptions};
+
+use crate::{config::delinks::DelinksExt, util::parse::parse_hex_address};
+
+pub trait ConfigExt {
+    /// Iterates over the main module, autoloads and overlays.
+    fn iter_modules(&self) -> impl Iterator<Item = (&ConfigModule, ModuleKind)>;
+    /// Finds a module by its name, such as `main`, `itcm` or `ov000`.
+    fn module_by_name(&self, name: &str) -> Option<(&ConfigModule, ModuleKind)>;
+    /// Returns the explicit origins of overlays, by overlay ID.
+    fn overlay_origins(&self) -> Result<BTreeMap<u16, u32>>;
+    /// Loads the ROM and returns the code of every module in it.
+    fn load_module_codes(&self, config_dir: &Path) -> Result<ModuleCodes>;
+}
+
+impl ConfigExt for Config {
+    fn iter_modules(&self) -> impl Iterator<Item = (&ConfigModule, ModuleKind)> {
+        [(&self.main_module, ModuleKind::Arm9)]
+            .into_iter()
+            .chain(self.autoloads.iter().map(|autoload| (&autoload.module, ModuleKind::Autoload(autoload.kind))))
+            .chain(self.overlays.iter().map(|overlay| (&overlay.module, ModuleKind::Overlay(overlay.id))))
+    }
+
+    fn module_by_name(&self, name: &str) -> Option<(&ConfigModule, ModuleKind)> {
+        self.iter_modules().find(|(module, _)| module.name == name)
+    }
+
+    fn overlay_origins(&self) -> Result<BTreeMap<u16, u32>> {
+        self.overlays
+            .iter()
+            .filter_map(|overlay| {
+                let origin = overlay.origin.as_ref()?;
+                let address =
+                    parse_hex_address(origin).with_context(|| format!("Invalid origin '{origin}' of overlay {}", overlay.id));
+                Some(address.map(|address| (overlay.id, address)))
+            })
+            .collect()
+    }
+
+    fn load_module_codes(&self, config_dir: &Path) -> Result<ModuleCodes> {
+        let rom = Rom::load(
+            config_dir.join(&self.rom_config),
+            RomLoadOptions { key: None, compress: false, encrypt: false, load_files: false },
+        )?;
+        let mut codes = BTreeMap::new();
+        codes.insert(ModuleKind::Arm9, rom.arm9().code()?.to_vec());
+        for autoload in rom.arm9().autoloads()?.iter() {
+            codes.insert(ModuleKind::Autoload(autoload.kind()), autoload.code().to_vec());
+        }
+        for overlay in rom.arm9_overlays() {
+            codes.insert(ModuleKind::Overlay(overlay.id()), overlay.code().to_vec());
+        }
+        Ok(ModuleCodes { codes })
+    }
+}
+
+/// Code of the modules in the ROM, which modules loaded by [`ConfigModuleExt::load_module`] can borrow for as long as
+/// needed.
+pub struct ModuleCodes {
+    codes: BTreeMap<ModuleKind, Vec<u8>>,
+}
+
+impl ModuleCodes {
+    /// Returns the code of a module, or an error if it's not in the ROM.
+    pub fn get(&self, module: &ConfigModule, kind: ModuleKind) -> Result<&[u8]> {
+        let code = self.codes.get(&kind).with_context(|| format!("Module {} is not in the ROM", module.name))?;
+        Ok(code)
+    }
+}
+
+pub trait ConfigModuleExt {
+    /// Returns the paths to the module's delinks.txt, symbols.txt and relocs.txt.
+    fn config_files(&self, config_dir: &Path) -> [PathBuf; 3];
+    /// Loads the module's delinks and relocations and analyzes its functions. Returns the module along with the files in
+    /// its delinks.txt, including gap files.
+    fn load_module<'a>(
+        &self,
+        config_dir: &Path,
+        kind: ModuleKind,
+        code: &'a [u8],
+        symbol_maps: &mut SymbolMaps,
+    ) -> Result<(Module<'a>, Vec<DelinkFile>)>;
+}
+
+impl ConfigModuleExt for ConfigModule {
+    fn config_files(&self, config_dir: &Path) -> [PathBuf; 3] {
+        [config_dir.join(&self.delinks), config_dir.join(&self.symbols), config_dir.join(&self.relocations)]
+    }
+
+    fn load_module<'a>(
+        &self,
+        config_dir: &Path,
+        kind: ModuleKind,
+        code: &'a [u8],
+        symbol_maps: &mut SymbolMaps,
+    ) -> Result<(Module<'a>, Vec<DelinkFile>)> {
+        let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&self.delinks), kind)?;
+        let relocations = Relocations::from_file(config_dir.join(&self.relocations))?;
+        let symbol_map = symbol_maps.get_mut(kind);
+        let name = self.name.clone();
+        let module = match kind {
+            ModuleKind::Arm9 => Module::new_arm9(name, symbol_map, relocations, delinks.sections, code)?,
+            ModuleKind::Autoload(autoload) => {
+                Module::new_autoload(name, symbol_map, relocations, delinks.sections, autoload, code)?
+            }
+            ModuleKind::Overlay(id) => Module::new_overlay(name, symbol_map, relocations, delinks.sections, id, code)?,
+        };
+        Ok((module, delinks.files))
+    }
+}
+
+/// Sections of new overlays in the order they are linked, along with their kinds and alignments.
+pub const NEW_OVERLAY_SECTIONS: [(&str, SectionKind, u32); 6] = [
+    (".text", SectionKind::Code, 32),
+    (".init", SectionKind::Code, 4),
+    (".rodata", SectionKind::Data, 4),
+    (".ctor", SectionKind::Data, 4),
+    (".data", SectionKind::Data, 32),
+    (".bss", SectionKind::Bss, 32),
+];
+
+/// Returns the name of a new overlay's section in its boundary symbols, like [`SectionExt::boundary_name`].
+///
+/// [`SectionExt::boundary_name`]: crate::config::section::SectionExt::boundary_name
+pub fn new_section_boundary_name(section_name: &str) -> String {
+    section_name.strip_prefix('.').unwrap_or(section_name).to_uppercase()
+}
+
+pub trait ConfigNewOverlayExt {
+    /// Returns the name of the overlay's memory region in linker scripts, such as `OV120`.
+    fn memory_name(&self) -> String;
+    /// Returns the name of the memory region which the overlay is loaded after.
+    fn after_memory_name(&self) -> String;
+    /// Returns the paths to the objects of the overlay's source files.
+    fn object_paths(&self, build_path: &Path) -> Vec<PathBuf>;
+}
+
+impl ConfigNewOverlayExt for ConfigNewOverlay {
+    fn memory_name(&self) -> String {
+        format!("OV{:03}", self.id)
+    }
+
+    fn after_memory_name(&self) -> String {
+        self.after.map(|id| format!("OV{id:03}")).unwrap_or_else(|| "ARM9".to_string())
+    }
+
+    fn object_paths(&self, build_path: &Path) -> Vec<PathBuf> {
+        self.files.iter().map(|file| build_path.join(file).with_extension("o")).collect()
+    }
+}
@@ -1,14 +1,23 @@
-use std::{cmp::Ordering, collections::HashMap, path::Path};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::Path,
+};
 
 use anyhow::{bail, Context, Result};
 use ds_decomp::config::{
     delinks::{DelinkFile, Delinks},
-    module::ModuleKind,
+    module::{Module, ModuleKind},
     section::{Section, Sections},
+    symbol::SymbolMaps,
 };
 use ds_rom::rom::raw::AutoloadKind;
+use fxhash::FxHasher64;
 use petgraph::{graph::NodeIndex, Graph};
 
+use crate::config::{relocation::RelocationModuleExt, section::SectionExt};
+
 pub trait DelinksExt
 where
     Self: Sized,
@@ -182,11 +191,15 @@ impl DelinksPrivExt for Delinks {
     }
 }
 
-trait DelinkFileExt
+pub trait DelinkFileExt
 where
     Self: Sized,
 {
     fn new_gap(module_kind: ModuleKind, id: usize) -> Result<Self>;
+
+    /// Hashes everything that the delinked object depends on: the file's sections and their code, the symbols and
+    /// relocations within them, and the external symbols that the relocations refer to.
+    fn input_hash(&self, module: &Module, symbol_maps: &SymbolMaps) -> Result<u64>;
 }
 
 impl DelinkFileExt for DelinkFile {
@@ -204,6 +217,42 @@ impl DelinkFileExt for DelinkFile {
             },
         };
 
-        Ok(Self { name, sections: Sections::new(), complete: false, gap: true })
+        let mut file = Self::new(name, Sections::new(), false);
+        file.gap = true;
+        Ok(file)
+    }
+
+    fn input_hash(&self, module: &Module, symbol_maps: &SymbolMaps) -> Result<u64> {
+        let symbol_map = symbol_maps.get(module.kind()).unwrap();
+        let mut hasher = FxHasher64::default();
+        for section in self.sections.sorted_by_address() {
+            (section.name(), section.start_address(), section.end_address()).hash(&mut hasher);
+            let code = section.relocatable_code(module)?;
+            if let Some(code) = &code {
+                for symbol in section.absolute_symbols(code, symbol_maps) {
+                    symbol.to_string().hash(&mut hasher);
+                }
+            }
+            code.hash(&mut hasher);
+            for symbol in symbol_map.iter_by_address(section.address_range()) {
+                symbol.to_string().hash(&mut hasher);
+            }
+            for (_, relocation) in module.relocations().iter_range(section.address_range()) {
+                relocation.to_string().hash(&mut hasher);
+
+                // External symbols are referred to by name
+                let Some(external_symbol_map) = relocation.module().first_module().and_then(|kind| symbol_maps.get(kind))
+                else {
+                    continue;
+                };
+                let to = relocation.to_address();
+                let external_symbol = match external_symbol_map.by_address(to)? {
+                    Some((_, symbol)) => Some(symbol),
+                    None => external_symbol_map.get_function(to)?.map(|(_, symbol)| symbol),
+                };
+                external_symbol.map(|symbol| symbol.to_string()).hash(&mut hasher);
+            }
+        }
+        Ok(hasher.finish())
     }
 }
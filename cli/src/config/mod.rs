@@ -1,3 +1,4 @@
+pub mod config;
 pub mod delinks;
 pub mod program;
 pub mod relocation;
@@ -45,6 +45,9 @@ where
 
     /// Returns all modules other than the first that this relocation is pointing to.
     fn other_modules(&self) -> Option<impl Iterator<Item = ModuleKind> + '_>;
+
+    /// Returns whether this relocation is pointing to the given module, possibly among others.
+    fn points_to(&self, module_kind: ModuleKind) -> bool;
 }
 
 impl RelocationModuleExt for RelocationModule {
@@ -71,4 +74,9 @@ impl RelocationModuleExt for RelocationModule {
             RelocationModule::Dtcm => None,
         }
     }
+
+    fn points_to(&self, module_kind: ModuleKind) -> bool {
+        self.first_module() == Some(module_kind)
+            || self.other_modules().is_some_and(|mut modules| modules.any(|module| module == module_kind))
+    }
 }
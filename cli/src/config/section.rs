@@ -5,12 +5,15 @@ use ds_decomp::config::{
     module::Module,
     relocations::{Relocation, RelocationKind},
     section::Section,
+    symbol::{Symbol, SymbolMaps},
 };
 use object::{Object, ObjectSymbol};
 
 pub trait SectionExt {
     fn relocatable_code(&self, module: &Module) -> Result<Option<Vec<u8>>>;
     fn relocations<'a>(&'a self, module: &'a Module) -> impl Iterator<Item = &'a Relocation>;
+    /// Absolute symbols whose addresses are stored in the words of `code`, which is the contents of this section.
+    fn absolute_symbols<'a>(&self, code: &[u8], symbol_maps: &'a SymbolMaps) -> Vec<&'a Symbol>;
 
     /// Name of this section for creating section boundary symbols, e.g. ARM9_BSS_START
     fn boundary_name(&self) -> String;
@@ -59,6 +62,15 @@ impl SectionExt for Section {
         Ok(Some(code))
     }
 
+    fn absolute_symbols<'a>(&self, code: &[u8], symbol_maps: &'a SymbolMaps) -> Vec<&'a Symbol> {
+        let first_word = (self.start_address().next_multiple_of(4) - self.start_address()) as usize;
+        code.get(first_word..)
+            .unwrap_or_default()
+            .chunks_exact(4)
+            .filter_map(|word| symbol_maps.absolute_symbol(u32::from_le_bytes([word[0], word[1], word[2], word[3]])))
+            .collect()
+    }
+
     fn relocations<'a>(&'a self, module: &'a Module) -> impl Iterator<Item = &'a Relocation> {
         module.relocations().iter_range(self.address_range()).map(|(_, r)| r)
     }
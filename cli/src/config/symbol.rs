@@ -1,14 +1,17 @@
 use std::io;
 
 use anyhow::{bail, Result};
-use ds_decomp::config::{
-    module::ModuleKind,
-    relocations::Relocations,
-    symbol::{InstructionMode, SymData, SymFunction, SymLabel, Symbol, SymbolKind, SymbolMap, SymbolMaps},
+use ds_decomp::{
+    analysis::fixed_point::{format_fx, looks_like_fx32},
+    config::{
+        module::ModuleKind,
+        relocations::Relocations,
+        symbol::{FxKind, InstructionMode, SymData, SymFunction, SymLabel, Symbol, SymbolKind, SymbolMap, SymbolMaps},
+    },
 };
 use unarm::LookupSymbol;
 
-use crate::util::bytes::FromSlice;
+use crate::util::{asm::AsmTarget, bytes::FromSlice};
 
 use super::relocation::RelocationModuleExt;
 
@@ -29,6 +32,8 @@ impl LookupSymbol for LookupSymbolMap {
 
 pub trait SymbolExt {
     fn mapping_symbol_name(&self) -> Option<&str>;
+    /// Scope of the symbol in an object file, which is file-local for `local` symbols.
+    fn as_obj_symbol_scope(&self) -> object::SymbolScope;
 }
 
 impl SymbolExt for Symbol {
@@ -47,7 +52,15 @@ impl SymbolExt for Symbol {
                 }
             }
             SymbolKind::Data(_) => Some("$d"),
-            SymbolKind::Bss(_) => None,
+            SymbolKind::Bss(_) | SymbolKind::Absolute => None,
+        }
+    }
+
+    fn as_obj_symbol_scope(&self) -> object::SymbolScope {
+        if self.local {
+            object::SymbolScope::Compilation
+        } else {
+            self.kind.as_obj_symbol_scope()
         }
     }
 }
@@ -66,6 +79,7 @@ impl SymbolKindExt for SymbolKind {
             Self::JumpTable(_) => object::SymbolKind::Label,
             Self::Data(_) => object::SymbolKind::Data,
             Self::Bss(_) => object::SymbolKind::Data,
+            Self::Absolute => object::SymbolKind::Data,
         }
     }
 
@@ -77,15 +91,43 @@ impl SymbolKindExt for SymbolKind {
             SymbolKind::JumpTable(_) => object::SymbolScope::Compilation,
             SymbolKind::Data(_) => object::SymbolScope::Dynamic,
             SymbolKind::Bss(_) => object::SymbolScope::Dynamic,
+            SymbolKind::Absolute => object::SymbolScope::Compilation,
         }
     }
 }
 
 pub trait SymDataExt {
     fn write_assembly<W: io::Write>(&self, w: &mut W, symbol: &Symbol, bytes: &[u8], symbols: &SymbolLookup) -> Result<()>;
+    /// C type of a single element, e.g. `u16` for `short[4]`
+    fn c_element_type(&self) -> &'static str;
+    /// C declaration of a variable with this type, e.g. `u16 name[4]`
+    fn c_declaration(&self, name: &str) -> String;
 }
 
 impl SymDataExt for SymData {
+    fn c_element_type(&self) -> &'static str {
+        match self {
+            SymData::Any | SymData::Byte { .. } => "u8",
+            SymData::Short { .. } => "u16",
+            SymData::Word { .. } => "u32",
+            SymData::Fx { kind, .. } => kind.c_type(),
+        }
+    }
+
+    fn c_declaration(&self, name: &str) -> String {
+        let element_type = self.c_element_type();
+        match self {
+            SymData::Any => format!("{element_type} {name}[]"),
+            SymData::Byte { count } | SymData::Short { count } | SymData::Word { count } | SymData::Fx { count, .. } => {
+                match count {
+                    Some(1) => format!("{element_type} {name}"),
+                    Some(count) => format!("{element_type} {name}[{count}]"),
+                    None => format!("{element_type} {name}[]"),
+                }
+            }
+        }
+    }
+
     fn write_assembly<W: io::Write>(&self, w: &mut W, symbol: &Symbol, bytes: &[u8], symbols: &SymbolLookup) -> Result<()> {
         if let Some(size) = self.size() {
             if bytes.len() < size as usize {
@@ -96,7 +138,7 @@ impl SymDataExt for SymData {
 
         let mut offset = 0;
         while offset < bytes.len() {
-            let mut data_directive = false;
+            let mut line = DataLine::default();
 
             let mut column = 0;
             while column < 16 {
@@ -112,38 +154,83 @@ impl SymDataExt for SymData {
                 if bytes.len() >= 4 && (address & 3) == 0 {
                     let pointer = u32::from_le_slice(bytes);
 
-                    if symbols.write_symbol(w, address, pointer, &mut data_directive, "    ")? {
+                    let mut symbol_line = vec![];
+                    if symbols.write_symbol(&mut symbol_line, address, pointer, &mut false, "    ")? {
+                        line.write(w, *self, symbols.asm_target)?;
+                        w.write_all(&symbol_line)?;
                         column += 4;
                         continue;
                     }
                 }
 
                 // If no symbol, write data literals
-                if !data_directive {
-                    match self {
-                        SymData::Any => write!(w, "    .byte 0x{:02x}", bytes[0])?,
-                        SymData::Byte { .. } => write!(w, "    .byte 0x{:02x}", bytes[0])?,
-                        SymData::Short { .. } => write!(w, "    .short {:#x}", bytes[0])?,
-                        SymData::Word { .. } => write!(w, "    .word {:#x}", u32::from_le_slice(bytes))?,
-                    }
-                    data_directive = true;
-                } else {
-                    match self {
-                        SymData::Any => write!(w, ", 0x{:02x}", bytes[0])?,
-                        SymData::Byte { .. } => write!(w, ", 0x{:02x}", bytes[0])?,
-                        SymData::Short { .. } => write!(w, ", {:#x}", u16::from_le_slice(bytes))?,
-                        SymData::Word { .. } => write!(w, ", {:#x}", u32::from_le_slice(bytes))?,
-                    }
+                line.push(*self, bytes);
+                column += self.value_size() as usize;
+            }
+            line.write(w, *self, symbols.asm_target)?;
+
+            offset += 16;
+        }
+
+        Ok(())
+    }
+}
+
+/// Data literals which are written on the same line.
+#[derive(Default)]
+struct DataLine {
+    literals: Vec<String>,
+    /// Decimal values of the literals which are fixed-point numbers
+    decimals: Vec<String>,
+}
+
+impl DataLine {
+    fn push(&mut self, data: SymData, bytes: &[u8]) {
+        match data {
+            SymData::Any | SymData::Byte { .. } => self.literals.push(format!("0x{:02x}", bytes[0])),
+            SymData::Short { .. } => self.literals.push(format!("{:#x}", u16::from_le_slice(bytes))),
+            SymData::Word { .. } => {
+                let value = u32::from_le_slice(bytes);
+                self.literals.push(format!("{value:#x}"));
+                if looks_like_fx32(value) {
+                    self.decimals.push(format_fx(FxKind::Fx32.to_f64(bytes)));
                 }
-                column += self.element_size() as usize;
             }
-            if data_directive {
-                writeln!(w)?;
+            SymData::Fx { kind, .. } => {
+                match kind.scalar() {
+                    FxKind::Fx16 => self.literals.push(format!("{:#x}", u16::from_le_slice(bytes))),
+                    FxKind::Fx64c => {
+                        self.literals.push(format!("{:#x}, {:#x}", u32::from_le_slice(bytes), u32::from_le_slice(&bytes[4..])))
+                    }
+                    _ => self.literals.push(format!("{:#x}", u32::from_le_slice(bytes))),
+                }
+                self.decimals.push(format_fx(kind.to_f64(bytes)));
             }
+        }
+    }
 
-            offset += 16;
+    /// Writes the literals and clears the line. Decimal values are only commented if every literal on the line has one.
+    fn write<W: io::Write>(&mut self, w: &mut W, data: SymData, asm_target: AsmTarget) -> Result<()> {
+        if self.literals.is_empty() {
+            return Ok(());
+        }
+        let directive = match data {
+            SymData::Any | SymData::Byte { .. } => ".byte",
+            SymData::Short { .. } => ".short",
+            SymData::Word { .. } => ".word",
+            SymData::Fx { kind, .. } => match kind.scalar() {
+                FxKind::Fx16 => ".short",
+                _ => ".word",
+            },
+        };
+        write!(w, "    {directive} {}", self.literals.join(", "))?;
+        if self.decimals.len() == self.literals.len() {
+            write!(w, " {} {}", asm_target.comment(), self.decimals.join(", "))?;
         }
+        writeln!(w)?;
 
+        self.literals.clear();
+        self.decimals.clear();
         Ok(())
     }
 }
@@ -155,6 +242,7 @@ pub struct SymbolLookup<'a> {
     /// All symbol maps, including external modules
     pub symbol_maps: &'a SymbolMaps,
     pub relocations: &'a Relocations,
+    pub asm_target: AsmTarget,
 }
 
 impl<'a> SymbolLookup<'a> {
@@ -227,7 +315,7 @@ impl<'a> SymbolLookup<'a> {
         let Some(relocation) = self.relocations.get(source) else { return Ok(()) };
 
         if let Some(overlays) = relocation.module().other_modules() {
-            write!(w, " ; ")?;
+            write!(w, " {} ", self.asm_target.comment())?;
             for (i, overlay) in overlays.enumerate() {
                 let Some(external_symbol_map) = self.symbol_maps.get(overlay) else {
                     log::warn!(
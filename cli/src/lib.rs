@@ -1,4 +1,6 @@
 pub mod analysis;
 pub mod cmd;
 pub mod config;
+pub mod linker;
+pub mod patch;
 pub mod util;
@@ -0,0 +1,265 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use object::{
+    elf::{R_ARM_ABS32, R_ARM_CALL, R_ARM_JUMP24, R_ARM_PC24, R_ARM_THM_PC22, R_ARM_THM_XPC22, R_ARM_XPC25, SHF_ALLOC},
+    Object, ObjectSection, ObjectSymbol, RelocationFlags, RelocationTarget, SectionFlags, SymbolSection,
+};
+
+use crate::util::{bytes::FromSlice, io::read_file};
+
+/// A relocatable ELF file to be linked, either delinked by dsd or compiled from source.
+pub struct InputObject {
+    pub path: PathBuf,
+    pub sections: Vec<InputSection>,
+    pub symbols: Vec<InputSymbol>,
+}
+
+pub struct InputSection {
+    pub name: String,
+    /// Section contents, or `None` for uninitialized sections like .bss
+    pub data: Option<Vec<u8>>,
+    pub size: u32,
+    pub alignment: u32,
+    pub relocations: Vec<InputRelocation>,
+    /// Instruction set at each offset, from the `$a`, `$t` and `$d` mapping symbols
+    pub mapping_symbols: BTreeMap<u32, MappingKind>,
+    /// Address assigned by the linker, or `None` if the section was discarded
+    pub address: Option<u32>,
+}
+
+pub struct InputSymbol {
+    pub name: String,
+    /// Index of the section containing this symbol, or `None` if the symbol is undefined or absolute
+    pub section: Option<usize>,
+    /// Whether the symbol has a fixed address, which is its `value`
+    pub absolute: bool,
+    /// Offset of the symbol within its section, or the address of an absolute symbol, without the Thumb bit
+    pub value: u32,
+    pub size: u32,
+    pub kind: object::SymbolKind,
+    pub global: bool,
+    pub weak: bool,
+    /// Whether the symbol points to Thumb code
+    pub thumb: bool,
+}
+
+pub struct InputRelocation {
+    pub offset: u32,
+    /// Index of the target symbol in [`InputObject::symbols`]
+    pub symbol: usize,
+    pub r_type: u32,
+    pub addend: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MappingKind {
+    Arm,
+    Thumb,
+    Data,
+}
+
+impl MappingKind {
+    fn parse(name: &str) -> Option<Self> {
+        let (prefix, _) = name.split_once('.').unwrap_or((name, ""));
+        match prefix {
+            "$a" => Some(Self::Arm),
+            "$t" => Some(Self::Thumb),
+            "$d" => Some(Self::Data),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Arm => "$a",
+            Self::Thumb => "$t",
+            Self::Data => "$d",
+        }
+    }
+}
+
+impl InputObject {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = read_file(path)?;
+        let file = object::File::parse(&*data).with_context(|| format!("Failed to parse object '{}'", path.display()))?;
+
+        let mut sections = vec![];
+        let mut section_indices = HashMap::new();
+        for section in file.sections() {
+            let SectionFlags::Elf { sh_flags } = section.flags() else { continue };
+            if (sh_flags & SHF_ALLOC as u64) == 0 {
+                continue;
+            }
+            let data = match section.kind() {
+                object::SectionKind::UninitializedData | object::SectionKind::UninitializedTls => None,
+                _ => Some(section.data()?.to_vec()),
+            };
+            section_indices.insert(section.index(), sections.len());
+            sections.push(InputSection {
+                name: section.name()?.to_string(),
+                data,
+                size: section.size() as u32,
+                alignment: (section.align() as u32).max(1),
+                relocations: vec![],
+                mapping_symbols: BTreeMap::new(),
+                address: None,
+            });
+        }
+
+        let mut symbols = vec![];
+        let mut symbol_indices = HashMap::new();
+        for symbol in file.symbols() {
+            let mut absolute = false;
+            let section = match symbol.section() {
+                SymbolSection::Section(index) => match section_indices.get(&index) {
+                    Some(&index) => Some(index),
+                    None => continue, // Symbol in a discarded section
+                },
+                SymbolSection::Undefined => None,
+                // Delinked objects name fixed addresses with local absolute symbols, which relocations may refer to
+                SymbolSection::Absolute if symbol.is_local() => {
+                    absolute = true;
+                    None
+                }
+                SymbolSection::Absolute => {
+                    log::warn!("Ignoring absolute symbol '{}' in '{}'", symbol.name()?, path.display());
+                    continue;
+                }
+                SymbolSection::Common => {
+                    bail!("Common symbol '{}' in '{}' is not supported", symbol.name()?, path.display());
+                }
+                SymbolSection::Unknown | SymbolSection::None => continue,
+            };
+            let name = symbol.name()?;
+            let value = symbol.address() as u32;
+
+            if let Some(section) = section {
+                if let Some(mapping_kind) = MappingKind::parse(name) {
+                    sections[section].mapping_symbols.insert(value, mapping_kind);
+                    continue;
+                }
+            }
+
+            symbol_indices.insert(symbol.index(), symbols.len());
+            // Only functions have the Thumb bit, data may be at any address
+            let thumb = symbol.kind() == object::SymbolKind::Text && (value & 1) != 0;
+            symbols.push(InputSymbol {
+                name: name.to_string(),
+                section,
+                absolute,
+                value: if thumb { value & !1 } else { value },
+                size: symbol.size() as u32,
+                kind: symbol.kind(),
+                global: symbol.is_global(),
+                weak: symbol.is_weak(),
+                thumb,
+            });
+        }
+
+        // Mapping symbols decide the instruction set of symbols which don't have the Thumb bit set
+        for symbol in &mut symbols {
+            let Some(section) = symbol.section else { continue };
+            if sections[section].mapping_kind_at(symbol.value) == Some(MappingKind::Thumb) {
+                symbol.thumb = true;
+            }
+        }
+
+        for section in file.sections() {
+            let Some(&section_index) = section_indices.get(&section.index()) else { continue };
+            let section_name = section.name()?;
+            for (offset, relocation) in section.relocations() {
+                let offset = offset as u32;
+                let location = || format!("at {offset:#x} in {section_name} of '{}'", path.display());
+
+                let RelocationFlags::Elf { r_type } = relocation.flags() else {
+                    bail!("Unsupported relocation {}", location());
+                };
+                let symbol = match relocation.target() {
+                    RelocationTarget::Symbol(index) => *symbol_indices
+                        .get(&index)
+                        .with_context(|| format!("Relocation {} has no target symbol", location()))?,
+                    RelocationTarget::Section(index) => {
+                        // Add a local symbol for the start of the target section
+                        let target_section = *section_indices
+                            .get(&index)
+                            .with_context(|| format!("Relocation {} targets a discarded section", location()))?;
+                        symbols.push(InputSymbol {
+                            name: sections[target_section].name.clone(),
+                            section: Some(target_section),
+                            absolute: false,
+                            value: 0,
+                            size: 0,
+                            kind: object::SymbolKind::Section,
+                            global: false,
+                            weak: false,
+                            thumb: false,
+                        });
+                        symbols.len() - 1
+                    }
+                    _ => bail!("Unsupported relocation target {}", location()),
+                };
+
+                let addend = if relocation.has_implicit_addend() {
+                    let Some(data) = &sections[section_index].data else {
+                        bail!("Relocation {} is in an uninitialized section", location());
+                    };
+                    implicit_addend(r_type, data.get(offset as usize..).unwrap_or_default()).with_context(location)?
+                } else {
+                    relocation.addend()
+                };
+
+                sections[section_index].relocations.push(InputRelocation { offset, symbol, r_type, addend });
+            }
+        }
+
+        Ok(Self { path: path.to_path_buf(), sections, symbols })
+    }
+
+    /// Returns the final address of a symbol without the Thumb bit, or `None` if the symbol is undefined or in a discarded
+    /// section.
+    pub fn symbol_address(&self, symbol: &InputSymbol) -> Option<u32> {
+        if symbol.absolute {
+            return Some(symbol.value);
+        }
+        Some(self.sections[symbol.section?].address? + symbol.value)
+    }
+}
+
+impl InputSymbol {
+    /// Returns whether the symbol is defined in its object, either in a section or at an absolute address.
+    pub fn is_defined(&self) -> bool {
+        self.section.is_some() || self.absolute
+    }
+}
+
+impl InputSection {
+    pub fn mapping_kind_at(&self, offset: u32) -> Option<MappingKind> {
+        self.mapping_symbols.range(..=offset).next_back().map(|(_, &kind)| kind)
+    }
+}
+
+/// Reads the addend which is stored in the relocated instruction or data, for objects with REL relocations.
+fn implicit_addend(r_type: u32, data: &[u8]) -> Result<i64> {
+    if data.len() < 4 {
+        bail!("Relocation reaches past the end of the section");
+    }
+    match r_type {
+        R_ARM_ABS32 => Ok(u32::from_le_slice(data) as i32 as i64),
+        R_ARM_PC24 | R_ARM_XPC25 | R_ARM_CALL | R_ARM_JUMP24 => {
+            let ins = u32::from_le_slice(data);
+            Ok((((ins & 0xffffff) << 8) as i32 >> 6) as i64)
+        }
+        R_ARM_THM_PC22 | R_ARM_THM_XPC22 => {
+            let high = u16::from_le_slice(data) as u32;
+            let low = u16::from_le_slice(&data[2..]) as u32;
+            let offset = ((high & 0x7ff) << 12) | ((low & 0x7ff) << 1);
+            Ok(((offset << 9) as i32 >> 9) as i64)
+        }
+        _ => bail!("Unsupported relocation type {r_type}"),
+    }
+}
@@ -0,0 +1,573 @@
+pub mod input;
+pub mod relocate;
+
+use std::{
+    collections::{hash_map, BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use ds_decomp::config::{
+    config::{ConfigModule, ConfigNewOverlay},
+    delinks::Delinks,
+    module::ModuleKind,
+};
+use ds_rom::rom::{raw::AutoloadKind, Rom};
+use object::{
+    build::elf::{Builder, SectionData, SectionId},
+    elf, Endianness,
+};
+
+use self::{
+    input::{InputObject, InputSymbol},
+    relocate::{relocate, RelocationTarget},
+};
+use crate::{
+    analysis::overlay_groups::OverlayGroups,
+    config::{
+        config::{new_section_boundary_name, ConfigNewOverlayExt, NEW_OVERLAY_SECTIONS},
+        delinks::DelinksExt,
+        section::SectionExt,
+    },
+    util::{
+        bytes::FromSlice,
+        io::{create_dir_all, write_file},
+        path::PathExt,
+    },
+};
+
+/// Links delinked and compiled objects into module binaries, following the same rules as the LCF generated by `dsd lcf`.
+pub struct Linker {
+    modules: Vec<LinkModule>,
+    objects: Vec<InputObject>,
+    globals: GlobalSymbols,
+}
+
+pub struct LinkModule {
+    pub kind: ModuleKind,
+    /// Name of the module's memory region in the LCF, e.g. ARM9 or OV012
+    pub memory_name: String,
+    /// Path to the module binary to output
+    pub binary_path: PathBuf,
+    /// Output sections in the order they are placed
+    sections: Vec<LinkSection>,
+    /// Indices of this module's objects
+    objects: Vec<usize>,
+    /// Module which a new overlay is placed after, or `None` for modules in the original ROM
+    after: Option<ModuleKind>,
+    pub start_address: u32,
+    /// End of the initialized sections, which is also the end of the module binary
+    pub code_end_address: u32,
+    /// End of all sections including .bss
+    pub end_address: u32,
+    /// Section boundary symbols, e.g. ARM9_BSS_START
+    pub boundary_symbols: Vec<(String, u32)>,
+}
+
+/// An output section of a module, such as `.text`.
+struct LinkSection {
+    name: String,
+    /// Name for the section boundary symbols, e.g. TEXT for ARM9_TEXT_START
+    boundary_name: String,
+    alignment: u32,
+    initialized: bool,
+    /// Indices of the objects whose input sections are placed in this section, in link order
+    objects: Vec<usize>,
+}
+
+/// Options for finding the objects of a module. An object is taken from `build_path` if its delink file is marked as
+/// complete, otherwise from `delinks_path`.
+pub struct LinkModuleOptions<'a> {
+    pub config_dir: &'a Path,
+    pub build_path: &'a Path,
+    pub delinks_path: &'a Path,
+}
+
+impl Linker {
+    pub fn new() -> Self {
+        Self { modules: vec![], objects: vec![], globals: GlobalSymbols::new() }
+    }
+
+    pub fn modules(&self) -> &[LinkModule] {
+        &self.modules
+    }
+
+    pub fn add_module(&mut self, module: &ConfigModule, kind: ModuleKind, options: &LinkModuleOptions) -> Result<()> {
+        let LinkModuleOptions { config_dir, build_path, delinks_path } = options;
+
+        let memory_name = match kind {
+            ModuleKind::Arm9 => "ARM9".to_string(),
+            ModuleKind::Overlay(id) => format!("OV{:03}", id),
+            ModuleKind::Autoload(AutoloadKind::Itcm) => "ITCM".to_string(),
+            ModuleKind::Autoload(AutoloadKind::Dtcm) => "DTCM".to_string(),
+            ModuleKind::Autoload(_) => bail!("Unknown autoload kind"),
+        };
+
+        let delinks = Delinks::from_file_and_generate_gaps(config_dir.join(&module.delinks), kind)?;
+        let mut objects = vec![];
+        for file in &delinks.files {
+            let (file_path, _) = file.split_file_ext();
+            let base_path = if file.complete { build_path } else { delinks_path };
+            let object_path = base_path.join(format!("{file_path}.o"));
+            let object = InputObject::from_file(&object_path)
+                .with_context(|| format!("Failed to load object for '{}' in {kind}", file.name))?;
+            objects.push(self.objects.len());
+            self.objects.push(object);
+        }
+
+        let sections = delinks
+            .sections
+            .sorted_by_address()
+            .into_iter()
+            .map(|section| LinkSection {
+                name: section.name().to_string(),
+                boundary_name: section.boundary_name(),
+                alignment: section.alignment(),
+                initialized: section.kind().is_initialized(),
+                objects: delinks
+                    .files
+                    .iter()
+                    .zip(&objects)
+                    .filter(|(file, _)| file.sections.by_name(section.name()).is_some())
+                    .map(|(_, &object_index)| object_index)
+                    .collect(),
+            })
+            .collect();
+
+        self.modules.push(LinkModule {
+            kind,
+            memory_name,
+            binary_path: config_dir.normalize_join(&module.object)?,
+            sections,
+            objects,
+            after: None,
+            start_address: 0,
+            code_end_address: 0,
+            end_address: 0,
+            boundary_symbols: vec![],
+        });
+        Ok(())
+    }
+
+    /// Adds an overlay which is not in the original ROM. Its objects are always taken from `build_path`, and every object
+    /// may contribute to every section, like in the LCF.
+    pub fn add_new_overlay(&mut self, overlay: &ConfigNewOverlay, options: &LinkModuleOptions) -> Result<()> {
+        let LinkModuleOptions { config_dir, build_path, .. } = options;
+
+        let mut objects = vec![];
+        for object_path in overlay.object_paths(build_path) {
+            let object = InputObject::from_file(&object_path)
+                .with_context(|| format!("Failed to load object '{}' of new overlay {}", object_path.display(), overlay.id))?;
+            objects.push(self.objects.len());
+            self.objects.push(object);
+        }
+
+        let sections = NEW_OVERLAY_SECTIONS
+            .iter()
+            .map(|&(name, kind, alignment)| LinkSection {
+                name: name.to_string(),
+                boundary_name: new_section_boundary_name(name),
+                alignment,
+                initialized: kind.is_initialized(),
+                objects: objects.clone(),
+            })
+            .collect();
+
+        self.modules.push(LinkModule {
+            kind: ModuleKind::Overlay(overlay.id),
+            memory_name: overlay.memory_name(),
+            binary_path: config_dir.normalize_join(&overlay.object)?,
+            sections,
+            objects,
+            after: Some(overlay.after.map_or(ModuleKind::Arm9, ModuleKind::Overlay)),
+            start_address: 0,
+            code_end_address: 0,
+            end_address: 0,
+            boundary_symbols: vec![],
+        });
+        Ok(())
+    }
+
+    /// Assigns addresses to all sections. The ARM9 and autoload modules are placed at their original addresses, while
+    /// overlays are placed after the modules they follow, like `ORIGIN = AFTER(...)` in the LCF. Overlays with an origin in
+    /// `overlay_origins` are placed there, and overlays which don't follow any module are placed at their original addresses.
+    /// New overlays are placed after the module they follow, including its .bss.
+    pub fn layout(&mut self, rom: &Rom, overlay_origins: &BTreeMap<u16, u32>) -> Result<()> {
+        self.place_module(ModuleKind::Arm9, rom.arm9().base_address())?;
+        let arm9_end = self.module(ModuleKind::Arm9)?.end_address;
+
+        for autoload in rom.arm9().autoloads()?.iter() {
+            if self.module(ModuleKind::Autoload(autoload.kind())).is_ok() {
+                self.place_module(ModuleKind::Autoload(autoload.kind()), autoload.base_address())?;
+            }
+        }
+
+        let overlay_groups = OverlayGroups::analyze(rom.arm9().end_address()?, rom.arm9_overlays(), overlay_origins);
+        for group in overlay_groups.iter() {
+            let origin = if group.fixed {
+                group.start_address
+            } else {
+                let mut origin = arm9_end;
+                for &id in &group.after {
+                    origin = origin.max(self.module(ModuleKind::Overlay(id))?.end_address);
+                }
+                origin
+            };
+            for &id in &group.overlays {
+                self.place_module(ModuleKind::Overlay(id), origin)?;
+            }
+        }
+
+        // New overlays are placed after their module in the order they were added, like `ORIGIN = AFTER(...)`
+        let new_overlays = self.modules.iter().filter_map(|module| Some((module.kind, module.after?))).collect::<Vec<_>>();
+        for (kind, after) in new_overlays {
+            let origin = self.module(after)?.end_address;
+            self.place_module(kind, origin)?;
+        }
+
+        for object in &self.objects {
+            for section in &object.sections {
+                if section.address.is_none() && section.size > 0 {
+                    log::warn!(
+                        "Discarding section {} in '{}' as it's not in the delinks file or the new overlay's sections",
+                        section.name,
+                        object.path.display()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn module(&self, kind: ModuleKind) -> Result<&LinkModule> {
+        self.modules.iter().find(|module| module.kind == kind).with_context(|| format!("Module {kind} was not added"))
+    }
+
+    fn place_module(&mut self, kind: ModuleKind, origin: u32) -> Result<()> {
+        let Self { modules, objects, .. } = self;
+        let module =
+            modules.iter_mut().find(|module| module.kind == kind).with_context(|| format!("Module {kind} was not added"))?;
+
+        let mut address = origin;
+        let mut code_end_address = origin;
+        for section in &module.sections {
+            address = address.next_multiple_of(section.alignment);
+            let start_address = address;
+
+            for &object_index in &section.objects {
+                let object = &mut objects[object_index];
+                let Some(input_section) = object.sections.iter_mut().find(|s| s.name == section.name) else {
+                    log::debug!("Object '{}' has no {} section", object.path.display(), section.name);
+                    continue;
+                };
+                address = address.next_multiple_of(input_section.alignment);
+                input_section.address = Some(address);
+                address += input_section.size;
+            }
+
+            let boundary_name = &section.boundary_name;
+            module.boundary_symbols.push((format!("{}_{boundary_name}_START", module.memory_name), start_address));
+            module.boundary_symbols.push((format!("{}_{boundary_name}_END", module.memory_name), address));
+
+            if section.initialized {
+                code_end_address = code_end_address.max(address);
+            }
+        }
+
+        module.start_address = origin;
+        module.code_end_address = code_end_address;
+        module.end_address = address;
+        Ok(())
+    }
+
+    /// Builds the global symbol table. Strong definitions take precedence over weak ones.
+    pub fn resolve_symbols(&mut self) -> Result<()> {
+        self.globals = resolve_symbols(&self.objects)?;
+        Ok(())
+    }
+
+    /// Applies all relocations in the placed sections.
+    pub fn relocate(&mut self) -> Result<()> {
+        let words = relocate_objects(&self.objects, |name| {
+            let &(object_index, symbol_index) = self.globals.get(name).context("the symbol is undefined")?;
+            let object = &self.objects[object_index];
+            object_target(object, &object.symbols[symbol_index]).context("the symbol is in a discarded section")
+        })?;
+        write_relocated_words(&mut self.objects, words);
+        Ok(())
+    }
+
+    /// Returns the contents of a module's binary, which spans all of its initialized sections.
+    pub fn module_binary(&self, module: &LinkModule) -> Vec<u8> {
+        let mut binary = vec![0; (module.code_end_address - module.start_address) as usize];
+        for &object_index in &module.objects {
+            for section in &self.objects[object_index].sections {
+                let (Some(address), Some(data)) = (section.address, &section.data) else { continue };
+                let offset = (address - module.start_address) as usize;
+                binary[offset..offset + data.len()].copy_from_slice(data);
+            }
+        }
+        binary
+    }
+
+    pub fn write_binaries(&self) -> Result<()> {
+        for module in &self.modules {
+            create_dir_all(module.binary_path.parent().unwrap())?;
+            write_file(&module.binary_path, self.module_binary(module))?;
+        }
+        Ok(())
+    }
+
+    /// Writes a linked ELF with one section per memory region, all symbols at their final addresses and the section
+    /// boundary symbols. This is the ELF which `dsd rom config` expects.
+    pub fn write_elf<P: AsRef<Path>>(&self, path: P, entry: &str) -> Result<()> {
+        let path = path.as_ref();
+
+        let mut builder = Builder::new(Endianness::Little, false);
+        builder.header.e_type = elf::ET_EXEC;
+        builder.header.e_machine = elf::EM_ARM;
+        builder.header.e_flags = elf::EF_ARM_EABI_VER5;
+
+        let &(entry_object, entry_symbol) =
+            self.globals.get(entry).with_context(|| format!("Entry symbol '{entry}' not found"))?;
+        let entry_object = &self.objects[entry_object];
+        let entry_symbol = &entry_object.symbols[entry_symbol];
+        builder.header.e_entry = (entry_object.symbol_address(entry_symbol).unwrap() | entry_symbol.thumb as u32) as u64;
+
+        for module in &self.modules {
+            let code_section = builder.sections.add();
+            code_section.name = module.memory_name.clone().into_bytes().into();
+            code_section.sh_type = elf::SHT_PROGBITS;
+            code_section.sh_flags = (elf::SHF_ALLOC | elf::SHF_EXECINSTR | elf::SHF_WRITE) as u64;
+            code_section.sh_addr = module.start_address as u64;
+            code_section.sh_addralign = 4;
+            code_section.data = SectionData::Data(self.module_binary(module).into());
+            let code_section_id = code_section.id();
+
+            let bss_section_id = if module.end_address > module.code_end_address {
+                let bss_section = builder.sections.add();
+                bss_section.name = format!("{}.bss", module.memory_name).into_bytes().into();
+                bss_section.sh_type = elf::SHT_NOBITS;
+                bss_section.sh_flags = (elf::SHF_ALLOC | elf::SHF_WRITE) as u64;
+                bss_section.sh_addr = module.code_end_address as u64;
+                bss_section.sh_addralign = 4;
+                bss_section.data = SectionData::UninitializedData((module.end_address - module.code_end_address) as u64);
+                Some(bss_section.id())
+            } else {
+                None
+            };
+
+            for &object_index in &module.objects {
+                self.add_object_symbols(&mut builder, &self.objects[object_index], code_section_id, bss_section_id);
+            }
+
+            for (name, address) in &module.boundary_symbols {
+                let symbol = builder.symbols.add();
+                symbol.name = name.clone().into_bytes().into();
+                symbol.set_st_info(elf::STB_GLOBAL, elf::STT_NOTYPE);
+                symbol.st_shndx = elf::SHN_ABS;
+                symbol.st_value = *address as u64;
+            }
+        }
+
+        for (name, data) in
+            [(".symtab", SectionData::Symbol), (".strtab", SectionData::String), (".shstrtab", SectionData::SectionString)]
+        {
+            let section = builder.sections.add();
+            section.name = name.into();
+            section.data = data;
+        }
+
+        let mut buffer = Vec::new();
+        builder.write(&mut buffer)?;
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        write_file(path, buffer)?;
+
+        Ok(())
+    }
+
+    fn add_object_symbols(
+        &self,
+        builder: &mut Builder<'_>,
+        object: &InputObject,
+        code_section_id: SectionId,
+        bss_section_id: Option<SectionId>,
+    ) {
+        let section_id = |section: usize| {
+            if object.sections[section].data.is_some() {
+                Some(code_section_id)
+            } else {
+                bss_section_id
+            }
+        };
+
+        for symbol in &object.symbols {
+            if symbol.kind == object::SymbolKind::Section {
+                continue;
+            }
+            let Some(address) = object.symbol_address(symbol) else { continue };
+
+            let st_bind = match (symbol.global, symbol.weak) {
+                (false, _) => elf::STB_LOCAL,
+                (true, false) => elf::STB_GLOBAL,
+                (true, true) => elf::STB_WEAK,
+            };
+            let st_type = match symbol.kind {
+                object::SymbolKind::Text => elf::STT_FUNC,
+                object::SymbolKind::Data => elf::STT_OBJECT,
+                _ => elf::STT_NOTYPE,
+            };
+            let thumb_bit = if st_type == elf::STT_FUNC && symbol.thumb { 1 } else { 0 };
+
+            let obj_symbol = builder.symbols.add();
+            obj_symbol.name = symbol.name.clone().into_bytes().into();
+            match symbol.section {
+                Some(section) => obj_symbol.section = section_id(section),
+                None => obj_symbol.st_shndx = elf::SHN_ABS,
+            }
+            obj_symbol.set_st_info(st_bind, st_type);
+            obj_symbol.st_value = (address | thumb_bit) as u64;
+            obj_symbol.st_size = symbol.size as u64;
+        }
+
+        for (section_index, section) in object.sections.iter().enumerate() {
+            let Some(section_address) = section.address else { continue };
+            for (&offset, &kind) in &section.mapping_symbols {
+                let mapping_symbol = builder.symbols.add();
+                mapping_symbol.name = kind.name().into();
+                mapping_symbol.section = section_id(section_index);
+                mapping_symbol.set_st_info(elf::STB_LOCAL, elf::STT_NOTYPE);
+                mapping_symbol.st_value = (section_address + offset) as u64;
+            }
+        }
+    }
+}
+
+/// Maps global symbol names to an object index and a symbol index within that object.
+pub type GlobalSymbols = HashMap<String, (usize, usize)>;
+
+/// A relocated word to be written to a section of an object.
+pub struct RelocatedWord {
+    object: usize,
+    section: usize,
+    offset: usize,
+    value: u32,
+}
+
+/// Builds the global symbol table of placed objects. Strong definitions take precedence over weak ones, and symbols in
+/// discarded sections are left out.
+pub fn resolve_symbols(objects: &[InputObject]) -> Result<GlobalSymbols> {
+    let mut globals = GlobalSymbols::new();
+    let mut error = false;
+    for (object_index, object) in objects.iter().enumerate() {
+        for (symbol_index, symbol) in object.symbols.iter().enumerate() {
+            if !symbol.global || object.symbol_address(symbol).is_none() {
+                continue;
+            }
+            match globals.entry(symbol.name.clone()) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert((object_index, symbol_index));
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    let (other_object_index, other_symbol_index) = *entry.get();
+                    let other_object = &objects[other_object_index];
+                    let other_symbol = &other_object.symbols[other_symbol_index];
+                    if other_symbol.weak && !symbol.weak {
+                        entry.insert((object_index, symbol_index));
+                    } else if !other_symbol.weak && !symbol.weak {
+                        log::error!(
+                            "Symbol '{}' is defined in both '{}' and '{}'",
+                            symbol.name,
+                            other_object.path.display(),
+                            object.path.display()
+                        );
+                        error = true;
+                    }
+                }
+            }
+        }
+    }
+    if error {
+        bail!("Failed to resolve symbols, see errors above");
+    }
+    Ok(globals)
+}
+
+/// Computes the relocated words in the placed sections of the objects. Symbols which are not defined in the object of the
+/// relocation are resolved with `resolve_undefined`, which returns why a symbol can't be resolved otherwise.
+pub fn relocate_objects<F>(objects: &[InputObject], resolve_undefined: F) -> Result<Vec<RelocatedWord>>
+where
+    F: Fn(&str) -> Result<RelocationTarget>,
+{
+    let mut words = vec![];
+    let mut error = false;
+    for (object_index, object) in objects.iter().enumerate() {
+        for (section_index, section) in object.sections.iter().enumerate() {
+            let (Some(section_address), Some(data)) = (section.address, &section.data) else { continue };
+            for relocation in &section.relocations {
+                let symbol = &object.symbols[relocation.symbol];
+                let target = if symbol.is_defined() {
+                    object_target(object, symbol).context("the symbol is in a discarded section")
+                } else {
+                    resolve_undefined(&symbol.name)
+                };
+                let target = match target {
+                    Ok(target) => target,
+                    Err(e) => {
+                        log::error!("Cannot resolve '{}' referenced in '{}': {e:#}", symbol.name, object.path.display());
+                        error = true;
+                        continue;
+                    }
+                };
+
+                let offset = relocation.offset as usize;
+                let Some(word) = data.get(offset..offset + 4) else {
+                    log::error!(
+                        "Relocation at {offset:#x} in {} of '{}' reaches past the end of the section",
+                        section.name,
+                        object.path.display()
+                    );
+                    error = true;
+                    continue;
+                };
+                let word = u32::from_le_slice(word);
+                let source = section_address + relocation.offset;
+                match relocate(relocation.r_type, word, source, &target, relocation.addend) {
+                    Ok(value) => words.push(RelocatedWord { object: object_index, section: section_index, offset, value }),
+                    Err(e) => {
+                        log::error!(
+                            "Failed to relocate {source:#010x} in '{}' to '{}': {e}",
+                            object.path.display(),
+                            symbol.name
+                        );
+                        error = true;
+                    }
+                }
+            }
+        }
+    }
+    if error {
+        bail!("Failed to relocate, see errors above");
+    }
+    Ok(words)
+}
+
+/// Writes relocated words from [`relocate_objects`] to the sections of the objects.
+pub fn write_relocated_words(objects: &mut [InputObject], words: Vec<RelocatedWord>) {
+    for RelocatedWord { object, section, offset, value } in words {
+        let data = objects[object].sections[section].data.as_mut().unwrap();
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Returns the relocation target of a symbol, or `None` if it's undefined or in a discarded section.
+pub fn object_target(object: &InputObject, symbol: &InputSymbol) -> Option<RelocationTarget> {
+    Some(RelocationTarget {
+        address: object.symbol_address(symbol)?,
+        thumb: symbol.thumb,
+        function: symbol.kind == object::SymbolKind::Text,
+    })
+}
@@ -0,0 +1,166 @@
+use anyhow::{bail, Result};
+use object::elf::{R_ARM_ABS32, R_ARM_CALL, R_ARM_JUMP24, R_ARM_PC24, R_ARM_THM_PC22, R_ARM_THM_XPC22, R_ARM_XPC25};
+
+/// The symbol that a relocation resolves to.
+pub struct RelocationTarget {
+    /// Address of the symbol, without the Thumb bit
+    pub address: u32,
+    /// Whether the symbol points to Thumb code
+    pub thumb: bool,
+    /// Whether the symbol is a function, which means pointers to it get the Thumb bit set if it's a Thumb function
+    pub function: bool,
+}
+
+const ARM_BRANCH_RANGE: std::ops::Range<i64> = -0x2000000..0x2000000;
+const THUMB_BRANCH_RANGE: std::ops::Range<i64> = -0x400000..0x400000;
+
+/// Computes the new value of the 32-bit word being relocated, given its old value `word`. The relocation types are the
+/// ones produced by `dsd delink` for each `RelocationKind`, along with the Thumb BLX type emitted by mwcc:
+/// - `arm_call` and `arm_branch`: `R_ARM_PC24`
+/// - `arm_call_thumb`: `R_ARM_XPC25`
+/// - `thumb_call` and `thumb_call_arm`: `R_ARM_THM_PC22` or `R_ARM_THM_XPC22`
+/// - `load`: `R_ARM_ABS32`
+///
+/// The `R_ARM_CALL` and `R_ARM_JUMP24` types emitted by GNU as are also supported.
+///
+/// Calls are converted between BL and BLX depending on the instruction set of the target, like mwld does with
+/// `-interworking`.
+pub fn relocate(r_type: u32, word: u32, source: u32, target: &RelocationTarget, addend: i64) -> Result<u32> {
+    let destination = target.address as i64 + addend;
+    match r_type {
+        R_ARM_ABS32 => {
+            let thumb_bit = if target.function && target.thumb { 1 } else { 0 };
+            Ok(destination as u32 | thumb_bit)
+        }
+        R_ARM_PC24 | R_ARM_XPC25 | R_ARM_CALL | R_ARM_JUMP24 => {
+            let offset = destination - source as i64;
+            check_range(offset, ARM_BRANCH_RANGE)?;
+
+            let condition = word >> 28;
+            let is_blx = condition == 0xf;
+            let is_call = r_type == R_ARM_XPC25 || is_blx || (word & 0x0f000000) == 0x0b000000;
+            if target.thumb {
+                if !is_call || (r_type != R_ARM_XPC25 && condition != 0xe && !is_blx) {
+                    bail!("Cannot branch from ARM to Thumb code at {:#010x} without an unconditional call", target.address);
+                }
+                // BLX
+                let h_bit = ((offset >> 1) & 1) as u32;
+                Ok(0xfa000000 | (h_bit << 24) | ((offset >> 2) as u32 & 0xffffff))
+            } else {
+                if (offset & 3) != 0 {
+                    bail!("Misaligned ARM branch destination {:#010x}", destination);
+                }
+                if r_type == R_ARM_XPC25 || is_blx {
+                    // BL
+                    Ok(0xeb000000 | ((offset >> 2) as u32 & 0xffffff))
+                } else {
+                    Ok((word & 0xff000000) | ((offset >> 2) as u32 & 0xffffff))
+                }
+            }
+        }
+        R_ARM_THM_PC22 | R_ARM_THM_XPC22 => {
+            let (offset, low_opcode) = if target.thumb {
+                // BL
+                (destination - source as i64, 0xf800)
+            } else {
+                // BLX, which is relative to the word-aligned PC
+                (destination - (source & !3) as i64, 0xe800)
+            };
+            check_range(offset, THUMB_BRANCH_RANGE)?;
+            if !target.thumb && (offset & 3) != 0 {
+                bail!("Misaligned ARM call destination {:#010x}", destination);
+            }
+
+            let high = 0xf000 | ((offset >> 12) as u32 & 0x7ff);
+            let low = low_opcode | ((offset >> 1) as u32 & 0x7ff);
+            Ok(high | (low << 16))
+        }
+        _ => bail!("Unsupported relocation type {r_type}"),
+    }
+}
+
+fn check_range(offset: i64, range: std::ops::Range<i64>) -> Result<()> {
+    if !range.contains(&offset) {
+        bail!("Branch offset {offset:#x} is out of range");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arm(address: u32) -> RelocationTarget {
+        RelocationTarget { address, thumb: false, function: true }
+    }
+
+    fn thumb(address: u32) -> RelocationTarget {
+        RelocationTarget { address, thumb: true, function: true }
+    }
+
+    #[test]
+    fn arm_branches() {
+        #[rustfmt::skip]
+        let cases = [
+            // ARM to ARM
+            (R_ARM_PC24,  0xeb000000, 0x02000000, arm(0x02000100),   0xeb00003e), // bl
+            (R_ARM_PC24,  0x0a000000, 0x02000000, arm(0x02000100),   0x0a00003e), // beq
+            (R_ARM_PC24,  0xeb000000, 0x02000100, arm(0x02000000),   0xebffffbe), // bl backwards
+            (R_ARM_XPC25, 0xfa000000, 0x02000000, arm(0x02000100),   0xeb00003e), // blx -> bl
+            // ARM to Thumb
+            (R_ARM_PC24,  0xeb000000, 0x02000000, thumb(0x02000100), 0xfa00003e), // bl -> blx
+            (R_ARM_PC24,  0xeb000000, 0x02000000, thumb(0x02000102), 0xfb00003e), // bl -> blx with H bit
+            (R_ARM_XPC25, 0xfa000000, 0x02000000, thumb(0x02000102), 0xfb00003e), // blx
+        ];
+        for (r_type, word, source, target, expected) in cases {
+            let result = relocate(r_type, word, source, &target, -8).unwrap();
+            assert_eq!(result, expected, "r_type {r_type}, word {word:#010x}, target {:#010x}", target.address);
+        }
+    }
+
+    #[test]
+    fn arm_branch_errors() {
+        // Only unconditional calls can switch to Thumb
+        assert!(relocate(R_ARM_PC24, 0xea000000, 0x02000000, &thumb(0x02000100), -8).is_err());
+        assert!(relocate(R_ARM_PC24, 0x0b000000, 0x02000000, &thumb(0x02000100), -8).is_err());
+        // Misaligned ARM destination
+        assert!(relocate(R_ARM_PC24, 0xeb000000, 0x02000000, &arm(0x02000102), -8).is_err());
+        // Out of range
+        assert!(relocate(R_ARM_PC24, 0xeb000000, 0x02000000, &arm(0x04000008), -8).is_err());
+    }
+
+    #[test]
+    fn thumb_calls() {
+        #[rustfmt::skip]
+        let cases = [
+            // Thumb to Thumb
+            (R_ARM_THM_PC22,  0x02000000, thumb(0x02000100), 0xf87ef000), // bl
+            (R_ARM_THM_PC22,  0x02000100, thumb(0x02000000), 0xff7ef7ff), // bl backwards
+            (R_ARM_THM_XPC22, 0x02000000, thumb(0x02000100), 0xf87ef000), // blx -> bl
+            // Thumb to ARM, relative to the word-aligned PC
+            (R_ARM_THM_PC22,  0x02000000, arm(0x02000100),   0xe87ef000), // bl -> blx
+            (R_ARM_THM_PC22,  0x02000002, arm(0x02000100),   0xe87ef000), // bl -> blx
+            (R_ARM_THM_XPC22, 0x02000002, arm(0x02000100),   0xe87ef000), // blx
+        ];
+        for (r_type, source, target, expected) in cases {
+            let result = relocate(r_type, 0xf800f000, source, &target, -4).unwrap();
+            assert_eq!(result, expected, "r_type {r_type}, source {source:#010x}, target {:#010x}", target.address);
+        }
+    }
+
+    #[test]
+    fn thumb_call_errors() {
+        // Misaligned ARM destination
+        assert!(relocate(R_ARM_THM_PC22, 0xf800f000, 0x02000000, &arm(0x02000102), -4).is_err());
+        // Out of range
+        assert!(relocate(R_ARM_THM_PC22, 0xf800f000, 0x02000000, &thumb(0x02400004), -4).is_err());
+    }
+
+    #[test]
+    fn pointers() {
+        assert_eq!(relocate(R_ARM_ABS32, 0, 0x02000000, &arm(0x02000100), 0).unwrap(), 0x02000100);
+        assert_eq!(relocate(R_ARM_ABS32, 0, 0x02000000, &thumb(0x02000100), 0).unwrap(), 0x02000101);
+        let data = RelocationTarget { address: 0x02000100, thumb: true, function: false };
+        assert_eq!(relocate(R_ARM_ABS32, 0, 0x02000000, &data, 4).unwrap(), 0x02000104);
+    }
+}
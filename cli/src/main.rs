@@ -1,6 +1,9 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use ds_decomp_cli::cmd::{CheckArgs, Delink, Disassemble, ImportArgs, Init, Lcf, Objdiff, RomArgs};
+use ds_decomp_cli::cmd::{
+    CheckArgs, DataInitializers, Delink, Disassemble, Elf, FxTypes, Headers, ImportArgs, Init, IoRegArgs, Lcf, Link, Objdiff,
+    Patch, Query, Rename, RomArgs, Scaffold, Serve, Split, Watch,
+};
 use log::LevelFilter;
 
 /// Command-line toolkit for decompiling DS games.
@@ -26,6 +29,20 @@ enum Command {
     Import(ImportArgs),
     Check(CheckArgs),
     Objdiff(Objdiff),
+    Elf(Elf),
+    Link(Link),
+    Scaffold(Scaffold),
+    Headers(Headers),
+    #[command(name = "data")]
+    DataInitializers(DataInitializers),
+    Split(Split),
+    Query(Query),
+    Rename(Rename),
+    Serve(Serve),
+    Watch(Watch),
+    Patch(Patch),
+    IoReg(IoRegArgs),
+    FxTypes(FxTypes),
 }
 
 impl Command {
@@ -39,6 +56,19 @@ impl Command {
             Command::Import(import) => import.run(),
             Command::Check(check) => check.run(),
             Command::Objdiff(objdiff) => objdiff.run(),
+            Command::Elf(elf) => elf.run(),
+            Command::Link(link) => link.run(),
+            Command::Scaffold(scaffold) => scaffold.run(),
+            Command::Headers(headers) => headers.run(),
+            Command::DataInitializers(data) => data.run(),
+            Command::Split(split) => split.run(),
+            Command::Query(query) => query.run(),
+            Command::Rename(rename) => rename.run(),
+            Command::Serve(serve) => serve.run(),
+            Command::Watch(watch) => watch.run(),
+            Command::Patch(patch) => patch.run(),
+            Command::IoReg(io_reg) => io_reg.run(),
+            Command::FxTypes(fx_types) => fx_types.run(),
         }
     }
 }
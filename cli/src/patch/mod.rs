@@ -0,0 +1,385 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use ds_decomp::config::{
+    config::Config,
+    module::ModuleKind,
+    symbol::{InstructionMode, Symbol, SymbolKind, SymbolMaps},
+};
+use object::elf::{R_ARM_CALL, R_ARM_THM_PC22};
+use serde::Deserialize;
+
+use crate::{
+    config::config::ConfigExt,
+    linker::{
+        input::InputObject,
+        object_target,
+        relocate::{relocate, RelocationTarget},
+        relocate_objects, resolve_symbols, write_relocated_words, GlobalSymbols,
+    },
+    util::{
+        io::{open_file, read_file},
+        parse::parse_hex_address,
+    },
+};
+
+/// A patch file, which declares the objects to link into the game and the hooks which connect them to the game's code.
+#[derive(Deserialize)]
+pub struct PatchConfig {
+    /// Command to run before linking, to compile and assemble the patch objects
+    #[serde(default)]
+    pub build: Vec<String>,
+    /// Paths to the patch objects, relative to the patch file
+    pub objects: Vec<PathBuf>,
+    /// Where to place the patch objects
+    pub region: PatchRegion,
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchRegion {
+    /// Unused space inside a module's binary, such as padding or code which the patches make unreachable
+    FreeSpace { module: String, start: String, end: String },
+    /// An overlay which is added to the ROM, loaded at the given address
+    NewOverlay { id: u16, address: String },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Hook {
+    /// Replaces a game function by jumping to `function` at its start
+    Replace {
+        target: String,
+        #[serde(default)]
+        module: Option<String>,
+        function: String,
+    },
+    /// Replaces the instruction at the target with a call to `function`
+    Call {
+        target: String,
+        #[serde(default)]
+        module: Option<String>,
+        function: String,
+    },
+    /// Overwrites data at the target with hexadecimal `bytes`, or with the address of the `pointer` symbol
+    Data {
+        target: String,
+        #[serde(default)]
+        module: Option<String>,
+        #[serde(default)]
+        bytes: Option<String>,
+        #[serde(default)]
+        pointer: Option<String>,
+    },
+}
+
+/// The binary of a module in the ROM, which hooks and patch objects are written to.
+pub struct PatchModule {
+    pub kind: ModuleKind,
+    pub base_address: u32,
+    pub binary: Vec<u8>,
+    /// Path to the original binary
+    pub binary_path: PathBuf,
+    pub changed: bool,
+}
+
+/// Links patch objects into the game's modules and applies hooks, reusing the objects and relocations of the linker.
+pub struct Patcher<'a> {
+    config: &'a Config,
+    symbol_maps: &'a SymbolMaps,
+    modules: BTreeMap<ModuleKind, PatchModule>,
+    objects: Vec<InputObject>,
+    globals: GlobalSymbols,
+    /// Module where the patch objects are placed, which is searched first for game symbols
+    region_module: Option<ModuleKind>,
+    /// Binary of the new overlay, if the patch objects are placed in one
+    new_overlay: Option<PatchModule>,
+}
+
+impl PatchConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        serde_yml::from_reader(open_file(path)?).with_context(|| format!("Failed to parse patch file '{}'", path.display()))
+    }
+}
+
+impl<'a> Patcher<'a> {
+    pub fn new(config: &'a Config, symbol_maps: &'a SymbolMaps, modules: Vec<PatchModule>) -> Self {
+        Self {
+            config,
+            symbol_maps,
+            modules: modules.into_iter().map(|module| (module.kind, module)).collect(),
+            objects: vec![],
+            globals: GlobalSymbols::new(),
+            region_module: None,
+            new_overlay: None,
+        }
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = &PatchModule> {
+        self.modules.values()
+    }
+
+    pub fn new_overlay(&self) -> Option<&PatchModule> {
+        self.new_overlay.as_ref()
+    }
+
+    pub fn add_object<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let object =
+            InputObject::from_file(path).with_context(|| format!("Failed to load patch object '{}'", path.display()))?;
+        self.objects.push(object);
+        Ok(())
+    }
+
+    /// Places the sections of all patch objects in the region, one after another. Uninitialized sections are filled with
+    /// zeros, since the region has no .bss of its own.
+    pub fn layout(&mut self, region: &PatchRegion) -> Result<()> {
+        let (start, end) = match region {
+            PatchRegion::FreeSpace { module, start, end } => {
+                let (_, kind) = self.config.module_by_name(module).with_context(|| format!("Module '{module}' not found"))?;
+                let start = parse_hex_address(start).with_context(|| format!("Invalid start address '{start}'"))?;
+                let end = parse_hex_address(end).with_context(|| format!("Invalid end address '{end}'"))?;
+                let module = self.modules.get(&kind).with_context(|| format!("Module {kind} was not loaded for patching"))?;
+                let module_end = module.base_address + module.binary.len() as u32;
+                if start < module.base_address || end > module_end || start > end {
+                    bail!(
+                        "Free space {start:#010x}..{end:#010x} is not inside {} at {:#010x}..{module_end:#010x}",
+                        kind,
+                        module.base_address
+                    );
+                }
+                self.region_module = Some(kind);
+                (start, Some(end))
+            }
+            PatchRegion::NewOverlay { id, address } => {
+                let address = parse_hex_address(address).with_context(|| format!("Invalid address '{address}'"))?;
+                self.new_overlay = Some(PatchModule {
+                    kind: ModuleKind::Overlay(*id),
+                    base_address: address,
+                    binary: vec![],
+                    binary_path: PathBuf::new(),
+                    changed: true,
+                });
+                (address, None)
+            }
+        };
+
+        let mut address = start;
+        for object in &mut self.objects {
+            for section in &mut object.sections {
+                address = address.next_multiple_of(section.alignment);
+                section.address = Some(address);
+                address += section.size;
+            }
+        }
+        if let Some(end) = end {
+            if address > end {
+                bail!("Patch objects need {:#x} bytes, but the free space only has {:#x}", address - start, end - start);
+            }
+        }
+        log::debug!("Patch objects placed at {start:#010x}..{address:#010x}");
+
+        Ok(())
+    }
+
+    /// Builds the global symbol table of the patch objects. Strong definitions take precedence over weak ones.
+    pub fn resolve_symbols(&mut self) -> Result<()> {
+        self.globals = resolve_symbols(&self.objects)?;
+        Ok(())
+    }
+
+    /// Applies the relocations of the patch objects and writes them to the region. Symbols which are not defined by the
+    /// patch objects are looked up in the game's symbols.
+    pub fn relocate(&mut self) -> Result<()> {
+        let words = relocate_objects(&self.objects, |name| self.resolve(name).context("the symbol is undefined"))?;
+        write_relocated_words(&mut self.objects, words);
+
+        let mut writes = vec![];
+        for object in &self.objects {
+            for section in &object.sections {
+                let Some(address) = section.address else { continue };
+                let data = section.data.clone().unwrap_or_else(|| vec![0; section.size as usize]);
+                writes.push((address, data));
+            }
+        }
+        match (&mut self.new_overlay, self.region_module) {
+            (Some(overlay), _) => {
+                for (address, data) in writes {
+                    let offset = (address - overlay.base_address) as usize;
+                    if overlay.binary.len() < offset + data.len() {
+                        overlay.binary.resize(offset + data.len(), 0);
+                    }
+                    overlay.binary[offset..offset + data.len()].copy_from_slice(&data);
+                }
+            }
+            (None, Some(kind)) => {
+                for (address, data) in writes {
+                    self.write(kind, address, &data)?;
+                }
+            }
+            (None, None) => bail!("Patch objects have not been placed"),
+        }
+
+        Ok(())
+    }
+
+    pub fn apply_hook(&mut self, hook: &Hook) -> Result<()> {
+        match hook {
+            Hook::Replace { target, module, function } => {
+                let (kind, address, symbol) = self.find_target(target, module.as_deref())?;
+                let Some(SymbolKind::Function(game_function)) = symbol.map(|symbol| symbol.kind) else {
+                    bail!("Cannot replace '{target}', it is not a function");
+                };
+                let destination = self.resolve(function).with_context(|| format!("Function '{function}' not found"))?;
+                let destination_address = destination.address | destination.thumb as u32;
+
+                // Jump with `ldr pc, [pc, #-4]`, which switches to Thumb if the destination has the Thumb bit set
+                let mut code = vec![];
+                if game_function.mode == InstructionMode::Thumb {
+                    // Switch to ARM with `bx pc`, which must be word-aligned as it branches to its own address + 4
+                    if address % 4 != 0 {
+                        code.extend(0x46c0u16.to_le_bytes()); // nop
+                    }
+                    code.extend(0x4778u16.to_le_bytes());
+                    code.extend(0x46c0u16.to_le_bytes()); // nop
+                }
+                code.extend(0xe51ff004u32.to_le_bytes());
+                code.extend(destination_address.to_le_bytes());
+                if code.len() as u32 > game_function.size {
+                    bail!("Function '{target}' is too small to be replaced, it needs {} bytes", code.len());
+                }
+                self.write(kind, address, &code)?;
+            }
+            Hook::Call { target, module, function } => {
+                let (kind, address, _) = self.find_target(target, module.as_deref())?;
+                let symbol_map = self.symbol_maps.get(kind).unwrap();
+                let (game_function, _) = symbol_map
+                    .get_function_containing(address)
+                    .with_context(|| format!("Call target {address:#010x} is not inside a function"))?;
+                let destination = self.resolve(function).with_context(|| format!("Function '{function}' not found"))?;
+
+                // Relocate a BL instruction to the destination, converting it to BLX if needed
+                let code = match game_function.mode {
+                    InstructionMode::Arm => relocate(R_ARM_CALL, 0xeb000000, address, &destination, -8)?.to_le_bytes(),
+                    InstructionMode::Thumb => relocate(R_ARM_THM_PC22, 0, address, &destination, -4)?.to_le_bytes(),
+                };
+                self.write(kind, address, &code)?;
+            }
+            Hook::Data { target, module, bytes, pointer } => {
+                let (kind, address, _) = self.find_target(target, module.as_deref())?;
+                let data = match (bytes, pointer) {
+                    (Some(bytes), None) => parse_hex_bytes(bytes).with_context(|| format!("Invalid bytes '{bytes}'"))?,
+                    (None, Some(pointer)) => {
+                        let target = self.resolve(pointer).with_context(|| format!("Symbol '{pointer}' not found"))?;
+                        let thumb_bit = target.function && target.thumb;
+                        (target.address | thumb_bit as u32).to_le_bytes().to_vec()
+                    }
+                    _ => bail!("Data hook at '{target}' must have one of 'bytes' and 'pointer'"),
+                };
+                self.write(kind, address, &data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds a symbol by name in the patch objects, or in the game's symbols. Game symbols are searched for in the region's
+    /// module first, then in the modules which are always loaded, and then in the other modules.
+    fn resolve(&self, name: &str) -> Option<RelocationTarget> {
+        if let Some(&(object_index, symbol_index)) = self.globals.get(name) {
+            let object = &self.objects[object_index];
+            return object_target(object, &object.symbols[symbol_index]);
+        }
+
+        let mut modules = self.config.iter_modules().map(|(_, kind)| kind).collect::<Vec<_>>();
+        modules.sort_by_key(|&kind| (Some(kind) != self.region_module, matches!(kind, ModuleKind::Overlay(_))));
+        for kind in modules {
+            let Some(symbol_map) = self.symbol_maps.get(kind) else { continue };
+            let Some(mut symbols) = symbol_map.for_name(name) else { continue };
+            let Some((_, symbol)) = symbols.find(|(_, symbol)| !symbol.local) else { continue };
+            let (thumb, function) = match symbol.kind {
+                SymbolKind::Function(function) => (function.mode == InstructionMode::Thumb, true),
+                _ => (false, false),
+            };
+            return Some(RelocationTarget { address: symbol.addr, thumb, function });
+        }
+        None
+    }
+
+    /// Parses a hook target, which is a symbol name with an optional hexadecimal offset like `func_02001234+0x10`, or an
+    /// address. Returns the module, the address and the symbol, if any.
+    fn find_target(&self, target: &str, module: Option<&str>) -> Result<(ModuleKind, u32, Option<&'a Symbol>)> {
+        let modules = self
+            .config
+            .iter_modules()
+            .filter(|(config, _)| module.map_or(true, |name| config.name == name))
+            .map(|(_, kind)| kind)
+            .collect::<Vec<_>>();
+        if modules.is_empty() {
+            bail!("Module '{}' not found", module.unwrap_or_default());
+        }
+
+        let (name, offset) = match target.split_once('+') {
+            Some((name, offset)) => {
+                (name.trim(), parse_hex_address(offset).with_context(|| format!("Invalid offset in '{target}'"))?)
+            }
+            None => (target.trim(), 0),
+        };
+
+        let mut found = vec![];
+        for &kind in &modules {
+            let Some(symbol_map) = self.symbol_maps.get(kind) else { continue };
+            if let Some(symbols) = symbol_map.for_name(name) {
+                found.extend(symbols.map(|(_, symbol)| (kind, symbol.addr + offset, Some(symbol))));
+            }
+        }
+        if found.is_empty() {
+            if let Ok(address) = parse_hex_address(name) {
+                for &kind in &modules {
+                    let module =
+                        self.modules.get(&kind).with_context(|| format!("Module {kind} was not loaded for patching"))?;
+                    if (module.base_address..module.base_address + module.binary.len() as u32).contains(&address) {
+                        let symbol = self.symbol_maps.get(kind).and_then(|map| map.by_address(address).ok().flatten());
+                        found.push((kind, address + offset, symbol.map(|(_, symbol)| symbol)));
+                    }
+                }
+            }
+        }
+
+        match found.as_slice() {
+            [] => bail!("Hook target '{target}' not found"),
+            &[found] => Ok(found),
+            _ => bail!("Hook target '{target}' is in multiple modules, set 'module' to choose one"),
+        }
+    }
+
+    fn write(&mut self, kind: ModuleKind, address: u32, data: &[u8]) -> Result<()> {
+        let module = self.modules.get_mut(&kind).with_context(|| format!("Module {kind} not found"))?;
+        let offset = address.checked_sub(module.base_address).map(|offset| offset as usize);
+        let Some(offset) = offset.filter(|offset| offset + data.len() <= module.binary.len()) else {
+            bail!("Cannot write {:#x} bytes at {address:#010x}, it is outside of the binary of {kind}", data.len());
+        };
+        module.binary[offset..offset + data.len()].copy_from_slice(data);
+        module.changed = true;
+        Ok(())
+    }
+}
+
+impl PatchModule {
+    pub fn from_file(kind: ModuleKind, base_address: u32, binary_path: PathBuf) -> Result<Self> {
+        let binary = read_file(&binary_path)?;
+        Ok(Self { kind, base_address, binary, binary_path, changed: false })
+    }
+}
+
+fn parse_hex_bytes(text: &str) -> Result<Vec<u8>> {
+    let hex = text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    if hex.len() % 2 != 0 {
+        bail!("odd number of hex digits");
+    }
+    (0..hex.len()).step_by(2).map(|i| Ok(u8::from_str_radix(&hex[i..i + 2], 16)?)).collect()
+}
@@ -0,0 +1,97 @@
+use clap::ValueEnum;
+use ds_decomp::config::section::SectionKind;
+
+/// Assembler to generate assembly code for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum AsmTarget {
+    /// Metrowerks assembler for ARM
+    Mwasmarm,
+    /// GNU assembler, such as arm-none-eabi-as
+    GnuAs,
+    /// Inline assembly in `asm` functions for mwccarm, without function declarations
+    #[value(skip)]
+    MwccInline,
+}
+
+const MWASMARM_FUNCTION_MACROS: &str = "\
+arm_func_start: .macro name
+    .arm
+    .endm
+
+arm_func_end: .macro name
+    .endm
+
+thumb_func_start: .macro name
+    .thumb
+    .endm
+
+thumb_func_end: .macro name
+    .endm
+";
+
+const GNU_AS_FUNCTION_MACROS: &str = "\
+    .macro arm_func_start name
+    .balign 4, 0
+    .arm
+    .type \\name, %function
+    .endm
+
+    .macro arm_func_end name
+    .size \\name, . - \\name
+    .endm
+
+    .macro thumb_func_start name
+    .balign 2, 0
+    .thumb
+    .thumb_func
+    .type \\name, %function
+    .endm
+
+    .macro thumb_func_end name
+    .size \\name, . - \\name
+    .endm
+";
+
+impl AsmTarget {
+    /// Starts a comment which lasts until the end of the line.
+    pub fn comment(self) -> &'static str {
+        match self {
+            Self::Mwasmarm => ";",
+            Self::GnuAs => "@",
+            Self::MwccInline => "//",
+        }
+    }
+
+    /// Contents of `macros/function.inc`, which defines the macros used at the start and end of every function.
+    pub fn function_macros(self) -> &'static str {
+        match self {
+            Self::Mwasmarm | Self::MwccInline => MWASMARM_FUNCTION_MACROS,
+            Self::GnuAs => GNU_AS_FUNCTION_MACROS,
+        }
+    }
+
+    /// Directive which defines a constant symbol.
+    pub fn equ(self, name: &str, value: u32) -> String {
+        match self {
+            Self::Mwasmarm | Self::MwccInline => format!("{name} .equ {value:#010x}"),
+            Self::GnuAs => format!("    .equ {name}, {value:#010x}"),
+        }
+    }
+
+    /// Directive for switching to a section.
+    pub fn section_directive(self, name: &str, kind: SectionKind) -> String {
+        match (self, name) {
+            (_, ".text") => "    .text".to_string(),
+            (Self::Mwasmarm | Self::MwccInline, _) => format!("    .section {name}, 4, 1, 4"),
+            (Self::GnuAs, _) => {
+                let (flags, section_type) = match kind {
+                    SectionKind::Code => ("ax", "%progbits"),
+                    SectionKind::Data if name.starts_with(".rodata") => ("a", "%progbits"),
+                    SectionKind::Data => ("aw", "%progbits"),
+                    SectionKind::Bss => ("aw", "%nobits"),
+                };
+                format!("    .section {name}, \"{flags}\", {section_type}")
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     fs::{self, File, ReadDir},
     io,
     path::Path,
+    time::SystemTime,
 };
 
 use snafu::Snafu;
@@ -145,3 +146,30 @@ pub fn create_dir_all<P: AsRef<Path>>(path: P) -> Result<(), FileError> {
     }
     Ok(())
 }
+
+/// Returns the time when the file was last modified, or `None` if it can't be read.
+pub fn modified_time<P: AsRef<Path>>(path: P) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Recreates the directory tree of `source` in `target`, where each file is hard linked to the original if possible and
+/// copied otherwise. Existing files in `target` are replaced.
+pub fn mirror_dir<P: AsRef<Path>, Q: AsRef<Path>>(source: P, target: Q) -> Result<(), FileError> {
+    let (source, target) = (source.as_ref(), target.as_ref());
+    create_dir_all(target)?;
+    for entry in read_dir(source)? {
+        let entry = entry?;
+        let target_path = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            mirror_dir(entry.path(), &target_path)?;
+            continue;
+        }
+        if target_path.exists() {
+            fs::remove_file(&target_path)?;
+        }
+        if fs::hard_link(entry.path(), &target_path).is_err() {
+            fs::copy(entry.path(), &target_path)?;
+        }
+    }
+    Ok(())
+}
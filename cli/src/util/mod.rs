@@ -1,3 +1,4 @@
+pub mod asm;
 pub mod bytes;
 pub mod debug;
 pub mod io;
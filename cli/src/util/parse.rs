@@ -1 +1,32 @@
+use std::num::ParseIntError;
 
+/// Parses a hexadecimal address, with or without the `0x` prefix.
+pub fn parse_hex_address(text: &str) -> Result<u32, ParseIntError> {
+    let text = text.trim();
+    let hex = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    u32::from_str_radix(hex, 16)
+}
+
+/// Splits a line of comma-separated values. Values may be wrapped in double quotes, in which case they can contain commas and
+/// escaped quotes (`""`).
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut value = String::new();
+    let mut quoted = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                value.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut value).trim().to_string()),
+            _ => value.push(c),
+        }
+    }
+    values.push(value.trim().to_string());
+
+    values
+}
@@ -12,12 +12,15 @@ use anyhow::Result;
 use ds_decomp::config::config::Config;
 use ds_decomp_cli::{
     analysis::data::AnalyzeExternalReferencesError,
-    cmd::{CheckModules, ConfigRom, Delink, Disassemble, Init, Lcf},
-    util::io::read_to_string,
+    cmd::{CheckModules, CheckObjects, ConfigRom, Delink, Disassemble, Init, Lcf, LcfFormat, Link},
+    util::{asm::AsmTarget, io::read_to_string},
 };
 use ds_rom::{
     crypto::blowfish::BlowfishKey,
-    rom::{raw, Rom},
+    rom::{
+        raw::{self, AutoloadKind},
+        Rom,
+    },
 };
 use log::LevelFilter;
 use zip::ZipArchive;
@@ -77,18 +80,48 @@ fn test_roundtrip() -> Result<()> {
         assert!(directory_equals(&target_config_dir, &dsd_config_dir)?);
 
         // Disassemble
-        let disassemble = Disassemble { config_path: dsd_config_yaml.clone(), asm_path: project_path.join("asm"), ual: false };
+        let disassemble = Disassemble {
+            config_path: dsd_config_yaml.clone(),
+            asm_path: project_path.join("asm"),
+            ual: false,
+            target: AsmTarget::Mwasmarm,
+        };
         disassemble.run()?;
 
         // Delink modules
-        let delink = Delink { config_path: dsd_config_yaml.clone() };
+        let delink = Delink { config_path: dsd_config_yaml.clone(), force: true };
         delink.run()?;
 
+        // Reassemble the disassembly, which must be equivalent to the delinked objects
+        if command_exists("arm-none-eabi-as") {
+            let asm_path = project_path.join("asm_gnu");
+            let disassemble = Disassemble {
+                config_path: dsd_config_yaml.clone(),
+                asm_path: asm_path.clone(),
+                ual: false,
+                target: AsmTarget::GnuAs,
+            };
+            disassemble.run()?;
+
+            let objects_path = project_path.join("asm_gnu_objects");
+            assemble_dir(&asm_path, &asm_path, &objects_path)?;
+
+            let check_objects = CheckObjects { config_path: dsd_config_yaml.clone(), objects_path, fail: true };
+            check_objects.run()?;
+        } else {
+            log::info!("arm-none-eabi-as not found, skipping reassembly check");
+        }
+
         // Generate LCF
-        let build_path = dsd_config_yaml.parent().unwrap().join(dsd_config.build_path);
+        let build_path = dsd_config_yaml.parent().unwrap().join(&dsd_config.build_path);
         let lcf_file = build_path.join("linker_script.lcf");
         let objects_file = build_path.join("objects.txt");
-        let lcf = Lcf { config_path: dsd_config_yaml.clone(), lcf_file: lcf_file.clone(), objects_file: objects_file.clone() };
+        let lcf = Lcf {
+            config_path: dsd_config_yaml.clone(),
+            lcf_file: lcf_file.clone(),
+            objects_file: objects_file.clone(),
+            format: LcfFormat::Mwld,
+        };
         lcf.run()?;
 
         // Run linker
@@ -130,6 +163,53 @@ fn test_roundtrip() -> Result<()> {
         let config_rom = ConfigRom { elf: linker_out_file.clone(), config: dsd_config_yaml.clone() };
         config_rom.run()?;
 
+        // Link with the native linker, which must also produce matching modules
+        let native_linker_out_file = build_path.join("arm9_native.o");
+        let link = Link {
+            config_path: dsd_config_yaml.clone(),
+            elf_path: native_linker_out_file.clone(),
+            entry: "Entry".to_string(),
+        };
+        link.run()?;
+        check_modules.run()?;
+
+        let config_rom = ConfigRom { elf: native_linker_out_file, config: dsd_config_yaml.clone() };
+        config_rom.run()?;
+
+        // Link with GNU ld, which must also produce matching modules
+        if command_exists("arm-none-eabi-ld") && command_exists("arm-none-eabi-objcopy") {
+            let gnu_lcf_file = build_path.join("linker_script.ld");
+            let gnu_objects_file = build_path.join("objects_gnu.txt");
+            let lcf = Lcf {
+                config_path: dsd_config_yaml.clone(),
+                lcf_file: gnu_lcf_file.clone(),
+                objects_file: gnu_objects_file.clone(),
+                format: LcfFormat::Gnu,
+            };
+            lcf.run()?;
+
+            let gnu_linker_out_file = build_path.join("arm9_gnu.o");
+            let linker_output = Command::new("arm-none-eabi-ld")
+                .arg("--no-check-sections")
+                .arg("-T")
+                .arg(&gnu_lcf_file)
+                .arg(format!("@{}", gnu_objects_file.display()))
+                .arg("-o")
+                .arg(&gnu_linker_out_file)
+                .output()?;
+            if !linker_output.status.success() {
+                let stderr = str::from_utf8(&linker_output.stderr)?;
+                log::error!("GNU linker failed, see stderr below");
+                log::error!("{stderr}");
+            }
+            assert!(linker_output.status.success());
+
+            extract_gnu_modules(&dsd_config, dsd_config_yaml.parent().unwrap(), &gnu_linker_out_file)?;
+            check_modules.run()?;
+        } else {
+            log::info!("arm-none-eabi-ld not found, skipping GNU linker check");
+        }
+
         fs::remove_dir_all(project_path)?;
     }
 
@@ -152,6 +232,72 @@ fn dsd_init(project_path: &Path, rom_config: &Path, allow_unknown_function_calls
     Ok(dsd_config_dir)
 }
 
+fn command_exists(program: &str) -> bool {
+    Command::new(program).arg("--version").output().is_ok_and(|output| output.status.success())
+}
+
+/// Writes each module binary of an ELF linked with the GNU linker script to its `object` path in the config.
+fn extract_gnu_modules(config: &Config, config_dir: &Path, elf_path: &Path) -> Result<()> {
+    let mut modules = vec![("ARM9".to_string(), &config.main_module)];
+    for autoload in &config.autoloads {
+        let memory_name = match autoload.kind {
+            AutoloadKind::Itcm => "ITCM",
+            AutoloadKind::Dtcm => "DTCM",
+            _ => continue,
+        };
+        modules.push((memory_name.to_string(), &autoload.module));
+    }
+    for overlay in &config.overlays {
+        modules.push((format!("OV{:03}", overlay.id), &overlay.module));
+    }
+
+    for (memory_name, module) in modules {
+        let output = Command::new("arm-none-eabi-objcopy")
+            .args(["-O", "binary", "-j", &memory_name])
+            .arg(elf_path)
+            .arg(config_dir.join(&module.object))
+            .output()?;
+        if !output.status.success() {
+            let stderr = str::from_utf8(&output.stderr)?;
+            log::error!("Failed to extract {memory_name}, see stderr below");
+            log::error!("{stderr}");
+        }
+        assert!(output.status.success());
+    }
+    Ok(())
+}
+
+fn assemble_dir(dir: &Path, asm_path: &Path, objects_path: &Path) -> Result<()> {
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.is_dir() {
+            assemble_dir(&path, asm_path, objects_path)?;
+            continue;
+        }
+        if path.extension() != Some(OsStr::new("s")) {
+            continue;
+        }
+
+        let object_path = objects_path.join(path.strip_prefix(asm_path)?).with_extension("o");
+        fs::create_dir_all(object_path.parent().unwrap())?;
+        let output = Command::new("arm-none-eabi-as")
+            .arg("-mcpu=arm946e-s")
+            .arg("-I")
+            .arg(asm_path)
+            .arg(&path)
+            .arg("-o")
+            .arg(&object_path)
+            .output()?;
+        if !output.status.success() {
+            let stderr = str::from_utf8(&output.stderr)?;
+            log::error!("Failed to assemble '{}', see stderr below", path.display());
+            log::error!("{stderr}");
+        }
+        assert!(output.status.success());
+    }
+    Ok(())
+}
+
 fn extract_rom(path: &Path, project_path: &Path, key: &BlowfishKey) -> Result<PathBuf> {
     let extract_path = project_path.join("extract");
     let raw_rom = raw::Rom::from_file(path)?;
@@ -0,0 +1,196 @@
+use unarm::{
+    args::{Argument, Reg, Register},
+    ParsedIns,
+};
+
+use crate::config::symbol::FxKind;
+
+use super::functions::{CalledFunction, Function};
+
+/// A NitroSDK math function which takes pointers to fixed-point vectors or matrices.
+#[derive(Clone, Copy, Debug)]
+pub struct FxFunction {
+    pub name: &'static str,
+    /// Types of the arguments in r0 to r3, or `None` if the argument is not a pointer to fixed-point data.
+    pub parameters: &'static [Option<FxKind>],
+}
+
+/// A pointer from a pool constant which is passed to an [`FxFunction`].
+#[derive(Clone, Copy, Debug)]
+pub struct FxArgument {
+    /// Address of the pool constant
+    pub pool_address: u32,
+    pub pointer: u32,
+    pub kind: FxKind,
+    pub function: &'static FxFunction,
+}
+
+impl FxFunction {
+    pub fn all() -> &'static [FxFunction] {
+        FX_FUNCTIONS
+    }
+
+    pub fn find(name: &str) -> Option<&'static FxFunction> {
+        FX_FUNCTIONS.iter().find(|function| function.name == name)
+    }
+
+    /// Finds pool constants which are loaded into argument registers of known fixed-point functions. The registers are
+    /// tracked linearly and forgotten at labels, so values which only reach the call through a branch are missed.
+    ///
+    /// `callee` looks up the function called at the given instruction address.
+    pub fn find_arguments(
+        function: &Function,
+        module_code: &[u8],
+        base_address: u32,
+        mut callee: impl FnMut(u32, &CalledFunction) -> Option<&'static FxFunction>,
+    ) -> Vec<FxArgument> {
+        let mut arguments = vec![];
+        // Pool constant address and value in r0 to r3
+        let mut registers: [Option<(u32, u32)>; 4] = [None; 4];
+        let labels = function.labels().copied().collect::<Vec<_>>();
+        let pool_constants = function.pool_constants();
+        for (address, ins, parsed_ins) in function.parser(module_code, base_address) {
+            if pool_constants.contains(&(address & !3)) {
+                continue;
+            }
+            if labels.binary_search(&address).is_ok() {
+                registers = [None; 4];
+            }
+
+            if let Some(called_function) = function.function_calls().get(&address) {
+                if let Some(fx_function) = callee(address, called_function) {
+                    for (register, kind) in registers.iter().zip(fx_function.parameters) {
+                        let (Some((pool_address, pointer)), Some(kind)) = (register, kind) else { continue };
+                        arguments.push(FxArgument {
+                            pool_address: *pool_address,
+                            pointer: *pointer,
+                            kind: *kind,
+                            function: fx_function,
+                        });
+                    }
+                }
+                registers = [None; 4];
+                continue;
+            }
+
+            let written = Self::written_register(&parsed_ins);
+            let Some(index) = written.and_then(Self::argument_index) else { continue };
+            registers[index] = Function::is_pool_load(ins, &parsed_ins, address, function.is_thumb()).map(|pool_address| {
+                let start = (pool_address - base_address) as usize;
+                let value = u32::from_le_bytes(module_code[start..start + 4].try_into().unwrap());
+                (pool_address, value)
+            });
+        }
+        arguments
+    }
+
+    /// Returns the register which an instruction most likely writes to.
+    fn written_register(parsed_ins: &ParsedIns) -> Option<Register> {
+        let mnemonic = parsed_ins.mnemonic;
+        if ["str", "stm", "push", "cmp", "cmn", "tst", "teq"].iter().any(|prefix| mnemonic.starts_with(prefix)) {
+            return None;
+        }
+        match parsed_ins.args[0] {
+            Argument::Reg(Reg { reg, .. }) => Some(reg),
+            _ => None,
+        }
+    }
+
+    fn argument_index(register: Register) -> Option<usize> {
+        match register {
+            Register::R0 => Some(0),
+            Register::R1 => Some(1),
+            Register::R2 => Some(2),
+            Register::R3 => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// Returns whether a word looks more like an fx32 constant than an integer, flag or address. The value must be between
+/// 1/16 and 128 in magnitude with at most six fractional bits. Powers of two outside of 0.25 to 4.0 are rejected, as they
+/// are more likely to be flags or sizes.
+pub fn looks_like_fx32(value: u32) -> bool {
+    let magnitude = (value as i32).unsigned_abs();
+    if !(0x100..=0x80000).contains(&magnitude) || magnitude & 0x3f != 0 {
+        return false;
+    }
+    !magnitude.is_power_of_two() || (0x400..=0x4000).contains(&magnitude)
+}
+
+/// Formats a fixed-point number as a decimal number, e.g. `1.5` for the fx32 value `0x1800`.
+pub fn format_fx(value: f64) -> String {
+    format!("{value:?}")
+}
+
+const VEC16: Option<FxKind> = Some(FxKind::VecFx16);
+const VEC32: Option<FxKind> = Some(FxKind::VecFx32);
+const MTX22: Option<FxKind> = Some(FxKind::MtxFx22);
+const MTX33: Option<FxKind> = Some(FxKind::MtxFx33);
+const MTX43: Option<FxKind> = Some(FxKind::MtxFx43);
+const MTX44: Option<FxKind> = Some(FxKind::MtxFx44);
+
+const FX_FUNCTIONS: &[FxFunction] = &[
+    FxFunction { name: "VEC_Add", parameters: &[VEC32, VEC32, VEC32] },
+    FxFunction { name: "VEC_Subtract", parameters: &[VEC32, VEC32, VEC32] },
+    FxFunction { name: "VEC_MultAdd", parameters: &[None, VEC32, VEC32, VEC32] },
+    FxFunction { name: "VEC_MultSubtract", parameters: &[None, VEC32, VEC32, VEC32] },
+    FxFunction { name: "VEC_DotProduct", parameters: &[VEC32, VEC32] },
+    FxFunction { name: "VEC_CrossProduct", parameters: &[VEC32, VEC32, VEC32] },
+    FxFunction { name: "VEC_Mag", parameters: &[VEC32] },
+    FxFunction { name: "VEC_Distance", parameters: &[VEC32, VEC32] },
+    FxFunction { name: "VEC_Normalize", parameters: &[VEC32, VEC32] },
+    FxFunction { name: "VEC_Fx16Add", parameters: &[VEC16, VEC16, VEC16] },
+    FxFunction { name: "VEC_Fx16Subtract", parameters: &[VEC16, VEC16, VEC16] },
+    FxFunction { name: "VEC_Fx16DotProduct", parameters: &[VEC16, VEC16] },
+    FxFunction { name: "VEC_Fx16CrossProduct", parameters: &[VEC16, VEC16, VEC16] },
+    FxFunction { name: "VEC_Fx16Mag", parameters: &[VEC16] },
+    FxFunction { name: "VEC_Fx16Distance", parameters: &[VEC16, VEC16] },
+    FxFunction { name: "VEC_Fx16Normalize", parameters: &[VEC16, VEC16] },
+    FxFunction { name: "MTX_Identity22", parameters: &[MTX22] },
+    FxFunction { name: "MTX_Identity33", parameters: &[MTX33] },
+    FxFunction { name: "MTX_Identity43", parameters: &[MTX43] },
+    FxFunction { name: "MTX_Identity44", parameters: &[MTX44] },
+    FxFunction { name: "MTX_Copy22", parameters: &[MTX22, MTX22] },
+    FxFunction { name: "MTX_Copy33", parameters: &[MTX33, MTX33] },
+    FxFunction { name: "MTX_Copy43", parameters: &[MTX43, MTX43] },
+    FxFunction { name: "MTX_Copy44", parameters: &[MTX44, MTX44] },
+    FxFunction { name: "MTX_Copy33To43", parameters: &[MTX33, MTX43] },
+    FxFunction { name: "MTX_Copy43To44", parameters: &[MTX43, MTX44] },
+    FxFunction { name: "MTX_Transpose22", parameters: &[MTX22, MTX22] },
+    FxFunction { name: "MTX_Transpose33", parameters: &[MTX33, MTX33] },
+    FxFunction { name: "MTX_Transpose44", parameters: &[MTX44, MTX44] },
+    FxFunction { name: "MTX_Concat22", parameters: &[MTX22, MTX22, MTX22] },
+    FxFunction { name: "MTX_Concat33", parameters: &[MTX33, MTX33, MTX33] },
+    FxFunction { name: "MTX_Concat43", parameters: &[MTX43, MTX43, MTX43] },
+    FxFunction { name: "MTX_Concat44", parameters: &[MTX44, MTX44, MTX44] },
+    FxFunction { name: "MTX_Inverse22", parameters: &[MTX22, MTX22] },
+    FxFunction { name: "MTX_Inverse33", parameters: &[MTX33, MTX33] },
+    FxFunction { name: "MTX_Inverse43", parameters: &[MTX43, MTX43] },
+    FxFunction { name: "MTX_MultVec33", parameters: &[VEC32, MTX33, VEC32] },
+    FxFunction { name: "MTX_MultVec43", parameters: &[VEC32, MTX43, VEC32] },
+    FxFunction { name: "MTX_Scale22", parameters: &[MTX22] },
+    FxFunction { name: "MTX_Scale33", parameters: &[MTX33] },
+    FxFunction { name: "MTX_Scale43", parameters: &[MTX43] },
+    FxFunction { name: "MTX_Scale44", parameters: &[MTX44] },
+    FxFunction { name: "MTX_Rot22", parameters: &[MTX22] },
+    FxFunction { name: "MTX_RotX33", parameters: &[MTX33] },
+    FxFunction { name: "MTX_RotY33", parameters: &[MTX33] },
+    FxFunction { name: "MTX_RotZ33", parameters: &[MTX33] },
+    FxFunction { name: "MTX_RotX43", parameters: &[MTX43] },
+    FxFunction { name: "MTX_RotY43", parameters: &[MTX43] },
+    FxFunction { name: "MTX_RotZ43", parameters: &[MTX43] },
+    FxFunction { name: "MTX_RotX44", parameters: &[MTX44] },
+    FxFunction { name: "MTX_RotY44", parameters: &[MTX44] },
+    FxFunction { name: "MTX_RotZ44", parameters: &[MTX44] },
+    FxFunction { name: "MTX_RotAxis33", parameters: &[MTX33, VEC32] },
+    FxFunction { name: "MTX_RotAxis43", parameters: &[MTX43, VEC32] },
+    FxFunction { name: "MTX_RotAxis44", parameters: &[MTX44, VEC32] },
+    FxFunction { name: "MTX_LookAt", parameters: &[VEC32, VEC32, VEC32, MTX43] },
+    FxFunction { name: "G3_LoadMtx43", parameters: &[MTX43] },
+    FxFunction { name: "G3_LoadMtx44", parameters: &[MTX44] },
+    FxFunction { name: "G3_MultMtx33", parameters: &[MTX33] },
+    FxFunction { name: "G3_MultMtx43", parameters: &[MTX43] },
+    FxFunction { name: "G3_MultMtx44", parameters: &[MTX44] },
+    FxFunction { name: "G3_LookAt", parameters: &[VEC32, VEC32, VEC32, MTX43] },
+];
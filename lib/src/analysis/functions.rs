@@ -146,7 +146,7 @@ impl Function {
         Some((address as i32 + dest).try_into().unwrap())
     }
 
-    fn is_pool_load(ins: Ins, parsed_ins: &ParsedIns, address: u32, thumb: bool) -> Option<u32> {
+    pub(crate) fn is_pool_load(ins: Ins, parsed_ins: &ParsedIns, address: u32, thumb: bool) -> Option<u32> {
         if ins.mnemonic() != "ldr" {
             return None;
         }
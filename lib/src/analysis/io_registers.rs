@@ -0,0 +1,386 @@
+use std::fmt::Display;
+
+/// A memory-mapped hardware register of the DS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IoRegister {
+    /// Name of the register, without the `REG_` prefix. Registers of the sub display engine start with `DB_`.
+    pub name: &'static str,
+    pub address: u32,
+    /// Size in bytes, which covers the whole array for registers like `FOG_TABLE`
+    pub size: u32,
+}
+
+/// The I/O register map of one of the DS processors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IoRegisterMap {
+    Arm9,
+    Arm7,
+}
+
+impl IoRegisterMap {
+    /// Returns the registers of this map, sorted by address.
+    pub fn registers(self) -> &'static [IoRegister] {
+        match self {
+            Self::Arm9 => ARM9_IO_REGISTERS,
+            Self::Arm7 => ARM7_IO_REGISTERS,
+        }
+    }
+
+    /// Finds the register which contains the given address, along with the offset of the address within the register.
+    pub fn find(self, address: u32) -> Option<(&'static IoRegister, u32)> {
+        let registers = self.registers();
+        let index = registers.partition_point(|register| register.address <= address).checked_sub(1)?;
+        let register = &registers[index];
+        let offset = address - register.address;
+        (offset < register.size).then_some((register, offset))
+    }
+}
+
+/// Displays the name of the register with the `REG_` prefix, as used in generated headers.
+impl Display for IoRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "REG_{}", self.name)
+    }
+}
+
+const ARM9_IO_REGISTERS: &[IoRegister] = &[
+    IoRegister { name: "DISPCNT", address: 0x04000000, size: 4 },
+    IoRegister { name: "DISPSTAT", address: 0x04000004, size: 2 },
+    IoRegister { name: "VCOUNT", address: 0x04000006, size: 2 },
+    IoRegister { name: "BG0CNT", address: 0x04000008, size: 2 },
+    IoRegister { name: "BG1CNT", address: 0x0400000a, size: 2 },
+    IoRegister { name: "BG2CNT", address: 0x0400000c, size: 2 },
+    IoRegister { name: "BG3CNT", address: 0x0400000e, size: 2 },
+    IoRegister { name: "BG0HOFS", address: 0x04000010, size: 2 },
+    IoRegister { name: "BG0VOFS", address: 0x04000012, size: 2 },
+    IoRegister { name: "BG1HOFS", address: 0x04000014, size: 2 },
+    IoRegister { name: "BG1VOFS", address: 0x04000016, size: 2 },
+    IoRegister { name: "BG2HOFS", address: 0x04000018, size: 2 },
+    IoRegister { name: "BG2VOFS", address: 0x0400001a, size: 2 },
+    IoRegister { name: "BG3HOFS", address: 0x0400001c, size: 2 },
+    IoRegister { name: "BG3VOFS", address: 0x0400001e, size: 2 },
+    IoRegister { name: "BG2PA", address: 0x04000020, size: 2 },
+    IoRegister { name: "BG2PB", address: 0x04000022, size: 2 },
+    IoRegister { name: "BG2PC", address: 0x04000024, size: 2 },
+    IoRegister { name: "BG2PD", address: 0x04000026, size: 2 },
+    IoRegister { name: "BG2X", address: 0x04000028, size: 4 },
+    IoRegister { name: "BG2Y", address: 0x0400002c, size: 4 },
+    IoRegister { name: "BG3PA", address: 0x04000030, size: 2 },
+    IoRegister { name: "BG3PB", address: 0x04000032, size: 2 },
+    IoRegister { name: "BG3PC", address: 0x04000034, size: 2 },
+    IoRegister { name: "BG3PD", address: 0x04000036, size: 2 },
+    IoRegister { name: "BG3X", address: 0x04000038, size: 4 },
+    IoRegister { name: "BG3Y", address: 0x0400003c, size: 4 },
+    IoRegister { name: "WIN0H", address: 0x04000040, size: 2 },
+    IoRegister { name: "WIN1H", address: 0x04000042, size: 2 },
+    IoRegister { name: "WIN0V", address: 0x04000044, size: 2 },
+    IoRegister { name: "WIN1V", address: 0x04000046, size: 2 },
+    IoRegister { name: "WININ", address: 0x04000048, size: 2 },
+    IoRegister { name: "WINOUT", address: 0x0400004a, size: 2 },
+    IoRegister { name: "MOSAIC", address: 0x0400004c, size: 2 },
+    IoRegister { name: "BLDCNT", address: 0x04000050, size: 2 },
+    IoRegister { name: "BLDALPHA", address: 0x04000052, size: 2 },
+    IoRegister { name: "BLDY", address: 0x04000054, size: 2 },
+    IoRegister { name: "DISP3DCNT", address: 0x04000060, size: 2 },
+    IoRegister { name: "DISPCAPCNT", address: 0x04000064, size: 4 },
+    IoRegister { name: "DISP_MMEM_FIFO", address: 0x04000068, size: 4 },
+    IoRegister { name: "MASTER_BRIGHT", address: 0x0400006c, size: 2 },
+    IoRegister { name: "DMA0SAD", address: 0x040000b0, size: 4 },
+    IoRegister { name: "DMA0DAD", address: 0x040000b4, size: 4 },
+    IoRegister { name: "DMA0CNT", address: 0x040000b8, size: 4 },
+    IoRegister { name: "DMA1SAD", address: 0x040000bc, size: 4 },
+    IoRegister { name: "DMA1DAD", address: 0x040000c0, size: 4 },
+    IoRegister { name: "DMA1CNT", address: 0x040000c4, size: 4 },
+    IoRegister { name: "DMA2SAD", address: 0x040000c8, size: 4 },
+    IoRegister { name: "DMA2DAD", address: 0x040000cc, size: 4 },
+    IoRegister { name: "DMA2CNT", address: 0x040000d0, size: 4 },
+    IoRegister { name: "DMA3SAD", address: 0x040000d4, size: 4 },
+    IoRegister { name: "DMA3DAD", address: 0x040000d8, size: 4 },
+    IoRegister { name: "DMA3CNT", address: 0x040000dc, size: 4 },
+    IoRegister { name: "DMA0FILL", address: 0x040000e0, size: 4 },
+    IoRegister { name: "DMA1FILL", address: 0x040000e4, size: 4 },
+    IoRegister { name: "DMA2FILL", address: 0x040000e8, size: 4 },
+    IoRegister { name: "DMA3FILL", address: 0x040000ec, size: 4 },
+    IoRegister { name: "TM0CNT_L", address: 0x04000100, size: 2 },
+    IoRegister { name: "TM0CNT_H", address: 0x04000102, size: 2 },
+    IoRegister { name: "TM1CNT_L", address: 0x04000104, size: 2 },
+    IoRegister { name: "TM1CNT_H", address: 0x04000106, size: 2 },
+    IoRegister { name: "TM2CNT_L", address: 0x04000108, size: 2 },
+    IoRegister { name: "TM2CNT_H", address: 0x0400010a, size: 2 },
+    IoRegister { name: "TM3CNT_L", address: 0x0400010c, size: 2 },
+    IoRegister { name: "TM3CNT_H", address: 0x0400010e, size: 2 },
+    IoRegister { name: "KEYINPUT", address: 0x04000130, size: 2 },
+    IoRegister { name: "KEYCNT", address: 0x04000132, size: 2 },
+    IoRegister { name: "IPCSYNC", address: 0x04000180, size: 2 },
+    IoRegister { name: "IPCFIFOCNT", address: 0x04000184, size: 2 },
+    IoRegister { name: "IPCFIFOSEND", address: 0x04000188, size: 4 },
+    IoRegister { name: "AUXSPICNT", address: 0x040001a0, size: 2 },
+    IoRegister { name: "AUXSPIDATA", address: 0x040001a2, size: 2 },
+    IoRegister { name: "ROMCTRL", address: 0x040001a4, size: 4 },
+    IoRegister { name: "CARD_COMMAND", address: 0x040001a8, size: 8 },
+    IoRegister { name: "ENCSEED0L", address: 0x040001b0, size: 4 },
+    IoRegister { name: "ENCSEED1L", address: 0x040001b4, size: 4 },
+    IoRegister { name: "ENCSEED0H", address: 0x040001b8, size: 2 },
+    IoRegister { name: "ENCSEED1H", address: 0x040001ba, size: 2 },
+    IoRegister { name: "EXMEMCNT", address: 0x04000204, size: 2 },
+    IoRegister { name: "IME", address: 0x04000208, size: 2 },
+    IoRegister { name: "IE", address: 0x04000210, size: 4 },
+    IoRegister { name: "IF", address: 0x04000214, size: 4 },
+    IoRegister { name: "VRAMCNT_A", address: 0x04000240, size: 1 },
+    IoRegister { name: "VRAMCNT_B", address: 0x04000241, size: 1 },
+    IoRegister { name: "VRAMCNT_C", address: 0x04000242, size: 1 },
+    IoRegister { name: "VRAMCNT_D", address: 0x04000243, size: 1 },
+    IoRegister { name: "VRAMCNT_E", address: 0x04000244, size: 1 },
+    IoRegister { name: "VRAMCNT_F", address: 0x04000245, size: 1 },
+    IoRegister { name: "VRAMCNT_G", address: 0x04000246, size: 1 },
+    IoRegister { name: "WRAMCNT", address: 0x04000247, size: 1 },
+    IoRegister { name: "VRAMCNT_H", address: 0x04000248, size: 1 },
+    IoRegister { name: "VRAMCNT_I", address: 0x04000249, size: 1 },
+    IoRegister { name: "DIVCNT", address: 0x04000280, size: 2 },
+    IoRegister { name: "DIV_NUMER", address: 0x04000290, size: 8 },
+    IoRegister { name: "DIV_DENOM", address: 0x04000298, size: 8 },
+    IoRegister { name: "DIV_RESULT", address: 0x040002a0, size: 8 },
+    IoRegister { name: "DIVREM_RESULT", address: 0x040002a8, size: 8 },
+    IoRegister { name: "SQRTCNT", address: 0x040002b0, size: 2 },
+    IoRegister { name: "SQRT_RESULT", address: 0x040002b4, size: 4 },
+    IoRegister { name: "SQRT_PARAM", address: 0x040002b8, size: 8 },
+    IoRegister { name: "POSTFLG", address: 0x04000300, size: 1 },
+    IoRegister { name: "POWCNT1", address: 0x04000304, size: 2 },
+    IoRegister { name: "RDLINES_COUNT", address: 0x04000320, size: 1 },
+    IoRegister { name: "EDGE_COLOR", address: 0x04000330, size: 16 },
+    IoRegister { name: "ALPHA_TEST_REF", address: 0x04000340, size: 1 },
+    IoRegister { name: "CLEAR_COLOR", address: 0x04000350, size: 4 },
+    IoRegister { name: "CLEAR_DEPTH", address: 0x04000354, size: 2 },
+    IoRegister { name: "CLRIMAGE_OFFSET", address: 0x04000356, size: 2 },
+    IoRegister { name: "FOG_COLOR", address: 0x04000358, size: 4 },
+    IoRegister { name: "FOG_OFFSET", address: 0x0400035c, size: 2 },
+    IoRegister { name: "FOG_TABLE", address: 0x04000360, size: 32 },
+    IoRegister { name: "TOON_TABLE", address: 0x04000380, size: 64 },
+    IoRegister { name: "GXFIFO", address: 0x04000400, size: 64 },
+    IoRegister { name: "MTX_MODE", address: 0x04000440, size: 4 },
+    IoRegister { name: "MTX_PUSH", address: 0x04000444, size: 4 },
+    IoRegister { name: "MTX_POP", address: 0x04000448, size: 4 },
+    IoRegister { name: "MTX_STORE", address: 0x0400044c, size: 4 },
+    IoRegister { name: "MTX_RESTORE", address: 0x04000450, size: 4 },
+    IoRegister { name: "MTX_IDENTITY", address: 0x04000454, size: 4 },
+    IoRegister { name: "MTX_LOAD_4x4", address: 0x04000458, size: 4 },
+    IoRegister { name: "MTX_LOAD_4x3", address: 0x0400045c, size: 4 },
+    IoRegister { name: "MTX_MULT_4x4", address: 0x04000460, size: 4 },
+    IoRegister { name: "MTX_MULT_4x3", address: 0x04000464, size: 4 },
+    IoRegister { name: "MTX_MULT_3x3", address: 0x04000468, size: 4 },
+    IoRegister { name: "MTX_SCALE", address: 0x0400046c, size: 4 },
+    IoRegister { name: "MTX_TRANS", address: 0x04000470, size: 4 },
+    IoRegister { name: "COLOR", address: 0x04000480, size: 4 },
+    IoRegister { name: "NORMAL", address: 0x04000484, size: 4 },
+    IoRegister { name: "TEXCOORD", address: 0x04000488, size: 4 },
+    IoRegister { name: "VTX_16", address: 0x0400048c, size: 4 },
+    IoRegister { name: "VTX_10", address: 0x04000490, size: 4 },
+    IoRegister { name: "VTX_XY", address: 0x04000494, size: 4 },
+    IoRegister { name: "VTX_XZ", address: 0x04000498, size: 4 },
+    IoRegister { name: "VTX_YZ", address: 0x0400049c, size: 4 },
+    IoRegister { name: "VTX_DIFF", address: 0x040004a0, size: 4 },
+    IoRegister { name: "POLYGON_ATTR", address: 0x040004a4, size: 4 },
+    IoRegister { name: "TEXIMAGE_PARAM", address: 0x040004a8, size: 4 },
+    IoRegister { name: "PLTT_BASE", address: 0x040004ac, size: 4 },
+    IoRegister { name: "DIF_AMB", address: 0x040004c0, size: 4 },
+    IoRegister { name: "SPE_EMI", address: 0x040004c4, size: 4 },
+    IoRegister { name: "LIGHT_VECTOR", address: 0x040004c8, size: 4 },
+    IoRegister { name: "LIGHT_COLOR", address: 0x040004cc, size: 4 },
+    IoRegister { name: "SHININESS", address: 0x040004d0, size: 4 },
+    IoRegister { name: "BEGIN_VTXS", address: 0x04000500, size: 4 },
+    IoRegister { name: "END_VTXS", address: 0x04000504, size: 4 },
+    IoRegister { name: "SWAP_BUFFERS", address: 0x04000540, size: 4 },
+    IoRegister { name: "VIEWPORT", address: 0x04000580, size: 4 },
+    IoRegister { name: "BOX_TEST", address: 0x040005c0, size: 4 },
+    IoRegister { name: "POS_TEST", address: 0x040005c4, size: 4 },
+    IoRegister { name: "VEC_TEST", address: 0x040005c8, size: 4 },
+    IoRegister { name: "GXSTAT", address: 0x04000600, size: 4 },
+    IoRegister { name: "RAM_COUNT", address: 0x04000604, size: 4 },
+    IoRegister { name: "DISP_1DOT_DEPTH", address: 0x04000610, size: 2 },
+    IoRegister { name: "POS_RESULT", address: 0x04000620, size: 16 },
+    IoRegister { name: "VEC_RESULT", address: 0x04000630, size: 6 },
+    IoRegister { name: "CLIPMTX_RESULT", address: 0x04000640, size: 64 },
+    IoRegister { name: "VECMTX_RESULT", address: 0x04000680, size: 36 },
+    IoRegister { name: "DB_DISPCNT", address: 0x04001000, size: 4 },
+    IoRegister { name: "DB_BG0CNT", address: 0x04001008, size: 2 },
+    IoRegister { name: "DB_BG1CNT", address: 0x0400100a, size: 2 },
+    IoRegister { name: "DB_BG2CNT", address: 0x0400100c, size: 2 },
+    IoRegister { name: "DB_BG3CNT", address: 0x0400100e, size: 2 },
+    IoRegister { name: "DB_BG0HOFS", address: 0x04001010, size: 2 },
+    IoRegister { name: "DB_BG0VOFS", address: 0x04001012, size: 2 },
+    IoRegister { name: "DB_BG1HOFS", address: 0x04001014, size: 2 },
+    IoRegister { name: "DB_BG1VOFS", address: 0x04001016, size: 2 },
+    IoRegister { name: "DB_BG2HOFS", address: 0x04001018, size: 2 },
+    IoRegister { name: "DB_BG2VOFS", address: 0x0400101a, size: 2 },
+    IoRegister { name: "DB_BG3HOFS", address: 0x0400101c, size: 2 },
+    IoRegister { name: "DB_BG3VOFS", address: 0x0400101e, size: 2 },
+    IoRegister { name: "DB_BG2PA", address: 0x04001020, size: 2 },
+    IoRegister { name: "DB_BG2PB", address: 0x04001022, size: 2 },
+    IoRegister { name: "DB_BG2PC", address: 0x04001024, size: 2 },
+    IoRegister { name: "DB_BG2PD", address: 0x04001026, size: 2 },
+    IoRegister { name: "DB_BG2X", address: 0x04001028, size: 4 },
+    IoRegister { name: "DB_BG2Y", address: 0x0400102c, size: 4 },
+    IoRegister { name: "DB_BG3PA", address: 0x04001030, size: 2 },
+    IoRegister { name: "DB_BG3PB", address: 0x04001032, size: 2 },
+    IoRegister { name: "DB_BG3PC", address: 0x04001034, size: 2 },
+    IoRegister { name: "DB_BG3PD", address: 0x04001036, size: 2 },
+    IoRegister { name: "DB_BG3X", address: 0x04001038, size: 4 },
+    IoRegister { name: "DB_BG3Y", address: 0x0400103c, size: 4 },
+    IoRegister { name: "DB_WIN0H", address: 0x04001040, size: 2 },
+    IoRegister { name: "DB_WIN1H", address: 0x04001042, size: 2 },
+    IoRegister { name: "DB_WIN0V", address: 0x04001044, size: 2 },
+    IoRegister { name: "DB_WIN1V", address: 0x04001046, size: 2 },
+    IoRegister { name: "DB_WININ", address: 0x04001048, size: 2 },
+    IoRegister { name: "DB_WINOUT", address: 0x0400104a, size: 2 },
+    IoRegister { name: "DB_MOSAIC", address: 0x0400104c, size: 2 },
+    IoRegister { name: "DB_BLDCNT", address: 0x04001050, size: 2 },
+    IoRegister { name: "DB_BLDALPHA", address: 0x04001052, size: 2 },
+    IoRegister { name: "DB_BLDY", address: 0x04001054, size: 2 },
+    IoRegister { name: "DB_MASTER_BRIGHT", address: 0x0400106c, size: 2 },
+    IoRegister { name: "IPCFIFORECV", address: 0x04100000, size: 4 },
+    IoRegister { name: "CARD_DATA_RD", address: 0x04100010, size: 4 },
+];
+
+const ARM7_IO_REGISTERS: &[IoRegister] = &[
+    IoRegister { name: "DISPSTAT", address: 0x04000004, size: 2 },
+    IoRegister { name: "VCOUNT", address: 0x04000006, size: 2 },
+    IoRegister { name: "DMA0SAD", address: 0x040000b0, size: 4 },
+    IoRegister { name: "DMA0DAD", address: 0x040000b4, size: 4 },
+    IoRegister { name: "DMA0CNT", address: 0x040000b8, size: 4 },
+    IoRegister { name: "DMA1SAD", address: 0x040000bc, size: 4 },
+    IoRegister { name: "DMA1DAD", address: 0x040000c0, size: 4 },
+    IoRegister { name: "DMA1CNT", address: 0x040000c4, size: 4 },
+    IoRegister { name: "DMA2SAD", address: 0x040000c8, size: 4 },
+    IoRegister { name: "DMA2DAD", address: 0x040000cc, size: 4 },
+    IoRegister { name: "DMA2CNT", address: 0x040000d0, size: 4 },
+    IoRegister { name: "DMA3SAD", address: 0x040000d4, size: 4 },
+    IoRegister { name: "DMA3DAD", address: 0x040000d8, size: 4 },
+    IoRegister { name: "DMA3CNT", address: 0x040000dc, size: 4 },
+    IoRegister { name: "TM0CNT_L", address: 0x04000100, size: 2 },
+    IoRegister { name: "TM0CNT_H", address: 0x04000102, size: 2 },
+    IoRegister { name: "TM1CNT_L", address: 0x04000104, size: 2 },
+    IoRegister { name: "TM1CNT_H", address: 0x04000106, size: 2 },
+    IoRegister { name: "TM2CNT_L", address: 0x04000108, size: 2 },
+    IoRegister { name: "TM2CNT_H", address: 0x0400010a, size: 2 },
+    IoRegister { name: "TM3CNT_L", address: 0x0400010c, size: 2 },
+    IoRegister { name: "TM3CNT_H", address: 0x0400010e, size: 2 },
+    IoRegister { name: "KEYINPUT", address: 0x04000130, size: 2 },
+    IoRegister { name: "KEYCNT", address: 0x04000132, size: 2 },
+    IoRegister { name: "RCNT", address: 0x04000134, size: 2 },
+    IoRegister { name: "EXTKEYIN", address: 0x04000136, size: 2 },
+    IoRegister { name: "RTC", address: 0x04000138, size: 1 },
+    IoRegister { name: "IPCSYNC", address: 0x04000180, size: 2 },
+    IoRegister { name: "IPCFIFOCNT", address: 0x04000184, size: 2 },
+    IoRegister { name: "IPCFIFOSEND", address: 0x04000188, size: 4 },
+    IoRegister { name: "AUXSPICNT", address: 0x040001a0, size: 2 },
+    IoRegister { name: "AUXSPIDATA", address: 0x040001a2, size: 2 },
+    IoRegister { name: "ROMCTRL", address: 0x040001a4, size: 4 },
+    IoRegister { name: "CARD_COMMAND", address: 0x040001a8, size: 8 },
+    IoRegister { name: "ENCSEED0L", address: 0x040001b0, size: 4 },
+    IoRegister { name: "ENCSEED1L", address: 0x040001b4, size: 4 },
+    IoRegister { name: "ENCSEED0H", address: 0x040001b8, size: 2 },
+    IoRegister { name: "ENCSEED1H", address: 0x040001ba, size: 2 },
+    IoRegister { name: "SPICNT", address: 0x040001c0, size: 2 },
+    IoRegister { name: "SPIDATA", address: 0x040001c2, size: 2 },
+    IoRegister { name: "EXMEMSTAT", address: 0x04000204, size: 2 },
+    IoRegister { name: "WIFIWAITCNT", address: 0x04000206, size: 2 },
+    IoRegister { name: "IME", address: 0x04000208, size: 2 },
+    IoRegister { name: "IE", address: 0x04000210, size: 4 },
+    IoRegister { name: "IF", address: 0x04000214, size: 4 },
+    IoRegister { name: "VRAMSTAT", address: 0x04000240, size: 1 },
+    IoRegister { name: "WRAMSTAT", address: 0x04000241, size: 1 },
+    IoRegister { name: "POSTFLG", address: 0x04000300, size: 1 },
+    IoRegister { name: "HALTCNT", address: 0x04000301, size: 1 },
+    IoRegister { name: "POWCNT2", address: 0x04000304, size: 2 },
+    IoRegister { name: "BIOSPROT", address: 0x04000308, size: 4 },
+    IoRegister { name: "SOUND0CNT", address: 0x04000400, size: 4 },
+    IoRegister { name: "SOUND0SAD", address: 0x04000404, size: 4 },
+    IoRegister { name: "SOUND0TMR", address: 0x04000408, size: 2 },
+    IoRegister { name: "SOUND0PNT", address: 0x0400040a, size: 2 },
+    IoRegister { name: "SOUND0LEN", address: 0x0400040c, size: 4 },
+    IoRegister { name: "SOUND1CNT", address: 0x04000410, size: 4 },
+    IoRegister { name: "SOUND1SAD", address: 0x04000414, size: 4 },
+    IoRegister { name: "SOUND1TMR", address: 0x04000418, size: 2 },
+    IoRegister { name: "SOUND1PNT", address: 0x0400041a, size: 2 },
+    IoRegister { name: "SOUND1LEN", address: 0x0400041c, size: 4 },
+    IoRegister { name: "SOUND2CNT", address: 0x04000420, size: 4 },
+    IoRegister { name: "SOUND2SAD", address: 0x04000424, size: 4 },
+    IoRegister { name: "SOUND2TMR", address: 0x04000428, size: 2 },
+    IoRegister { name: "SOUND2PNT", address: 0x0400042a, size: 2 },
+    IoRegister { name: "SOUND2LEN", address: 0x0400042c, size: 4 },
+    IoRegister { name: "SOUND3CNT", address: 0x04000430, size: 4 },
+    IoRegister { name: "SOUND3SAD", address: 0x04000434, size: 4 },
+    IoRegister { name: "SOUND3TMR", address: 0x04000438, size: 2 },
+    IoRegister { name: "SOUND3PNT", address: 0x0400043a, size: 2 },
+    IoRegister { name: "SOUND3LEN", address: 0x0400043c, size: 4 },
+    IoRegister { name: "SOUND4CNT", address: 0x04000440, size: 4 },
+    IoRegister { name: "SOUND4SAD", address: 0x04000444, size: 4 },
+    IoRegister { name: "SOUND4TMR", address: 0x04000448, size: 2 },
+    IoRegister { name: "SOUND4PNT", address: 0x0400044a, size: 2 },
+    IoRegister { name: "SOUND4LEN", address: 0x0400044c, size: 4 },
+    IoRegister { name: "SOUND5CNT", address: 0x04000450, size: 4 },
+    IoRegister { name: "SOUND5SAD", address: 0x04000454, size: 4 },
+    IoRegister { name: "SOUND5TMR", address: 0x04000458, size: 2 },
+    IoRegister { name: "SOUND5PNT", address: 0x0400045a, size: 2 },
+    IoRegister { name: "SOUND5LEN", address: 0x0400045c, size: 4 },
+    IoRegister { name: "SOUND6CNT", address: 0x04000460, size: 4 },
+    IoRegister { name: "SOUND6SAD", address: 0x04000464, size: 4 },
+    IoRegister { name: "SOUND6TMR", address: 0x04000468, size: 2 },
+    IoRegister { name: "SOUND6PNT", address: 0x0400046a, size: 2 },
+    IoRegister { name: "SOUND6LEN", address: 0x0400046c, size: 4 },
+    IoRegister { name: "SOUND7CNT", address: 0x04000470, size: 4 },
+    IoRegister { name: "SOUND7SAD", address: 0x04000474, size: 4 },
+    IoRegister { name: "SOUND7TMR", address: 0x04000478, size: 2 },
+    IoRegister { name: "SOUND7PNT", address: 0x0400047a, size: 2 },
+    IoRegister { name: "SOUND7LEN", address: 0x0400047c, size: 4 },
+    IoRegister { name: "SOUND8CNT", address: 0x04000480, size: 4 },
+    IoRegister { name: "SOUND8SAD", address: 0x04000484, size: 4 },
+    IoRegister { name: "SOUND8TMR", address: 0x04000488, size: 2 },
+    IoRegister { name: "SOUND8PNT", address: 0x0400048a, size: 2 },
+    IoRegister { name: "SOUND8LEN", address: 0x0400048c, size: 4 },
+    IoRegister { name: "SOUND9CNT", address: 0x04000490, size: 4 },
+    IoRegister { name: "SOUND9SAD", address: 0x04000494, size: 4 },
+    IoRegister { name: "SOUND9TMR", address: 0x04000498, size: 2 },
+    IoRegister { name: "SOUND9PNT", address: 0x0400049a, size: 2 },
+    IoRegister { name: "SOUND9LEN", address: 0x0400049c, size: 4 },
+    IoRegister { name: "SOUND10CNT", address: 0x040004a0, size: 4 },
+    IoRegister { name: "SOUND10SAD", address: 0x040004a4, size: 4 },
+    IoRegister { name: "SOUND10TMR", address: 0x040004a8, size: 2 },
+    IoRegister { name: "SOUND10PNT", address: 0x040004aa, size: 2 },
+    IoRegister { name: "SOUND10LEN", address: 0x040004ac, size: 4 },
+    IoRegister { name: "SOUND11CNT", address: 0x040004b0, size: 4 },
+    IoRegister { name: "SOUND11SAD", address: 0x040004b4, size: 4 },
+    IoRegister { name: "SOUND11TMR", address: 0x040004b8, size: 2 },
+    IoRegister { name: "SOUND11PNT", address: 0x040004ba, size: 2 },
+    IoRegister { name: "SOUND11LEN", address: 0x040004bc, size: 4 },
+    IoRegister { name: "SOUND12CNT", address: 0x040004c0, size: 4 },
+    IoRegister { name: "SOUND12SAD", address: 0x040004c4, size: 4 },
+    IoRegister { name: "SOUND12TMR", address: 0x040004c8, size: 2 },
+    IoRegister { name: "SOUND12PNT", address: 0x040004ca, size: 2 },
+    IoRegister { name: "SOUND12LEN", address: 0x040004cc, size: 4 },
+    IoRegister { name: "SOUND13CNT", address: 0x040004d0, size: 4 },
+    IoRegister { name: "SOUND13SAD", address: 0x040004d4, size: 4 },
+    IoRegister { name: "SOUND13TMR", address: 0x040004d8, size: 2 },
+    IoRegister { name: "SOUND13PNT", address: 0x040004da, size: 2 },
+    IoRegister { name: "SOUND13LEN", address: 0x040004dc, size: 4 },
+    IoRegister { name: "SOUND14CNT", address: 0x040004e0, size: 4 },
+    IoRegister { name: "SOUND14SAD", address: 0x040004e4, size: 4 },
+    IoRegister { name: "SOUND14TMR", address: 0x040004e8, size: 2 },
+    IoRegister { name: "SOUND14PNT", address: 0x040004ea, size: 2 },
+    IoRegister { name: "SOUND14LEN", address: 0x040004ec, size: 4 },
+    IoRegister { name: "SOUND15CNT", address: 0x040004f0, size: 4 },
+    IoRegister { name: "SOUND15SAD", address: 0x040004f4, size: 4 },
+    IoRegister { name: "SOUND15TMR", address: 0x040004f8, size: 2 },
+    IoRegister { name: "SOUND15PNT", address: 0x040004fa, size: 2 },
+    IoRegister { name: "SOUND15LEN", address: 0x040004fc, size: 4 },
+    IoRegister { name: "SOUNDCNT", address: 0x04000500, size: 2 },
+    IoRegister { name: "SOUNDBIAS", address: 0x04000504, size: 2 },
+    IoRegister { name: "SNDCAP0CNT", address: 0x04000508, size: 1 },
+    IoRegister { name: "SNDCAP1CNT", address: 0x04000509, size: 1 },
+    IoRegister { name: "SNDCAP0DAD", address: 0x04000510, size: 4 },
+    IoRegister { name: "SNDCAP0LEN", address: 0x04000514, size: 2 },
+    IoRegister { name: "SNDCAP1DAD", address: 0x04000518, size: 4 },
+    IoRegister { name: "SNDCAP1LEN", address: 0x0400051c, size: 2 },
+    IoRegister { name: "IPCFIFORECV", address: 0x04100000, size: 4 },
+    IoRegister { name: "CARD_DATA_RD", address: 0x04100010, size: 4 },
+];
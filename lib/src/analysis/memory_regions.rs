@@ -0,0 +1,89 @@
+use std::fmt::Display;
+
+use super::io_registers::{IoRegister, IoRegisterMap};
+
+/// A fixed memory region of the ARM9 which is not part of any module, such as VRAM or palette RAM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryRegion {
+    /// Name of the region. Regions of the sub display engine start with `DB_`.
+    pub name: &'static str,
+    pub start: u32,
+    /// End address, exclusive
+    pub end: u32,
+}
+
+/// What a constant which is not a pointer into a module may point to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedAddress {
+    /// An I/O register, with the offset into the register
+    IoRegister(&'static IoRegister, u32),
+    /// A memory region, with the offset into the region
+    MemoryRegion(&'static MemoryRegion, u32),
+}
+
+impl MemoryRegion {
+    /// Returns the regions of the ARM9, sorted by address.
+    pub fn all() -> &'static [MemoryRegion] {
+        MEMORY_REGIONS
+    }
+
+    /// Finds the region which contains the given address.
+    pub fn find(address: u32) -> Option<&'static MemoryRegion> {
+        let index = MEMORY_REGIONS.partition_point(|region| region.start <= address).checked_sub(1)?;
+        let region = &MEMORY_REGIONS[index];
+        (address < region.end).then_some(region)
+    }
+}
+
+impl FixedAddress {
+    /// Classifies an address on the ARM9 which is outside of the modules. I/O registers take precedence over the memory
+    /// regions.
+    pub fn classify(address: u32) -> Option<Self> {
+        if let Some((register, offset)) = IoRegisterMap::Arm9.find(address) {
+            return Some(Self::IoRegister(register, offset));
+        }
+        let region = MemoryRegion::find(address)?;
+        Some(Self::MemoryRegion(region, address - region.start))
+    }
+}
+
+/// Displays the name of the register or region, followed by the offset if it's not zero.
+impl Display for FixedAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let offset = match self {
+            Self::IoRegister(register, offset) => {
+                write!(f, "{register}")?;
+                *offset
+            }
+            Self::MemoryRegion(region, offset) => {
+                write!(f, "{}", region.name)?;
+                *offset
+            }
+        };
+        if offset != 0 {
+            write!(f, "+{offset:#x}")?;
+        }
+        Ok(())
+    }
+}
+
+const MEMORY_REGIONS: &[MemoryRegion] = &[
+    MemoryRegion { name: "MAIN_MEM", start: 0x02000000, end: 0x02400000 },
+    MemoryRegion { name: "MAIN_MEM_MIRROR", start: 0x02400000, end: 0x027ff000 },
+    MemoryRegion { name: "MAIN_MEM_SHARED", start: 0x027ff000, end: 0x02800000 },
+    MemoryRegion { name: "SHARED_WRAM", start: 0x03000000, end: 0x04000000 },
+    MemoryRegion { name: "BG_PLTT", start: 0x05000000, end: 0x05000200 },
+    MemoryRegion { name: "OBJ_PLTT", start: 0x05000200, end: 0x05000400 },
+    MemoryRegion { name: "DB_BG_PLTT", start: 0x05000400, end: 0x05000600 },
+    MemoryRegion { name: "DB_OBJ_PLTT", start: 0x05000600, end: 0x05000800 },
+    MemoryRegion { name: "BG_VRAM", start: 0x06000000, end: 0x06200000 },
+    MemoryRegion { name: "DB_BG_VRAM", start: 0x06200000, end: 0x06400000 },
+    MemoryRegion { name: "OBJ_VRAM", start: 0x06400000, end: 0x06600000 },
+    MemoryRegion { name: "DB_OBJ_VRAM", start: 0x06600000, end: 0x06800000 },
+    MemoryRegion { name: "LCDC_VRAM", start: 0x06800000, end: 0x068a4000 },
+    MemoryRegion { name: "OAM", start: 0x07000000, end: 0x07000400 },
+    MemoryRegion { name: "DB_OAM", start: 0x07000400, end: 0x07000800 },
+    MemoryRegion { name: "CTRDG_ROM", start: 0x08000000, end: 0x0a000000 },
+    MemoryRegion { name: "CTRDG_RAM", start: 0x0a000000, end: 0x0a010000 },
+    MemoryRegion { name: "BIOS", start: 0xffff0000, end: 0xffff8000 },
+];
@@ -1,10 +1,13 @@
 pub(crate) mod ctor;
 pub(crate) mod data;
+pub mod fixed_point;
 mod function_branch;
 mod function_start;
 pub mod functions;
 mod illegal_code;
 mod inline_table;
+pub mod io_registers;
 pub(crate) mod jump_table;
 pub(crate) mod main;
+pub mod memory_regions;
 pub mod secure_area;
@@ -73,7 +73,42 @@ pub enum SwiFunction {
     RLUnCompReadByCallbackWrite16bit,
 }
 
+/// A register used by a BIOS function, with a short description of its value.
+pub type SwiRegister = (&'static str, &'static str);
+
 impl SwiFunction {
+    /// All BIOS functions of the ARM9 BIOS. `Div` and `Mod` share the same interrupt value.
+    pub const ALL: [Self; 18] = [
+        Self::SoftReset,
+        Self::WaitByLoop,
+        Self::IntrWait,
+        Self::VBlankIntrWait,
+        Self::Halt,
+        Self::Div,
+        Self::Mod,
+        Self::CpuSet,
+        Self::CpuFastSet,
+        Self::Sqrt,
+        Self::GetCRC16,
+        Self::IsDebugger,
+        Self::BitUnPack,
+        Self::LZ77UnCompReadNormalWrite8bit,
+        Self::LZ77UnCompReadByCallbackWrite16bit,
+        Self::HuffUnCompReadByCallback,
+        Self::RLUnCompReadNormalWrite8bit,
+        Self::RLUnCompReadByCallbackWrite16bit,
+    ];
+
+    /// Returns the BIOS function called by a `swi`/`svc` instruction, if it is known. In ARM mode, the BIOS reads the
+    /// interrupt value from bits 16-23 of the comment field.
+    pub fn from_instruction(parsed_ins: &ParsedIns, thumb: bool) -> Option<Self> {
+        let ("swi" | "svc", Argument::UImm(comment)) = (parsed_ins.mnemonic, parsed_ins.args[0]) else {
+            return None;
+        };
+        let interrupt = if thumb { comment } else { comment >> 16 };
+        interrupt.try_into().ok()
+    }
+
     pub fn interrupt_value(self) -> u32 {
         match self {
             Self::SoftReset => 0x0,
@@ -119,6 +154,60 @@ impl SwiFunction {
             (Self::RLUnCompReadByCallbackWrite16bit, _) => "RLUnCompReadByCallbackWrite16bit",
         }
     }
+
+    /// Describes how the BIOS function is called, like `Sqrt(r0: value) -> (r0: root)`.
+    pub fn signature(self) -> String {
+        let registers = |registers: &[SwiRegister]| {
+            registers.iter().map(|(register, name)| format!("{register}: {name}")).collect::<Vec<_>>().join(", ")
+        };
+        let name = self.name(Register::R0);
+        let arguments = registers(self.arguments());
+        match self.return_values() {
+            [] => format!("{name}({arguments})"),
+            return_values => format!("{name}({arguments}) -> ({})", registers(return_values)),
+        }
+    }
+
+    /// Registers which are passed to the BIOS function.
+    pub fn arguments(self) -> &'static [SwiRegister] {
+        match self {
+            Self::SoftReset | Self::VBlankIntrWait | Self::Halt | Self::IsDebugger => &[],
+            Self::WaitByLoop => &[("r0", "count")],
+            Self::IntrWait => &[("r0", "discard_old"), ("r1", "flags")],
+            Self::Div | Self::Mod => &[("r0", "numerator"), ("r1", "denominator")],
+            Self::CpuSet | Self::CpuFastSet => &[("r0", "src"), ("r1", "dest"), ("r2", "length_mode")],
+            Self::Sqrt => &[("r0", "value")],
+            Self::GetCRC16 => &[("r0", "crc"), ("r1", "data"), ("r2", "size")],
+            Self::BitUnPack => &[("r0", "src"), ("r1", "dest"), ("r2", "info")],
+            Self::LZ77UnCompReadNormalWrite8bit | Self::RLUnCompReadNormalWrite8bit => &[("r0", "src"), ("r1", "dest")],
+            Self::LZ77UnCompReadByCallbackWrite16bit
+            | Self::HuffUnCompReadByCallback
+            | Self::RLUnCompReadByCallbackWrite16bit => &[("r0", "src"), ("r1", "dest"), ("r2", "param"), ("r3", "callbacks")],
+        }
+    }
+
+    /// Registers which hold the results of the BIOS function.
+    pub fn return_values(self) -> &'static [SwiRegister] {
+        match self {
+            Self::SoftReset
+            | Self::WaitByLoop
+            | Self::IntrWait
+            | Self::VBlankIntrWait
+            | Self::Halt
+            | Self::CpuSet
+            | Self::CpuFastSet
+            | Self::BitUnPack
+            | Self::LZ77UnCompReadNormalWrite8bit
+            | Self::RLUnCompReadNormalWrite8bit => &[],
+            Self::Div | Self::Mod => &[("r0", "quotient"), ("r1", "remainder"), ("r3", "abs_quotient")],
+            Self::Sqrt => &[("r0", "root")],
+            Self::GetCRC16 => &[("r0", "crc")],
+            Self::IsDebugger => &[("r0", "is_debugger")],
+            Self::LZ77UnCompReadByCallbackWrite16bit
+            | Self::HuffUnCompReadByCallback
+            | Self::RLUnCompReadByCallbackWrite16bit => &[("r0", "size")],
+        }
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -17,6 +17,12 @@ pub struct Config {
     pub main_module: ConfigModule,
     pub autoloads: Vec<ConfigAutoload>,
     pub overlays: Vec<ConfigOverlay>,
+    /// Overlays which are not in the original ROM, such as for mods
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub new_overlays: Vec<ConfigNewOverlay>,
+    /// Files which are added to the NitroFS file system of the original ROM
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub new_files: Vec<ConfigNewFile>,
 }
 
 #[derive(Debug, Snafu)]
@@ -55,6 +61,9 @@ pub struct ConfigOverlay {
     pub id: u16,
     #[serde(flatten)]
     pub module: ConfigModule,
+    /// Address to place the overlay at, such as `0x027e0000`, instead of after the modules it follows in the original ROM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -63,3 +72,26 @@ pub struct ConfigAutoload {
     #[serde(flatten)]
     pub module: ConfigModule,
 }
+
+#[derive(Serialize, Deserialize)]
+pub struct ConfigNewOverlay {
+    /// Overlay ID, which must follow the last overlay of the original ROM or another new overlay
+    pub id: u16,
+    /// Name of module
+    pub name: String,
+    /// Binary file to build
+    pub object: PathBuf,
+    /// ID of the overlay to load this overlay after, or the ARM9 program if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<u16>,
+    /// Source files to link into the overlay, relative to the build path
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConfigNewFile {
+    /// Path to the file to add
+    pub source: PathBuf,
+    /// Path of the file in the file system, such as `data/mod/foo.bin`
+    pub path: String,
+}
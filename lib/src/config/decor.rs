@@ -0,0 +1,204 @@
+use std::{collections::HashMap, fmt::Display};
+
+/// Comments and blank lines around an entry in a config text file, such as a symbol in `symbols.txt`. They are kept when
+/// parsing, so that tools can rewrite the file without losing them.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Decor {
+    /// Lines before the entry which contain only comments or whitespace
+    pub leading: Vec<String>,
+    /// Comment at the end of the entry's line, including the whitespace before `//`
+    pub trailing: Option<String>,
+}
+
+impl Decor {
+    /// Writes the leading lines of the entry.
+    pub fn fmt_leading(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        for line in &self.leading {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the trailing comment, or an empty string if there is none.
+    pub fn trailing(&self) -> &str {
+        self.trailing.as_deref().unwrap_or("")
+    }
+}
+
+/// Removes the first blank line, which is written automatically between some entries.
+pub(crate) fn remove_blank_line(lines: &mut Vec<String>) {
+    if let Some(pos) = lines.iter().position(|line| line.trim().is_empty()) {
+        lines.remove(pos);
+    }
+}
+
+/// Decors of the lines in a block, such as a file in `delinks.txt`, keyed by the first word of each line.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct LineDecors {
+    lines: HashMap<String, Decor>,
+    /// Lines at the end of the block which contain only comments or whitespace
+    pub footer: Vec<String>,
+}
+
+impl LineDecors {
+    pub fn get(&self, key: &str) -> Option<&Decor> {
+        self.lines.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, decor: Decor) {
+        self.lines.insert(key, decor);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Decor> {
+        self.lines.remove(key)
+    }
+
+    /// Writes a line with its decor, if it has one.
+    pub fn fmt_line(&self, f: &mut impl std::fmt::Write, key: &str, line: impl Display) -> std::fmt::Result {
+        match self.lines.get(key) {
+            Some(decor) => {
+                decor.fmt_leading(f)?;
+                writeln!(f, "{line}{}", decor.trailing())
+            }
+            None => writeln!(f, "{line}"),
+        }
+    }
+
+    pub fn fmt_footer(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        for line in &self.footer {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects comments and blank lines while parsing a config text file, and attaches them to the next entry.
+#[derive(Default)]
+pub(crate) struct DecorParser {
+    leading: Vec<String>,
+}
+
+impl DecorParser {
+    /// Splits a line into its contents and its comment. Returns `None` if the line has no contents, in which case the line
+    /// is kept for the next entry.
+    pub fn parse_line<'a>(&mut self, line: &'a str) -> Option<(&'a str, Decor)> {
+        let comment_start = line.find("//").unwrap_or(line.len());
+        let contents = &line[..comment_start];
+        if contents.trim().is_empty() {
+            self.leading.push(line.to_string());
+            return None;
+        }
+
+        let contents = contents.trim_end();
+        let trailing = &line[contents.len()..];
+        let trailing = (!trailing.is_empty()).then(|| trailing.to_string());
+        Some((contents, Decor { leading: std::mem::take(&mut self.leading), trailing }))
+    }
+
+    /// Returns the collected lines which have not been attached to an entry.
+    pub fn finish(self) -> Vec<String> {
+        self.leading
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::config::{delinks::Delinks, module::ModuleKind, relocations::Relocations, symbol::SymbolMap};
+
+    /// Writes `text` to a file, parses and writes it back with `round_trip`, and returns the written text.
+    fn rewrite(name: &str, text: &str, round_trip: impl FnOnce(&Path, &Path)) -> String {
+        let dir = std::env::temp_dir().join(format!("ds-decomp-decor-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join(name);
+        let output = dir.join(format!("{name}.out"));
+        fs::write(&input, text).unwrap();
+        round_trip(&input, &output);
+        let written = fs::read_to_string(&output).unwrap();
+        fs::remove_file(input).unwrap();
+        fs::remove_file(output).unwrap();
+        written
+    }
+
+    #[test]
+    fn parse_line() {
+        let mut parser = DecorParser::default();
+        assert!(parser.parse_line("// Functions").is_none());
+        assert!(parser.parse_line("   ").is_none());
+
+        let (contents, decor) = parser.parse_line("main kind:function(arm,size=0x30) addr:0x02000000\t// entry").unwrap();
+        assert_eq!(contents, "main kind:function(arm,size=0x30) addr:0x02000000");
+        assert_eq!(decor.leading, ["// Functions", "   "]);
+        assert_eq!(decor.trailing(), "\t// entry");
+
+        let (_, decor) = parser.parse_line("data_02000030 kind:data(any) addr:0x02000030").unwrap();
+        assert!(decor.leading.is_empty());
+        assert!(decor.trailing.is_none());
+
+        assert!(parser.parse_line("// end").is_none());
+        assert_eq!(parser.finish(), ["// end"]);
+    }
+
+    #[test]
+    fn symbols_round_trip() {
+        let text = "\
+// Functions
+main kind:function(arm,size=0x30) addr:0x02000c30 // entry point
+
+  // Helpers
+func_02000c60 kind:function(thumb,size=0x1c,nonmatching) addr:0x02000c60
+data_02050f54 kind:data(any) addr:0x02050f54\t// tab before the comment
+gBgTiles kind:abs addr:0x06010000
+
+// Footer
+";
+        let written = rewrite("symbols.txt", text, |input, output| {
+            SymbolMap::from_file(input).unwrap().to_file(output).unwrap();
+        });
+        assert_eq!(written, text);
+    }
+
+    #[test]
+    fn relocations_round_trip() {
+        let text = "\
+// Calls
+from:0x02000c34 kind:arm_call to:0x02000c60 module:main // to a helper
+
+from:0x02000c40 kind:load to:0x02050f54 module:overlays(1,2)
+// Footer
+
+";
+        let written = rewrite("relocs.txt", text, |input, output| {
+            Relocations::from_file(input).unwrap().to_file(output).unwrap();
+        });
+        assert_eq!(written, text);
+    }
+
+    #[test]
+    fn delinks_round_trip() {
+        let text = "\
+// Sections of the module
+    .text       start:0x02000000 end:0x02001000 kind:code align:32 // code
+    .data       start:0x02001000 end:0x02001100 kind:data align:4
+
+// First file
+src/main.c: // entry point
+    complete
+    .text       start:0x02000000 end:0x02000100
+    // Data of main.c
+    .data       start:0x02001000 end:0x02001010 // tables
+    // End of main.c
+
+src/foo.c:
+    .text       start:0x02000100 end:0x02000200
+
+// Footer
+";
+        let written = rewrite("delinks.txt", text, |input, output| {
+            Delinks::from_file(input, ModuleKind::Arm9).unwrap().to_file(output).unwrap();
+        });
+        assert_eq!(written, text);
+    }
+}
@@ -6,19 +6,22 @@ use std::{
     path::Path,
 };
 
-use snafu::Snafu;
+use snafu::{ensure, Snafu};
 
 use crate::util::io::{create_file, open_file, FileError};
 
 use super::{
+    decor::{remove_blank_line, Decor, DecorParser, LineDecors},
     module::ModuleKind,
-    section::{Section, SectionInheritParseError, SectionParseError, Sections, SectionsError},
+    section::{Section, SectionError, SectionInheritParseError, SectionParseError, Sections, SectionsError},
     ParseContext,
 };
 
 pub struct Delinks {
     pub sections: Sections,
     pub files: Vec<DelinkFile>,
+    /// Comments and blank lines of the module's sections, and at the end of delinks.txt
+    pub section_decors: LineDecors,
     module_kind: ModuleKind,
 }
 
@@ -44,9 +47,33 @@ pub enum DelinksWriteError {
     Io { source: io::Error },
 }
 
+#[derive(Debug, Snafu)]
+pub enum DelinksError {
+    #[snafu(display("file '{name}' not found:\n{backtrace}"))]
+    FileNotFound { name: String, backtrace: Backtrace },
+    #[snafu(display("file '{name}' already exists:\n{backtrace}"))]
+    DuplicateFile { name: String, backtrace: Backtrace },
+    #[snafu(display("section {section} of file '{file}' does not exist in the module:\n{backtrace}"))]
+    SectionNotInModule { file: String, section: String, backtrace: Backtrace },
+    #[snafu(display(
+        "section {section} of file '{file}' ({start:#010x}..{end:#010x}) is outside of the module's section:\n{backtrace}"
+    ))]
+    SectionOutsideModule { file: String, section: String, start: u32, end: u32, backtrace: Backtrace },
+    #[snafu(display("section {section} of file '{file}' overlaps with file '{other_file}':\n{backtrace}"))]
+    FileOverlap { file: String, other_file: String, section: String, backtrace: Backtrace },
+    #[snafu(display("file '{name}' has no sections after {address:#010x} to split:\n{backtrace}"))]
+    NothingToSplit { name: String, address: u32, backtrace: Backtrace },
+    #[snafu(display("file '{name}' has no sections before {address:#010x} to keep:\n{backtrace}"))]
+    NothingBeforeSplit { name: String, address: u32, backtrace: Backtrace },
+    #[snafu(transparent)]
+    Section { source: SectionError },
+    #[snafu(transparent)]
+    Sections { source: SectionsError },
+}
+
 impl Delinks {
     pub fn new(sections: Sections, files: Vec<DelinkFile>, module_kind: ModuleKind) -> Self {
-        Self { sections, files, module_kind }
+        Self { sections, files, section_decors: LineDecors::default(), module_kind }
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P, module_kind: ModuleKind) -> Result<Self, DelinksParseError> {
@@ -57,67 +84,172 @@ impl Delinks {
         let reader = BufReader::new(file);
 
         let mut sections: Sections = Sections::new();
+        let mut section_decors = LineDecors::default();
         let mut files = vec![];
 
+        let mut decor_parser = DecorParser::default();
         let mut lines = reader.lines();
         while let Some(line) = lines.next() {
             context.row += 1;
 
             let line = line?;
-            let comment_start = line.find("//").unwrap_or(line.len());
-            let line = &line[..comment_start];
-
-            if Self::try_parse_delink_file(line, &mut lines, &mut context, &mut files, &sections)? {
-                break;
+            let Some((line, mut decor)) = decor_parser.parse_line(&line) else { continue };
+
+            if Self::is_delink_file(line) {
+                if files.is_empty() {
+                    // The blank line between the module and the files is written automatically
+                    remove_blank_line(&mut decor.leading);
+                }
+                files.push(DelinkFile::parse(line, decor, &mut lines, &mut context, &sections)?);
+                continue;
+            }
+            if !files.is_empty() {
+                continue;
             }
             let Some(section) = Section::parse(line, &context)? else {
                 continue;
             };
+            section_decors.insert(section.name().to_string(), decor);
             sections.add(section).map_err(|error| SectionsSnafu { context: context.clone(), error }.build())?;
         }
 
-        while let Some(line) = lines.next() {
-            context.row += 1;
-
-            let line = line?;
-            let comment_start = line.find("//").unwrap_or(line.len());
-            let line = &line[..comment_start];
-
-            Self::try_parse_delink_file(line, &mut lines, &mut context, &mut files, &sections)?;
+        section_decors.footer = decor_parser.finish();
+        if files.is_empty() {
+            remove_blank_line(&mut section_decors.footer);
         }
 
-        Ok(Self { sections, files, module_kind })
+        Ok(Self { sections, files, section_decors, module_kind })
     }
 
-    fn try_parse_delink_file(
-        line: &str,
-        lines: &mut Lines<BufReader<File>>,
-        context: &mut ParseContext,
-        files: &mut Vec<DelinkFile>,
-        sections: &Sections,
-    ) -> Result<bool, DelinkFileParseError> {
-        if line.chars().next().map_or(false, |c| !c.is_whitespace()) {
-            let delink_file = DelinkFile::parse(line, lines, context, sections)?;
-            files.push(delink_file);
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+    /// Returns whether the line starts a file, i.e. is not indented.
+    fn is_delink_file(line: &str) -> bool {
+        line.chars().next().map_or(false, |c| !c.is_whitespace())
+    }
+
+    /// Writes the module's sections and all files, except for gap files.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), DelinksWriteError> {
+        let path = path.as_ref();
+
+        let file = create_file(path)?;
+        let mut writer = BufWriter::new(file);
+
+        write!(writer, "{}", self.display())?;
+
+        Ok(())
     }
 
-    pub fn to_file<P: AsRef<Path>>(path: P, sections: &Sections) -> Result<(), DelinksWriteError> {
+    /// Writes a new delinks.txt which only has the module's sections.
+    pub fn sections_to_file<P: AsRef<Path>>(path: P, sections: &Sections) -> Result<(), DelinksWriteError> {
         let path = path.as_ref();
 
         let file = create_file(path)?;
         let mut writer = BufWriter::new(file);
 
-        write!(writer, "{}", DisplayDelinks { sections, files: &[] })?;
+        write!(writer, "{}", DisplayDelinks { sections, files: &[], section_decors: &LineDecors::default() })?;
 
         Ok(())
     }
 
+    pub fn file_index(&self, name: &str) -> Option<usize> {
+        self.files.iter().position(|file| file.name == name)
+    }
+
+    pub fn file_by_name(&self, name: &str) -> Option<&DelinkFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+
+    /// Inserts a file at `index`, or at the end if `index` is `None`. The file's sections must be within the module and must
+    /// not overlap with other files.
+    pub fn add_file(&mut self, file: DelinkFile, index: Option<usize>) -> Result<(), DelinksError> {
+        ensure!(self.file_index(&file.name).is_none(), DuplicateFileSnafu { name: file.name });
+        self.validate_file(&file)?;
+
+        let index = index.unwrap_or(self.files.len()).min(self.files.len());
+        self.files.insert(index, file);
+        Ok(())
+    }
+
+    pub fn remove_file(&mut self, name: &str) -> Option<DelinkFile> {
+        let index = self.file_index(name)?;
+        Some(self.files.remove(index))
+    }
+
+    /// Moves a file to `index` in the list of files.
+    pub fn move_file(&mut self, name: &str, index: usize) -> Result<(), DelinksError> {
+        let from = self.file_index(name).ok_or_else(|| FileNotFoundSnafu { name }.build())?;
+        let file = self.files.remove(from);
+        self.files.insert(index.min(self.files.len()), file);
+        Ok(())
+    }
+
+    /// Splits a file at an address. Sections which start at or after the address are moved to a new file, and a section
+    /// which contains the address is divided between the two files. The new file is inserted after the split file.
+    /// `kept_complete` and `new_complete` are whether the first and second half are complete, as a complete file can be
+    /// split into a complete and an incomplete half.
+    pub fn split_file(
+        &mut self,
+        name: &str,
+        address: u32,
+        new_name: String,
+        kept_complete: bool,
+        new_complete: bool,
+    ) -> Result<&DelinkFile, DelinksError> {
+        let index = self.file_index(name).ok_or_else(|| FileNotFoundSnafu { name }.build())?;
+        ensure!(self.file_index(&new_name).is_none(), DuplicateFileSnafu { name: new_name });
+
+        // Both halves are built before changing the file, so that it's left as is on error
+        let file = &self.files[index];
+        let mut kept_sections = Sections::new();
+        let mut new_sections = Sections::new();
+        for section in file.sections.iter() {
+            if section.end_address() <= address {
+                kept_sections.add(section.clone())?;
+            } else if section.start_address() >= address {
+                new_sections.add(section.clone())?;
+            } else {
+                kept_sections.add(Section::inherit(section, section.start_address(), address)?)?;
+                new_sections.add(Section::inherit(section, address, section.end_address())?)?;
+            }
+        }
+        ensure!(kept_sections.len() > 0, NothingBeforeSplitSnafu { name, address });
+        ensure!(new_sections.len() > 0, NothingToSplitSnafu { name, address });
+
+        let new_file = DelinkFile::new(new_name, new_sections, new_complete);
+        self.files[index].sections = kept_sections;
+        self.files[index].complete = kept_complete;
+        self.files.insert(index + 1, new_file);
+        Ok(&self.files[index + 1])
+    }
+
+    fn validate_file(&self, file: &DelinkFile) -> Result<(), DelinksError> {
+        for section in file.sections.iter() {
+            let Some((_, module_section)) = self.sections.by_name(section.name()) else {
+                return SectionNotInModuleSnafu { file: &file.name, section: section.name() }.fail();
+            };
+            ensure!(
+                module_section.start_address() <= section.start_address()
+                    && section.end_address() <= module_section.end_address(),
+                SectionOutsideModuleSnafu {
+                    file: &file.name,
+                    section: section.name(),
+                    start: section.start_address(),
+                    end: section.end_address()
+                }
+            );
+
+            for other in self.files.iter().filter(|other| other.name != file.name) {
+                let Some((_, other_section)) = other.sections.by_name(section.name()) else { continue };
+                ensure!(
+                    !section.overlaps_with(other_section),
+                    FileOverlapSnafu { file: &file.name, other_file: &other.name, section: section.name() }
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub fn display(&self) -> DisplayDelinks {
-        DisplayDelinks { sections: &self.sections, files: &self.files }
+        DisplayDelinks { sections: &self.sections, files: &self.files, section_decors: &self.section_decors }
     }
 
     pub fn module_kind(&self) -> ModuleKind {
@@ -127,17 +259,19 @@ impl Delinks {
 pub struct DisplayDelinks<'a> {
     sections: &'a Sections,
     files: &'a [DelinkFile],
+    section_decors: &'a LineDecors,
 }
 
 impl<'a> Display for DisplayDelinks<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for section in self.sections.sorted_by_address() {
-            writeln!(f, "    {section}")?;
+            self.section_decors.fmt_line(f, section.name(), format_args!("    {section}"))?;
         }
         writeln!(f)?;
-        for file in self.files {
+        for file in self.files.iter().filter(|file| !file.gap) {
             writeln!(f, "{file}")?;
         }
+        self.section_decors.fmt_footer(f)?;
         Ok(())
     }
 }
@@ -147,6 +281,10 @@ pub struct DelinkFile {
     pub sections: Sections,
     pub complete: bool,
     pub gap: bool,
+    /// Comments and blank lines before the file's name
+    pub decor: Decor,
+    /// Comments of the file's options and sections, by option or section name
+    pub line_decors: LineDecors,
 }
 
 #[derive(Debug, Snafu)]
@@ -163,11 +301,12 @@ pub enum DelinkFileParseError {
 
 impl DelinkFile {
     pub fn new(name: String, sections: Sections, complete: bool) -> Self {
-        Self { name, sections, complete, gap: false }
+        Self { name, sections, complete, gap: false, decor: Decor::default(), line_decors: LineDecors::default() }
     }
 
     pub fn parse(
         first_line: &str,
+        decor: Decor,
         lines: &mut Lines<BufReader<File>>,
         context: &mut ParseContext,
         inherit_sections: &Sections,
@@ -180,22 +319,28 @@ impl DelinkFile {
 
         let mut complete = false;
         let mut sections = Sections::new();
+        let mut line_decors = LineDecors::default();
+        let mut decor_parser = DecorParser::default();
         for line in lines.by_ref() {
             context.row += 1;
             let line = line?;
-            let line = line.trim();
-            if line.is_empty() {
+            if line.trim().is_empty() {
                 break;
             }
+            let Some((line, decor)) = decor_parser.parse_line(&line) else { continue };
+            let line = line.trim();
             if line == "complete" {
                 complete = true;
+                line_decors.insert(line.to_string(), decor);
                 continue;
             }
             let section = Section::parse_inherit(line, context, inherit_sections)?.unwrap();
+            line_decors.insert(section.name().to_string(), decor);
             sections.add(section)?;
         }
+        line_decors.footer = decor_parser.finish();
 
-        Ok(DelinkFile { name, sections, complete, gap: false })
+        Ok(DelinkFile { name, sections, complete, gap: false, decor, line_decors })
     }
 
     pub fn split_file_ext(&self) -> (&str, &str) {
@@ -209,10 +354,177 @@ impl DelinkFile {
 
 impl Display for DelinkFile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}:", self.name)?;
+        self.decor.fmt_leading(f)?;
+        writeln!(f, "{}:{}", self.name, self.decor.trailing())?;
+        if self.complete {
+            self.line_decors.fmt_line(f, "complete", "    complete")?;
+        }
         for section in self.sections.sorted_by_address() {
-            writeln!(f, "    {section}")?;
+            self.line_decors.fmt_line(f, section.name(), format_args!("    {}", section.display_inherit()))?;
         }
+        self.line_decors.fmt_footer(f)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::section::SectionKind;
+
+    fn delinks() -> Delinks {
+        let mut sections = Sections::new();
+        sections.add(Section::new(".text".to_string(), SectionKind::Code, 0x02000000, 0x02001000, 32).unwrap()).unwrap();
+        sections.add(Section::new(".data".to_string(), SectionKind::Data, 0x02001000, 0x02001100, 4).unwrap()).unwrap();
+        Delinks::new(sections, vec![], ModuleKind::Arm9)
+    }
+
+    /// Creates a file whose sections inherit from the module's sections, or have code kind if the module has no such
+    /// section.
+    fn file(delinks: &Delinks, name: &str, ranges: &[(&str, u32, u32)]) -> DelinkFile {
+        let mut sections = Sections::new();
+        for &(section, start, end) in ranges {
+            let section = match delinks.sections.by_name(section) {
+                Some((_, module_section)) => Section::inherit(module_section, start, end).unwrap(),
+                None => Section::new(section.to_string(), SectionKind::Code, start, end, 4).unwrap(),
+            };
+            sections.add(section).unwrap();
+        }
+        DelinkFile::new(name.to_string(), sections, false)
+    }
+
+    fn ranges(file: &DelinkFile) -> Vec<(&str, u32, u32)> {
+        file.sections
+            .sorted_by_address()
+            .into_iter()
+            .map(|section| (section.name(), section.start_address(), section.end_address()))
+            .collect()
+    }
+
+    fn names(delinks: &Delinks) -> Vec<&str> {
+        delinks.files.iter().map(|file| file.name.as_str()).collect()
+    }
+
+    #[test]
+    fn add_file() {
+        let mut delinks = delinks();
+        let a = file(&delinks, "a.c", &[(".text", 0x02000000, 0x02000100)]);
+        delinks.add_file(a, None).unwrap();
+        // Files may end and start at the same address
+        let c = file(&delinks, "c.c", &[(".text", 0x02000200, 0x02000300)]);
+        delinks.add_file(c, None).unwrap();
+        let b = file(&delinks, "b.c", &[(".text", 0x02000100, 0x02000200)]);
+        delinks.add_file(b, Some(1)).unwrap();
+        assert_eq!(names(&delinks), ["a.c", "b.c", "c.c"]);
+    }
+
+    #[test]
+    fn add_file_errors() {
+        let mut delinks = delinks();
+        let a = file(&delinks, "a.c", &[(".text", 0x02000000, 0x02000100), (".data", 0x02001000, 0x02001010)]);
+        delinks.add_file(a, None).unwrap();
+
+        let error = |delinks: &mut Delinks, name: &str, ranges: &[(&str, u32, u32)]| {
+            let file = file(delinks, name, ranges);
+            delinks.add_file(file, None).unwrap_err()
+        };
+        assert!(matches!(
+            error(&mut delinks, "b.c", &[(".text", 0x020000f0, 0x02000200)]),
+            DelinksError::FileOverlap { file, other_file, section, .. }
+                if file == "b.c" && other_file == "a.c" && section == ".text"
+        ));
+        assert!(matches!(
+            error(&mut delinks, "b.c", &[(".text", 0x02000100, 0x02000200), (".data", 0x02001008, 0x02001020)]),
+            DelinksError::FileOverlap { section, .. } if section == ".data"
+        ));
+        assert!(matches!(
+            error(&mut delinks, "b.c", &[(".text", 0x02000f00, 0x02001100)]),
+            DelinksError::SectionOutsideModule { start: 0x02000f00, end: 0x02001100, .. }
+        ));
+        assert!(matches!(
+            error(&mut delinks, "b.c", &[(".bss", 0x02001100, 0x02001200)]),
+            DelinksError::SectionNotInModule { section, .. } if section == ".bss"
+        ));
+        assert!(matches!(
+            error(&mut delinks, "a.c", &[(".text", 0x02000100, 0x02000200)]),
+            DelinksError::DuplicateFile { name, .. } if name == "a.c"
+        ));
+        assert_eq!(names(&delinks), ["a.c"]);
+    }
+
+    #[test]
+    fn move_and_remove_file() {
+        let mut delinks = delinks();
+        for (i, name) in ["a.c", "b.c", "c.c"].into_iter().enumerate() {
+            let start = 0x02000000 + i as u32 * 0x100;
+            let file = file(&delinks, name, &[(".text", start, start + 0x100)]);
+            delinks.add_file(file, None).unwrap();
+        }
+
+        delinks.move_file("a.c", 2).unwrap();
+        assert_eq!(names(&delinks), ["b.c", "c.c", "a.c"]);
+        delinks.move_file("a.c", 10).unwrap();
+        assert_eq!(names(&delinks), ["b.c", "c.c", "a.c"]);
+        assert!(matches!(delinks.move_file("d.c", 0), Err(DelinksError::FileNotFound { .. })));
+
+        assert!(delinks.remove_file("c.c").is_some());
+        assert!(delinks.remove_file("c.c").is_none());
+        assert_eq!(names(&delinks), ["b.c", "a.c"]);
+    }
+
+    #[test]
+    fn split_file_inside_section() {
+        let mut delinks = delinks();
+        let mut a = file(&delinks, "a.c", &[(".text", 0x02000000, 0x02000200), (".data", 0x02001000, 0x02001010)]);
+        a.complete = true;
+        delinks.add_file(a, None).unwrap();
+
+        let b = delinks.split_file("a.c", 0x02000100, "b.c".to_string(), true, false).unwrap();
+        assert_eq!(ranges(b), [(".text", 0x02000100, 0x02000200), (".data", 0x02001000, 0x02001010)]);
+        assert!(!b.complete);
+
+        let a = delinks.file_by_name("a.c").unwrap();
+        assert_eq!(ranges(a), [(".text", 0x02000000, 0x02000100)]);
+        assert!(a.complete);
+        assert_eq!(names(&delinks), ["a.c", "b.c"]);
+    }
+
+    #[test]
+    fn split_file_at_section_boundary() {
+        let mut delinks = delinks();
+        let a = file(&delinks, "a.c", &[(".text", 0x02000000, 0x02000100), (".data", 0x02001000, 0x02001010)]);
+        delinks.add_file(a, None).unwrap();
+
+        // Sections which end or start at the address are moved whole, instead of leaving an empty section behind
+        let b = delinks.split_file("a.c", 0x02000100, "b.c".to_string(), false, true).unwrap();
+        assert_eq!(ranges(b), [(".data", 0x02001000, 0x02001010)]);
+        assert!(b.complete);
+        let a = delinks.file_by_name("a.c").unwrap();
+        assert_eq!(ranges(a), [(".text", 0x02000000, 0x02000100)]);
+
+        delinks.split_file("b.c", 0x02001008, "c.c".to_string(), false, false).unwrap();
+        assert_eq!(ranges(delinks.file_by_name("b.c").unwrap()), [(".data", 0x02001000, 0x02001008)]);
+        assert_eq!(ranges(delinks.file_by_name("c.c").unwrap()), [(".data", 0x02001008, 0x02001010)]);
+        assert_eq!(names(&delinks), ["a.c", "b.c", "c.c"]);
+    }
+
+    #[test]
+    fn split_file_errors() {
+        let mut delinks = delinks();
+        let a = file(&delinks, "a.c", &[(".text", 0x02000000, 0x02000100)]);
+        delinks.add_file(a, None).unwrap();
+        let b = file(&delinks, "b.c", &[(".text", 0x02000100, 0x02000200)]);
+        delinks.add_file(b, None).unwrap();
+
+        let mut split = |name: &str, address: u32, new_name: &str| {
+            delinks.split_file(name, address, new_name.to_string(), false, false).map(|_| ()).unwrap_err()
+        };
+        assert!(matches!(split("a.c", 0x02000000, "c.c"), DelinksError::NothingBeforeSplit { .. }));
+        assert!(matches!(split("a.c", 0x02000100, "c.c"), DelinksError::NothingToSplit { .. }));
+        assert!(matches!(split("a.c", 0x02000080, "b.c"), DelinksError::DuplicateFile { .. }));
+        assert!(matches!(split("c.c", 0x02000080, "d.c"), DelinksError::FileNotFound { .. }));
+
+        assert_eq!(names(&delinks), ["a.c", "b.c"]);
+        assert_eq!(ranges(delinks.file_by_name("a.c").unwrap()), [(".text", 0x02000000, 0x02000100)]);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fmt::Display, str::SplitWhitespace};
 
 pub mod config;
+pub mod decor;
 pub mod delinks;
 pub mod module;
 pub mod relocations;
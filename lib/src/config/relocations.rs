@@ -1,6 +1,6 @@
 use std::{
     backtrace::Backtrace,
-    collections::{btree_map, BTreeMap},
+    collections::{btree_map, BTreeMap, HashSet},
     fmt::Display,
     io::{self, BufRead, BufReader, BufWriter, Write},
     iter,
@@ -18,6 +18,7 @@ use crate::util::{
 };
 
 use super::{
+    decor::{Decor, DecorParser},
     iter_attributes,
     module::{Module, ModuleKind},
     ParseContext,
@@ -25,6 +26,10 @@ use super::{
 
 pub struct Relocations {
     relocations: BTreeMap<u32, Relocation>,
+    /// Addresses of the relocations in the order they were loaded from relocs.txt, with their comments and blank lines
+    loaded: Vec<(u32, Decor)>,
+    /// Comments and blank lines after the last relocation in relocs.txt
+    footer: Vec<String>,
 }
 
 #[derive(Debug, Snafu)]
@@ -53,7 +58,7 @@ pub enum RelocationsError {
 
 impl Relocations {
     pub fn new() -> Self {
-        Self { relocations: BTreeMap::new() }
+        Self { relocations: BTreeMap::new(), loaded: vec![], footer: vec![] }
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, RelocationsParseError> {
@@ -64,31 +69,55 @@ impl Relocations {
         let reader = BufReader::new(file);
 
         let mut relocations = BTreeMap::new();
+        let mut loaded = vec![];
+        let mut decor_parser = DecorParser::default();
         for line in reader.lines() {
             context.row += 1;
 
             let line = line?;
-            let comment_start = line.find("//").unwrap_or(line.len());
-            let line = &line[..comment_start];
+            let Some((line, decor)) = decor_parser.parse_line(&line) else { continue };
 
             let Some(relocation) = Relocation::parse(line, &context)? else {
                 continue;
             };
-            relocations.insert(relocation.from, relocation);
+            let from = relocation.from;
+            if relocations.insert(from, relocation).is_none() {
+                loaded.push((from, decor));
+            }
         }
 
-        Ok(Self { relocations })
+        Ok(Self { relocations, loaded, footer: decor_parser.finish() })
     }
 
+    /// Writes the relocations to a file. Relocations loaded from relocs.txt keep their order, comments and blank lines, and
+    /// new relocations are inserted by address.
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), RelocationsWriteError> {
         let path = path.as_ref();
 
         let file = create_file(path)?;
         let mut writer = BufWriter::new(file);
 
-        for relocation in self.relocations.values() {
+        let loaded = self.loaded.iter().map(|(from, _)| *from).collect::<HashSet<_>>();
+        let mut new_relocations = self.relocations.values().filter(|r| !loaded.contains(&r.from)).peekable();
+
+        for (from, decor) in &self.loaded {
+            while let Some(relocation) = new_relocations.next_if(|r| r.from < *from) {
+                writeln!(writer, "{relocation}")?;
+            }
+
+            for line in &decor.leading {
+                writeln!(writer, "{line}")?;
+            }
+            if let Some(relocation) = self.relocations.get(from) {
+                writeln!(writer, "{relocation}{}", decor.trailing())?;
+            }
+        }
+        for relocation in new_relocations {
             writeln!(writer, "{relocation}")?;
         }
+        for line in &self.footer {
+            writeln!(writer, "{line}")?;
+        }
         Ok(())
     }
 
@@ -250,6 +250,10 @@ impl Section {
         self.start_address..self.end_address
     }
 
+    pub fn display_inherit(&self) -> DisplayInheritSection {
+        DisplayInheritSection(self)
+    }
+
     pub fn alignment(&self) -> u32 {
         self.alignment
     }
@@ -281,6 +285,15 @@ impl Display for Section {
     }
 }
 
+/// Displays a section of a file in delinks.txt, which inherits its kind and alignment from the module's section.
+pub struct DisplayInheritSection<'a>(&'a Section);
+
+impl Display for DisplayInheritSection<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:11} start:{:#010x} end:{:#010x}", self.0.name, self.0.start_address, self.0.end_address)
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum SectionKind {
     Code,
@@ -1,6 +1,6 @@
 use std::{
     backtrace::Backtrace,
-    collections::{btree_map, hash_map, BTreeMap, HashMap},
+    collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet},
     fmt::Display,
     io::{self, BufRead, BufReader, BufWriter, Write},
     num::ParseIntError,
@@ -19,7 +19,14 @@ use crate::{
     },
 };
 
-use super::{config::Config, iter_attributes, module::ModuleKind, ParseContext};
+use super::{
+    config::Config,
+    decor::{Decor, DecorParser},
+    iter_attributes,
+    module::ModuleKind,
+    section::Sections,
+    ParseContext,
+};
 
 pub struct SymbolMaps {
     symbol_maps: Vec<SymbolMap>,
@@ -48,6 +55,16 @@ impl SymbolMaps {
         self.symbol_maps.get(module.index())
     }
 
+    /// Finds an absolute symbol at the given address, which may be defined in any module's symbol map.
+    pub fn absolute_symbol(&self, address: u32) -> Option<&Symbol> {
+        self.symbol_maps
+            .iter()
+            .filter_map(|symbol_map| symbol_map.for_address(address))
+            .flatten()
+            .map(|(_, symbol)| symbol)
+            .find(|symbol| symbol.kind == SymbolKind::Absolute)
+    }
+
     pub fn get_mut(&mut self, module: ModuleKind) -> &mut SymbolMap {
         let index = module.index();
         if index >= self.symbol_maps.len() {
@@ -94,13 +111,17 @@ impl SymbolMaps {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SymbolIndex(usize);
 
 pub struct SymbolMap {
     symbols: Vec<Symbol>,
     symbols_by_address: BTreeMap<u32, Vec<SymbolIndex>>,
     symbols_by_name: HashMap<String, Vec<SymbolIndex>>,
+    /// Symbols in the order they were loaded from symbols.txt, with their comments and blank lines
+    loaded: Vec<(SymbolIndex, Decor)>,
+    /// Comments and blank lines after the last symbol in symbols.txt
+    footer: Vec<String>,
 }
 
 #[derive(Debug, Snafu)]
@@ -131,6 +152,8 @@ pub enum SymbolMapError {
     NoSymbolToRename { address: u32, new_name: String, backtrace: Backtrace },
     #[snafu(display("there must be exactly one symbol at {address:#010x} to rename to '{new_name}':\n{backtrace}"))]
     RenameMultiple { address: u32, new_name: String, backtrace: Backtrace },
+    #[snafu(display("no data symbol at {address:#010x} to change to '{data}':\n{backtrace}"))]
+    NoDataToRetype { address: u32, data: String, backtrace: Backtrace },
 }
 
 impl SymbolMap {
@@ -147,7 +170,7 @@ impl SymbolMap {
             symbols_by_name.entry(symbol.name.clone()).or_default().push(SymbolIndex(index));
         }
 
-        Self { symbols, symbols_by_address, symbols_by_name }
+        Self { symbols, symbols_by_address, symbols_by_name, loaded: vec![], footer: vec![] }
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SymbolMapParseError> {
@@ -163,32 +186,60 @@ impl SymbolMap {
         let file = open_file(path)?;
         let reader = BufReader::new(file);
 
+        let mut decor_parser = DecorParser::default();
         for line in reader.lines() {
             context.row += 1;
 
             let line = line?;
-            let comment_start = line.find("//").unwrap_or(line.len());
-            let line = &line[..comment_start];
+            let Some((line, decor)) = decor_parser.parse_line(&line) else { continue };
 
             let Some(symbol) = Symbol::parse(line, &context)? else { continue };
-            self.add(symbol);
+            let (index, _) = self.add(symbol);
+            self.loaded.push((index, decor));
         }
+        self.footer = decor_parser.finish();
         Ok(())
     }
 
+    /// Writes the symbols to a file. Symbols loaded from symbols.txt keep their order, comments and blank lines, and new
+    /// symbols are inserted by address.
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SymbolMapWriteError> {
         let path = path.as_ref();
 
         let file = create_file(path)?;
         let mut writer = BufWriter::new(file);
 
-        for indices in self.symbols_by_address.values() {
-            for &index in indices {
-                let symbol = &self.symbols[index.0];
-                if symbol.should_write() {
-                    writeln!(writer, "{symbol}")?;
+        let loaded = self.loaded.iter().map(|(index, _)| *index).collect::<HashSet<_>>();
+        let mut new_symbols = self
+            .symbols_by_address
+            .values()
+            .flatten()
+            .filter(|index| !loaded.contains(index))
+            .map(|index| &self.symbols[index.0])
+            .peekable();
+
+        for (index, decor) in &self.loaded {
+            let symbol = &self.symbols[index.0];
+            while let Some(new_symbol) = new_symbols.next_if(|new_symbol| new_symbol.addr < symbol.addr) {
+                if new_symbol.should_write() {
+                    writeln!(writer, "{new_symbol}")?;
                 }
             }
+
+            for line in &decor.leading {
+                writeln!(writer, "{line}")?;
+            }
+            if symbol.should_write() {
+                writeln!(writer, "{symbol}{}", decor.trailing())?;
+            }
+        }
+        for new_symbol in new_symbols {
+            if new_symbol.should_write() {
+                writeln!(writer, "{new_symbol}")?;
+            }
+        }
+        for line in &self.footer {
+            writeln!(writer, "{line}")?;
         }
 
         Ok(())
@@ -213,11 +264,47 @@ impl SymbolMap {
         Some(self.symbols_by_name.get(name)?.iter().map(|&i| (i, &self.symbols[i.0])))
     }
 
+    /// Returns the symbol with the given name. If there are multiple, the only non-local symbol among them is returned.
     pub fn by_name(&self, name: &str) -> Result<Option<(SymbolIndex, &Symbol)>, SymbolMapError> {
-        let Some(mut symbols) = self.for_name(name) else {
+        let Some(symbols) = self.for_name(name) else {
+            return Ok(None);
+        };
+        let symbols = symbols.collect::<Vec<_>>();
+        if let [symbol] = symbols.as_slice() {
+            return Ok(Some(*symbol));
+        }
+        let globals = symbols.iter().copied().filter(|(_, symbol)| !symbol.local);
+        match Self::unique_by_name(name, globals)? {
+            Some(symbol) => Ok(Some(symbol)),
+            None => DuplicateNameSnafu { name, new_address: symbols[1].1.addr, old_address: symbols[0].1.addr }.fail(),
+        }
+    }
+
+    /// Returns the symbol with the given name as seen from a file, i.e. a local symbol within the file's sections if there
+    /// is one, otherwise a non-local symbol.
+    pub fn by_name_from_file(
+        &self,
+        name: &str,
+        file_sections: &Sections,
+    ) -> Result<Option<(SymbolIndex, &Symbol)>, SymbolMapError> {
+        let Some(symbols) = self.for_name(name) else {
+            return Ok(None);
+        };
+        let (locals, globals): (Vec<_>, Vec<_>) = symbols.partition(|(_, symbol)| symbol.local);
+        let locals = locals.into_iter().filter(|(_, symbol)| file_sections.get_by_contained_address(symbol.addr).is_some());
+        match Self::unique_by_name(name, locals)? {
+            Some(symbol) => Ok(Some(symbol)),
+            None => Self::unique_by_name(name, globals.into_iter()),
+        }
+    }
+
+    fn unique_by_name<'a>(
+        name: &str,
+        mut symbols: impl Iterator<Item = (SymbolIndex, &'a Symbol)>,
+    ) -> Result<Option<(SymbolIndex, &'a Symbol)>, SymbolMapError> {
+        let Some((index, symbol)) = symbols.next() else {
             return Ok(None);
         };
-        let (index, symbol) = symbols.next().unwrap();
         if let Some((_, other)) = symbols.next() {
             return DuplicateNameSnafu { name, new_address: symbol.addr, old_address: other.addr }.fail();
         }
@@ -269,6 +356,31 @@ impl SymbolMap {
             .next()
     }
 
+    /// Returns the function, data or .bss symbol which contains the given address, such as an array which is referenced
+    /// by one of its elements.
+    pub fn get_containing(&self, addr: u32) -> Option<&Symbol> {
+        let is_container =
+            |symbol: &&Symbol| matches!(symbol.kind, SymbolKind::Function(_) | SymbolKind::Data(_) | SymbolKind::Bss(_));
+        let symbol = self
+            .symbols_by_address
+            .range(..=addr)
+            .rev()
+            .flat_map(|(_, indices)| indices.iter().map(|index| &self.symbols[index.0]))
+            .find(is_container)?;
+        let next_address = self
+            .symbols_by_address
+            .range(symbol.addr + 1..)
+            .flat_map(|(_, indices)| indices.iter().map(|index| &self.symbols[index.0]))
+            .find(is_container)
+            .map_or(u32::MAX, |next| next.addr);
+        (addr < symbol.addr + symbol.size(next_address)).then_some(symbol)
+    }
+
+    /// Iterates over all symbols along with their indices, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (SymbolIndex, &Symbol)> {
+        self.symbols.iter().enumerate().map(|(index, symbol)| (SymbolIndex(index), symbol))
+    }
+
     pub fn functions(&self) -> impl Iterator<Item = (SymFunction, &'_ Symbol)> {
         FunctionSymbolIterator {
             symbols_by_address: self.symbols_by_address.values(),
@@ -291,6 +403,11 @@ impl SymbolMap {
         })
     }
 
+    /// Returns the symbols which name addresses outside of the module.
+    pub fn absolute_symbols(&self) -> impl Iterator<Item = &'_ Symbol> {
+        self.symbols.iter().filter(|symbol| symbol.kind == SymbolKind::Absolute)
+    }
+
     pub fn bss_symbols(&self) -> impl Iterator<Item = (SymBss, &'_ Symbol)> {
         self.symbols.iter().filter_map(
             |symbol| {
@@ -415,8 +532,41 @@ impl SymbolMap {
         let symbol_indices =
             self.symbols_by_address.get(&address).ok_or_else(|| NoSymbolToRenameSnafu { address, new_name }.build())?;
         ensure!(symbol_indices.len() == 1, RenameMultipleSnafu { address, new_name });
+        self.rename(symbol_indices[0], new_name)
+    }
+
+    /// Renames a symbol. Local symbols may share their name with other symbols, but two non-local symbols may not.
+    pub fn rename(&mut self, symbol_index: SymbolIndex, new_name: &str) -> Result<(), SymbolMapError> {
+        self.rename_many(&[(symbol_index, new_name)])
+    }
+
+    /// Renames several symbols at once, so that they can take each other's names. Nothing is renamed if two non-local
+    /// symbols would end up with the same name.
+    pub fn rename_many(&mut self, renames: &[(SymbolIndex, &str)]) -> Result<(), SymbolMapError> {
+        let renamed = renames.iter().map(|&(index, _)| index).collect::<HashSet<_>>();
+        let mut new_globals = HashMap::<&str, SymbolIndex>::new();
+        for &(symbol_index, new_name) in renames {
+            let symbol = &self.symbols[symbol_index.0];
+            if symbol.local {
+                continue;
+            }
+            let existing = self
+                .for_name(new_name)
+                .and_then(|mut symbols| symbols.find(|(index, other)| !renamed.contains(index) && !other.local))
+                .map(|(index, _)| index);
+            if let Some(other) = existing.or_else(|| new_globals.insert(new_name, symbol_index)) {
+                let old_address = self.symbols[other.0].addr;
+                return DuplicateNameSnafu { name: new_name, new_address: symbol.addr, old_address }.fail();
+            }
+        }
 
-        let symbol_index = symbol_indices[0];
+        for &(symbol_index, new_name) in renames {
+            self.rename_unchecked(symbol_index, new_name);
+        }
+        Ok(())
+    }
+
+    fn rename_unchecked(&mut self, symbol_index: SymbolIndex, new_name: &str) {
         let name = &self.symbols[symbol_index.0].name;
 
         match self.symbols_by_name.entry(name.clone()) {
@@ -445,7 +595,14 @@ impl SymbolMap {
         }
 
         self.symbols[symbol_index.0].name = new_name.to_string();
+    }
 
+    /// Changes the type of the data symbol at the given address.
+    pub fn retype_data_by_address(&mut self, address: u32, data: SymData) -> Result<(), SymbolMapError> {
+        let Some((index, SymbolKind::Data(_))) = self.by_address(address)?.map(|(index, symbol)| (index, symbol.kind)) else {
+            return NoDataToRetypeSnafu { address, data: data.to_string() }.fail();
+        };
+        self.symbols[index.0].kind = SymbolKind::Data(data);
         Ok(())
     }
 }
@@ -503,6 +660,9 @@ pub struct Symbol {
     pub addr: u32,
     /// If true, this symbol is involved in an ambiguous external reference to one of many overlays
     pub ambiguous: bool,
+    /// If true, this symbol is only visible within the delink file that contains it, like a `static` function in C. Local
+    /// symbols in different files may have the same name.
+    pub local: bool,
 }
 
 #[derive(Debug, Snafu)]
@@ -511,7 +671,9 @@ pub enum SymbolParseError {
     SymbolKindParse { source: SymbolKindParseError },
     #[snafu(display("{context}: failed to parse address '{value}': {error}\n{backtrace}"))]
     ParseAddress { context: ParseContext, value: String, error: ParseIntError, backtrace: Backtrace },
-    #[snafu(display("{context}: expected symbol attribute 'kind' or 'addr' but got '{key}':\n{backtrace}"))]
+    #[snafu(display(
+        "{context}: expected symbol attribute 'kind', 'addr', 'ambiguous' or 'local' but got '{key}':\n{backtrace}"
+    ))]
     UnknownAttribute { context: ParseContext, key: String, backtrace: Backtrace },
     #[snafu(display("{context}: missing '{attribute}' attribute:\n{backtrace}"))]
     MissingAttribute { context: ParseContext, attribute: String, backtrace: Backtrace },
@@ -525,11 +687,13 @@ impl Symbol {
         let mut kind = None;
         let mut addr = None;
         let mut ambiguous = false;
+        let mut local = false;
         for (key, value) in iter_attributes(words) {
             match key {
                 "kind" => kind = Some(SymbolKind::parse(value, context)?),
                 "addr" => addr = Some(parse_u32(value).map_err(|error| ParseAddressSnafu { context, value, error }.build())?),
                 "ambiguous" => ambiguous = true,
+                "local" => local = true,
                 _ => return UnknownAttributeSnafu { context, key }.fail(),
             }
         }
@@ -538,7 +702,7 @@ impl Symbol {
         let kind = kind.ok_or_else(|| MissingAttributeSnafu { context, attribute: "kind" }.build())?;
         let addr = addr.ok_or_else(|| MissingAttributeSnafu { context, attribute: "addr" }.build())?;
 
-        Ok(Some(Symbol { name, kind, addr, ambiguous }))
+        Ok(Some(Symbol { name, kind, addr, ambiguous, local }))
     }
 
     fn should_write(&self) -> bool {
@@ -552,18 +716,26 @@ impl Symbol {
                 mode: InstructionMode::from_thumb(function.is_thumb()),
                 size: function.size(),
                 unknown: false,
+                status: None,
             }),
             addr: function.first_instruction_address() & !1,
             ambiguous: false,
+            local: false,
         }
     }
 
     pub fn new_unknown_function(name: String, addr: u32, thumb: bool) -> Self {
         Self {
             name,
-            kind: SymbolKind::Function(SymFunction { mode: InstructionMode::from_thumb(thumb), size: 0, unknown: true }),
+            kind: SymbolKind::Function(SymFunction {
+                mode: InstructionMode::from_thumb(thumb),
+                size: 0,
+                unknown: true,
+                status: None,
+            }),
             addr,
             ambiguous: false,
+            local: false,
         }
     }
 
@@ -573,6 +745,7 @@ impl Symbol {
             kind: SymbolKind::Label(SymLabel { external: false, mode: InstructionMode::from_thumb(thumb) }),
             addr,
             ambiguous: false,
+            local: false,
         }
     }
 
@@ -582,23 +755,24 @@ impl Symbol {
             kind: SymbolKind::Label(SymLabel { external: true, mode: InstructionMode::from_thumb(thumb) }),
             addr,
             ambiguous: false,
+            local: false,
         }
     }
 
     pub fn new_pool_constant(name: String, addr: u32) -> Self {
-        Self { name, kind: SymbolKind::PoolConstant, addr, ambiguous: false }
+        Self { name, kind: SymbolKind::PoolConstant, addr, ambiguous: false, local: false }
     }
 
     pub fn new_jump_table(name: String, addr: u32, size: u32, code: bool) -> Self {
-        Self { name, kind: SymbolKind::JumpTable(SymJumpTable { size, code }), addr, ambiguous: false }
+        Self { name, kind: SymbolKind::JumpTable(SymJumpTable { size, code }), addr, ambiguous: false, local: false }
     }
 
     pub fn new_data(name: String, addr: u32, data: SymData, ambiguous: bool) -> Symbol {
-        Self { name, kind: SymbolKind::Data(data), addr, ambiguous }
+        Self { name, kind: SymbolKind::Data(data), addr, ambiguous, local: false }
     }
 
     pub fn new_bss(name: String, addr: u32, data: SymBss, ambiguous: bool) -> Symbol {
-        Self { name, kind: SymbolKind::Bss(data), addr, ambiguous }
+        Self { name, kind: SymbolKind::Bss(data), addr, ambiguous, local: false }
     }
 
     pub fn size(&self, max_address: u32) -> u32 {
@@ -612,6 +786,9 @@ impl Display for Symbol {
         if self.ambiguous {
             write!(f, " ambiguous")?;
         }
+        if self.local {
+            write!(f, " local")?;
+        }
         Ok(())
     }
 }
@@ -624,6 +801,8 @@ pub enum SymbolKind {
     JumpTable(SymJumpTable),
     Data(SymData),
     Bss(SymBss),
+    /// Named address outside of the module, such as VRAM or shared WRAM, which is emitted as an absolute symbol.
+    Absolute,
 }
 
 #[derive(Debug, Snafu)]
@@ -636,7 +815,9 @@ pub enum SymbolKindParseError {
     SymBssParse { source: SymBssParseError },
     #[snafu(transparent)]
     SymLabelParse { source: SymLabelParseError },
-    #[snafu(display("{context}: unknown symbol kind '{kind}', must be one of: function, data, bss, label:\n{backtrace}"))]
+    #[snafu(display(
+        "{context}: unknown symbol kind '{kind}', must be one of: function, data, bss, label, abs:\n{backtrace}"
+    ))]
     UnknownKind { context: ParseContext, kind: String, backtrace: Backtrace },
 }
 
@@ -650,6 +831,7 @@ impl SymbolKind {
             "data" => Ok(Self::Data(SymData::parse(options, context)?)),
             "bss" => Ok(Self::Bss(SymBss::parse(options, context)?)),
             "label" => Ok(Self::Label(SymLabel::parse(options, context)?)),
+            "abs" => Ok(Self::Absolute),
             _ => UnknownKindSnafu { context, kind }.fail(),
         }
     }
@@ -662,6 +844,7 @@ impl SymbolKind {
             SymbolKind::JumpTable(_) => false,
             SymbolKind::Data(_) => true,
             SymbolKind::Bss(_) => true,
+            SymbolKind::Absolute => true,
         }
     }
 
@@ -673,6 +856,7 @@ impl SymbolKind {
             SymbolKind::JumpTable(_) => 0,
             SymbolKind::Data(data) => data.size().unwrap_or(max_size),
             SymbolKind::Bss(bss) => bss.size.unwrap_or(max_size),
+            SymbolKind::Absolute => 0,
         }
     }
 }
@@ -684,6 +868,7 @@ impl Display for SymbolKind {
             SymbolKind::Data(data) => write!(f, "data({data})")?,
             SymbolKind::Bss(bss) => write!(f, "bss{bss}")?,
             SymbolKind::Label(label) => write!(f, "label({label})")?,
+            SymbolKind::Absolute => write!(f, "abs")?,
             SymbolKind::PoolConstant => {}
             SymbolKind::JumpTable(_) => {}
         }
@@ -698,6 +883,8 @@ pub struct SymFunction {
     /// Is `true` for functions that were not found during function analysis, but are being called from somewhere. This can
     /// happen if the function is encrypted.
     pub unknown: bool,
+    /// Decompilation status of the function. If `None`, the status follows whether its file is complete in delinks.txt.
+    pub status: Option<FunctionStatus>,
 }
 
 #[derive(Debug, Snafu)]
@@ -705,7 +892,7 @@ pub enum SymFunctionParseError {
     #[snafu(display("{context}: failed to parse size '{value}': {error}\n{backtrace}"))]
     ParseFunctionSize { context: ParseContext, value: String, error: ParseIntError, backtrace: Backtrace },
     #[snafu(display(
-        "{context}: unknown function attribute '{key}', must be one of: size, unknown, arm, thumb:\n{backtrace}"
+        "{context}: unknown function attribute '{key}', must be one of: size, unknown, arm, thumb, matching, nonmatching, asm:\n{backtrace}"
     ))]
     UnknownFunctionAttribute { context: ParseContext, key: String, backtrace: Backtrace },
     #[snafu(transparent)]
@@ -721,6 +908,7 @@ impl SymFunction {
         let mut size = None;
         let mut mode = None;
         let mut unknown = false;
+        let mut status = None;
         for option in options.split(',') {
             if let Some((key, value)) = option.split_once('=') {
                 match key {
@@ -733,6 +921,9 @@ impl SymFunction {
             } else {
                 match option {
                     "unknown" => unknown = true,
+                    "matching" => status = Some(FunctionStatus::Matching),
+                    "nonmatching" => status = Some(FunctionStatus::Nonmatching),
+                    "asm" => status = Some(FunctionStatus::Asm),
                     _ => mode = Some(InstructionMode::parse(option, context)?),
                 }
             }
@@ -742,9 +933,15 @@ impl SymFunction {
             mode: mode.ok_or_else(|| MissingInstructionModeSnafu { context }.build())?,
             size: size.ok_or_else(|| MissingFunctionAttributeSnafu { context, attribute: "size" }.build())?,
             unknown,
+            status,
         })
     }
 
+    /// Returns the decompilation status of the function, given whether its file is complete.
+    pub fn status(&self, file_complete: bool) -> FunctionStatus {
+        self.status.unwrap_or(if file_complete { FunctionStatus::Matching } else { FunctionStatus::Asm })
+    }
+
     fn contains(&self, sym: &Symbol, addr: u32) -> bool {
         if !self.unknown {
             let start = sym.addr;
@@ -763,10 +960,33 @@ impl Display for SymFunction {
         if self.unknown {
             write!(f, ",unknown")?;
         }
+        if let Some(status) = self.status {
+            write!(f, ",{status}")?;
+        }
         Ok(())
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FunctionStatus {
+    /// Decompiled and matches the original code
+    Matching,
+    /// Decompiled, but does not match the original code yet
+    Nonmatching,
+    /// Not decompiled, still assembled from `dsd dis` output
+    Asm,
+}
+
+impl Display for FunctionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Matching => write!(f, "matching"),
+            Self::Nonmatching => write!(f, "nonmatching"),
+            Self::Asm => write!(f, "asm"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct SymLabel {
     /// If true, the label is not used by the function itself, but accessed externally. Such labels are only discovered
@@ -852,11 +1072,31 @@ pub enum SymData {
     Byte { count: Option<u32> },
     Short { count: Option<u32> },
     Word { count: Option<u32> },
+    Fx { kind: FxKind, count: Option<u32> },
+}
+
+/// Fixed-point types of the NitroSDK, and the vectors and matrices made of them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FxKind {
+    /// Signed 4.12 fixed-point number
+    Fx16,
+    /// Signed 20.12 fixed-point number
+    Fx32,
+    /// Signed 32.32 fixed-point number, used for precise intermediate results
+    Fx64c,
+    VecFx16,
+    VecFx32,
+    MtxFx22,
+    MtxFx33,
+    MtxFx43,
+    MtxFx44,
 }
 
 #[derive(Debug, Snafu)]
 pub enum SymDataParseError {
-    #[snafu(display("{context}: expected data kind 'any', 'byte', 'short' or 'word' but got nothing:\n{backtrace}"))]
+    #[snafu(display(
+        "{context}: expected data kind 'any', 'byte', 'short', 'word' or a fixed-point type but got nothing:\n{backtrace}"
+    ))]
     EmptyData { context: ParseContext, backtrace: Backtrace },
     #[snafu(display("{context}: failed to parse count '{value}': {error}\n{backtrace}"))]
     ParseCount { context: ParseContext, value: String, error: ParseIntError, backtrace: Backtrace },
@@ -864,7 +1104,9 @@ pub enum SymDataParseError {
     CharacterAfterArray { context: ParseContext, backtrace: Backtrace },
     #[snafu(display("{context}: data type 'any' cannot be an array:\n{backtrace}"))]
     ArrayOfAny { context: ParseContext, backtrace: Backtrace },
-    #[snafu(display("{context}: expected data kind 'any', 'byte', 'short' or 'word' but got '{kind}':\n{backtrace}"))]
+    #[snafu(display(
+        "{context}: expected data kind 'any', 'byte', 'short', 'word' or a fixed-point type but got '{kind}':\n{backtrace}"
+    ))]
     UnknownDataKind { context: ParseContext, kind: String, backtrace: Backtrace },
 }
 
@@ -903,7 +1145,10 @@ impl SymData {
             "short" => Ok(Self::Short { count }),
             "byte" => Ok(Self::Byte { count }),
             "word" => Ok(Self::Word { count }),
-            kind => UnknownDataKindSnafu { context, kind }.fail(),
+            kind => match FxKind::parse(kind) {
+                Some(kind) => Ok(Self::Fx { kind, count }),
+                None => UnknownDataKindSnafu { context, kind }.fail(),
+            },
         }
     }
 
@@ -913,6 +1158,7 @@ impl SymData {
             Self::Byte { count } => count,
             Self::Short { count } => count,
             Self::Word { count } => count,
+            Self::Fx { count, .. } => count,
         }
     }
 
@@ -922,6 +1168,15 @@ impl SymData {
             Self::Byte { .. } => 1,
             Self::Short { .. } => 2,
             Self::Word { .. } => 4,
+            Self::Fx { kind, .. } => kind.size(),
+        }
+    }
+
+    /// Size of each scalar value in an element, e.g. 4 for the `fx32` components of a `vecfx32`.
+    pub fn value_size(self) -> u32 {
+        match self {
+            Self::Fx { kind, .. } => kind.scalar().size(),
+            _ => self.element_size(),
         }
     }
 
@@ -937,14 +1192,119 @@ impl Display for SymData {
             Self::Byte { count: Some(1) } => write!(f, "byte"),
             Self::Short { count: Some(1) } => write!(f, "short"),
             Self::Word { count: Some(1) } => write!(f, "word"),
+            Self::Fx { kind, count: Some(1) } => write!(f, "{kind}"),
             Self::Byte { count: Some(count) } => write!(f, "byte[{count}]"),
             Self::Short { count: Some(count) } => write!(f, "short[{count}]"),
             Self::Word { count: Some(count) } => write!(f, "word[{count}]"),
+            Self::Fx { kind, count: Some(count) } => write!(f, "{kind}[{count}]"),
             Self::Byte { count: None } => write!(f, "byte[]"),
             Self::Short { count: None } => write!(f, "short[]"),
             Self::Word { count: None } => write!(f, "word[]"),
+            Self::Fx { kind, count: None } => write!(f, "{kind}[]"),
+        }
+    }
+}
+
+impl FxKind {
+    pub const ALL: [Self; 9] = [
+        Self::Fx16,
+        Self::Fx32,
+        Self::Fx64c,
+        Self::VecFx16,
+        Self::VecFx32,
+        Self::MtxFx22,
+        Self::MtxFx33,
+        Self::MtxFx43,
+        Self::MtxFx44,
+    ];
+
+    fn parse(kind: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|fx_kind| fx_kind.name() == kind)
+    }
+
+    /// Name in `symbols.txt`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fx16 => "fx16",
+            Self::Fx32 => "fx32",
+            Self::Fx64c => "fx64c",
+            Self::VecFx16 => "vecfx16",
+            Self::VecFx32 => "vecfx32",
+            Self::MtxFx22 => "mtxfx22",
+            Self::MtxFx33 => "mtxfx33",
+            Self::MtxFx43 => "mtxfx43",
+            Self::MtxFx44 => "mtxfx44",
+        }
+    }
+
+    /// Name of the type in the NitroSDK headers
+    pub fn c_type(self) -> &'static str {
+        match self {
+            Self::Fx16 => "fx16",
+            Self::Fx32 => "fx32",
+            Self::Fx64c => "fx64c",
+            Self::VecFx16 => "VecFx16",
+            Self::VecFx32 => "VecFx32",
+            Self::MtxFx22 => "MtxFx22",
+            Self::MtxFx33 => "MtxFx33",
+            Self::MtxFx43 => "MtxFx43",
+            Self::MtxFx44 => "MtxFx44",
+        }
+    }
+
+    /// Type of the components, e.g. `Fx32` for `VecFx32`. Scalar types are their own components.
+    pub fn scalar(self) -> Self {
+        match self {
+            Self::Fx16 | Self::VecFx16 => Self::Fx16,
+            Self::Fx64c => Self::Fx64c,
+            Self::Fx32 | Self::VecFx32 | Self::MtxFx22 | Self::MtxFx33 | Self::MtxFx43 | Self::MtxFx44 => Self::Fx32,
+        }
+    }
+
+    /// Number of scalar components
+    pub fn components(self) -> u32 {
+        match self {
+            Self::Fx16 | Self::Fx32 | Self::Fx64c => 1,
+            Self::VecFx16 | Self::VecFx32 => 3,
+            Self::MtxFx22 => 4,
+            Self::MtxFx33 => 9,
+            Self::MtxFx43 => 12,
+            Self::MtxFx44 => 16,
         }
     }
+
+    pub fn size(self) -> u32 {
+        let scalar_size = match self.scalar() {
+            Self::Fx16 => 2,
+            Self::Fx64c => 8,
+            _ => 4,
+        };
+        scalar_size * self.components()
+    }
+
+    /// Number of fractional bits of the components
+    pub fn fraction_bits(self) -> u32 {
+        match self.scalar() {
+            Self::Fx64c => 32,
+            _ => 12,
+        }
+    }
+
+    /// Converts a little-endian scalar of this type to a floating-point number.
+    pub fn to_f64(self, bytes: &[u8]) -> f64 {
+        let value = match self.scalar() {
+            Self::Fx16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Self::Fx64c => i64::from_le_bytes(bytes[..8].try_into().unwrap()) as f64,
+            _ => i32::from_le_bytes(bytes[..4].try_into().unwrap()) as f64,
+        };
+        value / (1u64 << self.fraction_bits()) as f64
+    }
+}
+
+impl Display for FxKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]